@@ -7,13 +7,16 @@ use std::io::{
   BufReader,
   Read
 };
+use std::ops::Range;
 use std::path::{
   Path,
   PathBuf
 };
 use std::process::Command as ProcessCommand;
 
+use anyhow::Context;
 use clap::{
+  Args,
   Parser as ClapParser,
   Subcommand,
   ValueEnum
@@ -27,9 +30,25 @@ use crate::dictionary::{
   Dictionary,
   DictionaryAdapter,
   DictionaryCode,
-  DictionaryConfig
+  DictionaryConfig,
+  DictionaryLearner,
+  DictionaryMatch,
+  DictionaryWeights,
+  KeyScheme,
+  LayeredDictionary,
+  TermNormalizer
+};
+use crate::document::{
+  CleanText,
+  Document,
+  InputFormat
+};
+use crate::finder::{
+  CitationKey,
+  Finder,
+  InTextCitation,
+  ReferenceSection
 };
-use crate::finder::Finder;
 use crate::format::{
   Format,
   ParseFormat
@@ -39,9 +58,18 @@ use crate::model::{
   ParserModel
 };
 use crate::normalizer::NormalizationConfig;
+use crate::normalizer::title::{
+  ProtectedWords,
+  TitleCase
+};
 use crate::parser::{
+  FieldValue,
+  InputNormalizer,
+  LineJoiner,
   Parser,
   Reference,
+  ReferenceSpans,
+  UnicodeForm,
   sequence_signature,
   tagged_sequence_signature
 };
@@ -52,19 +80,78 @@ use crate::sequence_model::SequenceModel;
 #[command(about = "Rust port of AnyStyle", long_about = None)]
 pub struct Cli {
   #[arg(long, global = true)]
-  parser_model:      Option<PathBuf>,
+  parser_model:         Option<PathBuf>,
+  #[arg(long, global = true)]
+  finder_model:         Option<PathBuf>,
+  #[arg(long, global = true)]
+  parser_sequences:     Option<PathBuf>,
+  #[arg(long, global = true)]
+  finder_sequences:     Option<PathBuf>,
   #[arg(long, global = true)]
-  finder_model:      Option<PathBuf>,
+  report_dir:           Option<PathBuf>,
   #[arg(long, global = true)]
-  parser_sequences:  Option<PathBuf>,
+  normalization_dir:    Option<PathBuf>,
+  /// Recase titles in parse output
+  #[arg(
+    long,
+    global = true,
+    value_enum,
+    default_value_t = TitleCase::AsIs
+  )]
+  title_case:           TitleCase,
+  /// File of words kept verbatim when
+  /// recasing titles, one per line
+  #[arg(long, global = true)]
+  protected_words:      Option<PathBuf>,
+  /// Add city/region/country objects
+  /// as `structured-location` in
+  /// JSON output
+  #[arg(long, global = true)]
+  structured_locations: bool,
+  /// Collapse publisher variants such
+  /// as "OUP" into canonical names
   #[arg(long, global = true)]
-  finder_sequences:  Option<PathBuf>,
+  canonical_publishers: bool,
+  /// Report imprints under their
+  /// parent publisher
+  #[arg(long, global = true)]
+  imprint_parents:      bool,
+  /// Rewrite roman numeral volumes and
+  /// issues as arabic numbers
+  #[arg(long, global = true)]
+  arabic_volumes:       bool,
+  /// Unicode normalization applied to
+  /// input before tokenization
+  #[arg(
+    long,
+    global = true,
+    value_enum,
+    default_value_t = UnicodeForm::Nfc
+  )]
+  unicode_form:         UnicodeForm,
+  /// Skip ligature, dash, quote and
+  /// control-character cleanup of
+  /// input
   #[arg(long, global = true)]
-  report_dir:        Option<PathBuf>,
+  raw_input:            bool,
+  /// Parser dictionary layer as
+  /// ADAPTER or ADAPTER:PATH (a store
+  /// path, or the URL for redis); the
+  /// first is the base, later ones
+  /// overlay it
+  #[arg(
+    long = "dictionary",
+    global = true,
+    value_name = "ADAPTER[:PATH]"
+  )]
+  dictionaries:         Vec<String>,
+  /// Terms whose codes the parser
+  /// ignores, optionally under `#!
+  /// tag` headers naming the codes
   #[arg(long, global = true)]
-  normalization_dir: Option<PathBuf>,
+  dictionary_deny:      Vec<PathBuf>,
   #[command(subcommand)]
-  command:           Command
+  command:              Command
 }
 
 #[derive(Subcommand, Debug)]
@@ -80,21 +167,61 @@ enum Command {
       default_value_t = ParseFormat::Json,
       value_enum
     )]
-    output_format: ParseFormat
+    output_format: ParseFormat,
+    /// Re-join references wrapped over
+    /// several lines and mend words
+    /// hyphenated at line ends
+    #[arg(long)]
+    join_lines:    bool,
+    /// Add source spans of each
+    /// reference and field value to
+    /// JSON output
+    #[arg(long)]
+    with_spans:    bool
   },
 
   /// Find references inside a textual
   /// document
   Find {
     /// Path or inline text to scan
-    input:         String,
+    input:          String,
     #[arg(
       short,
       long,
       default_value_t = ParseFormat::Json,
       value_enum
     )]
-    output_format: ParseFormat
+    output_format:  ParseFormat,
+    /// Segment the whole document
+    /// instead of its reference
+    /// sections
+    #[arg(long)]
+    whole_document: bool,
+    /// Add page, line and byte offsets
+    /// of each reference and spans of
+    /// its field values to JSON output
+    #[arg(long)]
+    with_spans:     bool,
+    /// Read citations from numbered
+    /// footnotes anywhere in the
+    /// document, resolving "ibid.",
+    /// "op. cit." and short titles
+    #[arg(long)]
+    footnotes:      bool,
+    /// Find in-text citations outside
+    /// the reference sections and link
+    /// them to the found references
+    #[arg(
+      long,
+      conflicts_with = "footnotes"
+    )]
+    link_citations: bool,
+    /// Input format; by default taken
+    /// from the file extension,
+    /// telling PDF from text
+    /// otherwise
+    #[arg(long, value_enum)]
+    input_format:   Option<InputFormat>
   },
 
   /// Train models
@@ -153,21 +280,18 @@ enum Command {
   /// Query dictionary adapters
   Dictionary {
     /// Term to lookup
-    term:      String,
-    #[arg(
-      long,
-      value_enum,
-      default_value_t = DictionaryAdapterArg::Memory
-    )]
-    adapter:   DictionaryAdapterArg,
-    #[arg(long)]
-    gdbm_path: Option<PathBuf>,
+    term:   String,
+    /// List keys starting with the
+    /// term (fst adapter)
     #[arg(long)]
-    lmdb_path: Option<PathBuf>,
+    prefix: bool,
+    /// List keys within this many
+    /// edits of the term (fst
+    /// adapter)
     #[arg(long)]
-    redis_url: Option<String>,
-    #[arg(long)]
-    namespace: Option<String>
+    fuzzy:  Option<u32>,
+    #[command(flatten)]
+    store:  DictionaryStoreArgs
   },
 
   /// Import terms into dictionary
@@ -175,33 +299,21 @@ enum Command {
   #[command(name = "dictionary-import")]
   DictionaryImport {
     /// Dictionary file paths
-    inputs:    Vec<PathBuf>,
-    #[arg(
-      long,
-      value_enum,
-      default_value_t = DictionaryAdapterArg::Memory
-    )]
-    adapter:   DictionaryAdapterArg,
+    inputs: Vec<PathBuf>,
     #[arg(
       long,
       value_enum,
       default_value_t = DictionaryImportFormat::Plain
     )]
-    format:    DictionaryImportFormat,
+    format: DictionaryImportFormat,
     #[arg(
       long,
       value_enum,
       default_value_t = DictionaryCodeArg::Place
     )]
-    code:      DictionaryCodeArg,
-    #[arg(long)]
-    gdbm_path: Option<PathBuf>,
-    #[arg(long)]
-    lmdb_path: Option<PathBuf>,
-    #[arg(long)]
-    redis_url: Option<String>,
-    #[arg(long)]
-    namespace: Option<String>
+    code:   DictionaryCodeArg,
+    #[command(flatten)]
+    store:  DictionaryStoreArgs
   },
 
   /// Sync AnyStyle dictionaries into
@@ -216,20 +328,128 @@ enum Command {
     source_dir: PathBuf,
     #[arg(long)]
     pattern:    Vec<String>,
+    /// Also compile the synced files
+    /// into an fst dictionary here
+    #[arg(long)]
+    compile:    Option<PathBuf>,
+    #[command(flatten)]
+    store:      DictionaryStoreArgs
+  },
+
+  /// Rewrite a dictionary store with
+  /// Unicode keys, re-importing
+  /// AnyStyle dictionaries
+  #[command(
+    name = "dictionary-migrate",
+    mut_arg("adapter", |arg| {
+      arg.default_value("lmdb")
+    })
+  )]
+  DictionaryMigrate {
+    /// AnyStyle dictionary directory
+    /// to re-import after the
+    /// rewrite
+    #[arg(long)]
+    source_dir:      Option<PathBuf>,
+    #[arg(long)]
+    pattern:         Vec<String>,
+    /// Fold diacritics in the new keys
+    #[arg(long)]
+    fold_diacritics: bool,
+    #[command(flatten)]
+    store: DictionaryStoreArgs
+  },
+
+  /// Compile AnyStyle dictionaries into
+  /// a read-only fst dictionary for the
+  /// fst adapter
+  #[command(
+    name = "dictionary-compile"
+  )]
+  DictionaryCompile {
+    #[arg(
+      long,
+      default_value = "tmp/anystyle/\
+                       data"
+    )]
+    source_dir:      PathBuf,
+    #[arg(long)]
+    pattern:         Vec<String>,
+    #[arg(
+      long,
+      default_value = "target/\
+                       dictionaries/fst/\
+                       places.fst"
+    )]
+    output:          PathBuf,
+    /// Fold diacritics in the keys
+    #[arg(long)]
+    fold_diacritics: bool
+  },
+
+  /// Learn name, place, publisher and
+  /// journal terms from tagged parser
+  /// datasets
+  #[command(name = "dictionary-learn")]
+  DictionaryLearn {
+    #[arg(
+      long,
+      default_value = DEFAULT_PARSER_PATTERN
+    )]
+    dataset:   String,
+    /// Keep terms seen at least this
+    /// often for a code
+    #[arg(long, default_value_t = 2)]
+    min_count: usize,
+    /// Extra stop words, one per line
+    #[arg(long)]
+    stop_list: Option<PathBuf>,
+    #[command(flatten)]
+    store:     DictionaryStoreArgs
+  },
+
+  /// Dump every term of a dictionary
+  /// with its codes
+  #[command(name = "dictionary-export")]
+  DictionaryExport {
     #[arg(
       long,
       value_enum,
-      default_value_t = DictionaryAdapterArg::Memory
+      default_value_t = DictionaryExportFormat::Plain
     )]
-    adapter:    DictionaryAdapterArg,
+    format: DictionaryExportFormat,
+    /// Write here instead of stdout
     #[arg(long)]
-    gdbm_path:  Option<PathBuf>,
+    output: Option<PathBuf>,
+    #[command(flatten)]
+    store:  DictionaryStoreArgs
+  },
+
+  /// Count terms per code, code
+  /// overlaps and the store size
+  #[command(name = "dictionary-stats")]
+  DictionaryStats {
+    #[command(flatten)]
+    store: DictionaryStoreArgs
+  },
+
+  /// Compare two dictionary stores,
+  /// e.g. LMDB against Redis
+  #[command(name = "dictionary-diff")]
+  DictionaryDiff {
+    #[command(flatten)]
+    store: DictionaryStoreArgs,
+    #[arg(long, value_enum)]
+    other_adapter: DictionaryAdapterArg,
+    /// Store path of the other
+    /// dictionary (gdbm, lmdb, sqlite
+    /// or fst)
     #[arg(long)]
-    lmdb_path:  Option<PathBuf>,
+    other_path:      Option<PathBuf>,
     #[arg(long)]
-    redis_url:  Option<String>,
+    other_redis_url: Option<String>,
     #[arg(long)]
-    namespace:  Option<String>
+    other_namespace: Option<String>
   },
 
   /// Sync normalization assets
@@ -269,7 +489,83 @@ enum DictionaryAdapterArg {
   Memory,
   Redis,
   Lmdb,
-  Gdbm
+  Gdbm,
+  Sqlite,
+  Fst
+}
+
+/// Adapter and store location shared by
+/// the dictionary commands.
+#[derive(Args, Debug)]
+struct DictionaryStoreArgs {
+  #[arg(
+    long,
+    value_enum,
+    default_value_t = DictionaryAdapterArg::Memory
+  )]
+  adapter:     DictionaryAdapterArg,
+  #[arg(long)]
+  gdbm_path:   Option<PathBuf>,
+  #[arg(long)]
+  lmdb_path:   Option<PathBuf>,
+  #[arg(long)]
+  sqlite_path: Option<PathBuf>,
+  #[arg(long)]
+  fst_path:    Option<PathBuf>,
+  #[arg(long)]
+  redis_url:   Option<String>,
+  #[arg(long)]
+  namespace:   Option<String>
+}
+
+impl DictionaryStoreArgs {
+  fn config(&self) -> DictionaryConfig {
+    let mut config =
+      DictionaryConfig::new(
+        DictionaryAdapter::from(
+          self.adapter
+        )
+      );
+    if let Some(path) = &self.gdbm_path
+    {
+      config =
+        config.with_gdbm_path(path);
+    }
+    if let Some(path) = &self.lmdb_path
+    {
+      config =
+        config.with_lmdb_path(path);
+    }
+    if let Some(path) =
+      &self.sqlite_path
+    {
+      config =
+        config.with_sqlite_path(path);
+    }
+    if let Some(path) = &self.fst_path {
+      config =
+        config.with_fst_path(path);
+    }
+    if let Some(url) = &self.redis_url {
+      config = config
+        .with_redis_url(url.clone());
+    }
+    if let Some(name) = &self.namespace
+    {
+      config = config
+        .with_namespace(name.clone());
+    }
+    config
+  }
+}
+
+#[derive(
+  Copy, Clone, Debug, ValueEnum,
+)]
+enum DictionaryExportFormat {
+  /// "term<TAB>codes" lines
+  Plain,
+  Json
 }
 
 #[derive(
@@ -279,7 +575,13 @@ enum DictionaryCodeArg {
   Name,
   Place,
   Publisher,
-  Journal
+  Journal,
+  Month,
+  Editor,
+  Translator,
+  Genre,
+  PageLocator,
+  VolumeLocator
 }
 
 impl From<DictionaryCodeArg>
@@ -301,6 +603,24 @@ impl From<DictionaryCodeArg>
       | DictionaryCodeArg::Journal => {
         DictionaryCode::Journal
       }
+      | DictionaryCodeArg::Month => {
+        DictionaryCode::Month
+      }
+      | DictionaryCodeArg::Editor => {
+        DictionaryCode::Editor
+      }
+      | DictionaryCodeArg::Translator => {
+        DictionaryCode::Translator
+      }
+      | DictionaryCodeArg::Genre => {
+        DictionaryCode::Genre
+      }
+      | DictionaryCodeArg::PageLocator => {
+        DictionaryCode::PageLocator
+      }
+      | DictionaryCodeArg::VolumeLocator => {
+        DictionaryCode::VolumeLocator
+      }
     }
   }
 }
@@ -332,6 +652,12 @@ impl From<DictionaryAdapterArg>
       DictionaryAdapterArg::Gdbm => {
         DictionaryAdapter::Gdbm
       }
+      DictionaryAdapterArg::Sqlite => {
+        DictionaryAdapter::Sqlite
+      }
+      DictionaryAdapterArg::Fst => {
+        DictionaryAdapter::Fst
+      }
     }
   }
 }
@@ -500,14 +826,35 @@ pub fn run() -> anyhow::Result<()> {
   match cli.command {
     | Command::Parse {
       input,
-      output_format
+      output_format,
+      join_lines,
+      with_spans
     } => {
+      check_json_format(
+        "--with-spans",
+        with_spans,
+        output_format
+      )?;
       let text = load_input(&input)?;
+      let parser = if join_lines {
+        parser.with_line_joiner(
+          LineJoiner::new()
+        )
+      } else {
+        parser
+      };
       let references = parser.parse(
         &[text.as_str()],
         output_format
       );
       let output = match output_format {
+        | ParseFormat::Json
+          if with_spans =>
+        {
+          formatter.to_json_with_spans(
+            &references
+          )
+        }
         | ParseFormat::Json => {
           formatter.to_json(&references)
         }
@@ -523,48 +870,258 @@ pub fn run() -> anyhow::Result<()> {
     }
     | Command::Find {
       input,
-      output_format
+      output_format,
+      whole_document,
+      with_spans,
+      footnotes,
+      link_citations,
+      input_format
     } => {
-      let text = load_input(&input)?;
-      let signatures =
-        SequenceModel::load(
-          &paths.finder_sequences
-        )?;
-      let finder =
-        Finder::with_signatures(
-          signatures
+      check_json_format(
+        "--with-spans",
+        with_spans,
+        output_format
+      )?;
+      check_json_format(
+        "--link-citations",
+        link_citations,
+        output_format
+      )?;
+      let document = load_document(
+        &input,
+        input_format
+      )?;
+      if footnotes {
+        let clean = document.clean();
+        let parsed =
+          footnote_references(
+            &parser,
+            &clean,
+            &document,
+            output_format
+          );
+        eprintln!(
+          "found {} citation(s)",
+          parsed.len()
         );
-      let _ = finder.label(&text);
-      let segments =
-        Finder::segments(&text);
-      println!(
-        "found {} sequence(s)",
-        segments.len()
-      );
-      if !segments.is_empty() {
-        let references = segments
+        if !parsed.is_empty() {
+          println!(
+            "{}",
+            render(
+              &formatter,
+              &parsed,
+              output_format,
+              with_spans
+            )
+          );
+        }
+        return Ok(());
+      }
+      let sections = if whole_document {
+        Vec::new()
+      } else {
+        Finder::sections(&document)
+      };
+      for section in &sections {
+        eprintln!(
+          "{}",
+          describe_section(section)
+        );
+      }
+      let entries =
+        document.references();
+      let parsed = if entries.is_empty()
+      {
+        // Running headers, footers and
+        // page numbers would otherwise
+        // merge into references at page
+        // breaks. Without a reference
+        // section the whole document is
+        // searched.
+        let clean =
+          if sections.is_empty() {
+            document.clean()
+          } else {
+            Finder::section_text(
+              &document, &sections
+            )
+          };
+        let text = clean.text();
+        let signatures =
+          SequenceModel::load(
+            &paths.finder_sequences
+          )?;
+        let finder =
+          Finder::with_signatures(
+            signatures
+          );
+        let _ = finder.label(text);
+        let segments =
+          Finder::segment_ranges(text);
+        println!(
+          "found {} sequence(s)",
+          segments.len()
+        );
+        // Segments are paragraphs; the
+        // joiner splits them into
+        // references and mends
+        // hyphenation, learning
+        // spellings
+        // from the whole document.
+        let parser = parser
+          .with_line_joiner(
+            LineJoiner::new()
+              .with_lexicon(text)
+          );
+        segments
           .iter()
-          .map(|segment| {
-            segment.as_str()
+          .flat_map(|segment| {
+            parser
+              .parse(
+                &[&text
+                  [segment.clone()]],
+                output_format
+              )
+              .into_iter()
+              .map(|reference| {
+                locate(
+                  reference,
+                  segment.start,
+                  &clean,
+                  &document
+                )
+              })
           })
-          .collect::<Vec<_>>();
-        let parsed = parser.parse(
-          &references,
-          output_format
+          .collect::<Vec<_>>()
+      } else {
+        // A JATS reference list is
+        // split into entries
+        // already, and tagged entries
+        // need no parsing.
+        println!(
+          "found {} sequence(s)",
+          entries.len()
         );
-        let output = match output_format
+        let text = document.text();
+        let tagged =
+          document.tagged_references();
+        entries
+          .iter()
+          .enumerate()
+          .flat_map(|(idx, entry)| {
+            let references = match tagged
+              .get(idx)
+              .cloned()
+              .flatten()
+            {
+              | Some(reference) => {
+                vec![reference.with_spans(
+                  ReferenceSpans {
+                    end: entry.len(),
+                    ..ReferenceSpans::default()
+                  }
+                )]
+              }
+              | None => parser.parse(
+                &[&text[entry.clone()]],
+                output_format
+              )
+            };
+            references
+              .into_iter()
+              .map(|reference| {
+                place(
+                  reference,
+                  |range| {
+                    entry.start
+                      + range.start
+                      ..entry.start
+                        + range.end
+                  },
+                  &document
+                )
+              })
+          })
+          .collect::<Vec<_>>()
+      };
+      if !link_citations {
+        if !parsed.is_empty() {
+          println!(
+            "{}",
+            render(
+              &formatter,
+              &parsed,
+              output_format,
+              with_spans
+            )
+          );
+        }
+      } else {
+        // Citations are read from the
+        // running text, leaving out the
+        // bibliography itself.
+        let body = if sections
+          .is_empty()
+          && entries.is_empty()
         {
-          | ParseFormat::Json => {
-            formatter.to_json(&parsed)
-          }
-          | ParseFormat::BibTeX => {
-            formatter.to_bibtex(&parsed)
-          }
-          | ParseFormat::Csl => {
-            formatter.to_csl(&parsed)
-          }
+          document.clean()
+        } else {
+          document.clean_where(
+            |page, line| {
+              !sections.iter().any(
+                |section| {
+                  section.contains(
+                    page,
+                    line.index()
+                  )
+                }
+              ) && !entries.iter().any(
+                |entry| {
+                  entry.contains(
+                    &line.offset()
+                  )
+                }
+              )
+            }
+          )
+        };
+        let mut citations =
+          Finder::citations(
+            body.text()
+          );
+        Finder::link_citations(
+          &mut citations,
+          &parsed
+        );
+        eprintln!(
+          "found {} citation(s)",
+          citations.len()
+        );
+        // One document, so the output
+        // stays valid JSON.
+        let references = if with_spans {
+          formatter.to_value_with_spans(
+            &parsed
+          )
+        } else {
+          formatter.to_value(&parsed)
         };
-        println!("{output}",);
+        let output = serde_json::json!({
+          "references": references,
+          "citations": citation_values(
+            &citations, &body,
+            &document
+          )
+        });
+        println!(
+          "{}",
+          serde_json::to_string_pretty(
+            &output
+          )
+          .unwrap_or_else(
+            |_| { "{}".into() }
+          )
+        );
       }
     }
     | Command::Train {
@@ -620,60 +1177,56 @@ pub fn run() -> anyhow::Result<()> {
     }
     | Command::Dictionary {
       term,
-      adapter,
-      gdbm_path,
-      lmdb_path,
-      redis_url,
-      namespace
+      prefix,
+      fuzzy,
+      store
     } => {
-      let mut config =
-        DictionaryConfig::new(
-          DictionaryAdapter::from(
-            adapter
-          )
-        );
-      if let Some(path) = gdbm_path {
-        config =
-          config.with_gdbm_path(path);
-      }
-      if let Some(path) = lmdb_path {
-        config =
-          config.with_lmdb_path(path);
-      }
-      if let Some(url) = redis_url {
-        config =
-          config.with_redis_url(url);
-      }
-      if let Some(name) = namespace {
-        config =
-          config.with_namespace(name);
-      }
       let dictionary =
-        Dictionary::try_create(config)?;
-      let codes =
-        dictionary.lookup(&term);
-      if codes.is_empty() {
+        Dictionary::try_create(
+          store.config()
+        )?;
+      if prefix || fuzzy.is_some() {
+        let matches = match fuzzy {
+          | Some(distance) => {
+            dictionary.lookup_fuzzy(
+              &term, distance
+            )?
+          }
+          | None => {
+            dictionary
+              .lookup_prefix(&term)?
+          }
+        };
+        if matches.is_empty() {
+          println!("no matches");
+        }
+        for found in matches {
+          println!(
+            "{}\t{}",
+            found.term,
+            weight_labels(
+              &found.weights
+            )
+          );
+        }
+        return Ok(());
+      }
+      let weights = dictionary
+        .lookup_weights(&term);
+      if weights.is_empty() {
         println!("no matches");
       } else {
-        let labels = codes
-          .into_iter()
-          .map(|code| {
-            format!("{code:?}")
-          })
-          .collect::<Vec<_>>()
-          .join(", ");
-        println!("{labels}");
+        println!(
+          "{}",
+          weight_labels(&weights)
+        );
       }
     }
     | Command::DictionaryImport {
       inputs,
-      adapter,
       format,
       code,
-      gdbm_path,
-      lmdb_path,
-      redis_url,
-      namespace
+      store
     } => {
       if inputs.is_empty() {
         anyhow::bail!(
@@ -681,35 +1234,26 @@ pub fn run() -> anyhow::Result<()> {
            at least one input file"
         );
       }
-      let mut config =
-        DictionaryConfig::new(
-          DictionaryAdapter::from(
-            adapter
-          )
-        );
-      if let Some(path) = gdbm_path {
-        config =
-          config.with_gdbm_path(path);
-      }
-      if let Some(path) = lmdb_path {
-        config =
-          config.with_lmdb_path(path);
-      }
-      if let Some(url) = redis_url {
-        config =
-          config.with_redis_url(url);
-      }
-      if let Some(name) = namespace {
-        config =
-          config.with_namespace(name);
-      }
       let mut dictionary =
-        Dictionary::try_create(config)?;
+        Dictionary::try_create(
+          store.config()
+        )?;
+      warn_legacy_keys(&dictionary);
       let mut total = 0usize;
       let code =
         DictionaryCode::from(code);
       for input in inputs {
         let inserted = match format {
+          | DictionaryImportFormat::Plain
+            if code
+              == DictionaryCode::Month =>
+          {
+            let entries =
+              load_month_terms(&input)?;
+            dictionary.import_weighted(
+              entries
+            )?
+          }
           | DictionaryImportFormat::Plain => {
             let terms =
               load_dictionary_terms(
@@ -724,7 +1268,7 @@ pub fn run() -> anyhow::Result<()> {
               load_anystyle_entries(
                 &input
               )?;
-            dictionary.import_entries(
+            dictionary.import_weighted(
               entries
             )?
           }
@@ -743,71 +1287,326 @@ pub fn run() -> anyhow::Result<()> {
     | Command::DictionarySync {
       source_dir,
       pattern,
-      adapter,
-      gdbm_path,
-      lmdb_path,
-      redis_url,
-      namespace
+      compile,
+      store
+    } => {
+      let mut dictionary =
+        Dictionary::try_create(
+          store.config()
+        )?;
+      warn_legacy_keys(&dictionary);
+      let total =
+        sync_dictionary_files(
+          &mut dictionary,
+          &source_dir,
+          pattern.clone()
+        )?;
+      println!("total synced: {total}");
+      if let Some(output) = compile {
+        let keys =
+          compile_dictionary_files(
+            &output,
+            dictionary
+              .term_normalizer(),
+            &source_dir,
+            pattern
+          )?;
+        println!(
+          "compiled {keys} keys into \
+           {}",
+          output.display()
+        );
+      }
+    }
+    | Command::DictionaryCompile {
+      source_dir,
+      pattern,
+      output,
+      fold_diacritics
+    } => {
+      let keys =
+        compile_dictionary_files(
+          &output,
+          TermNormalizer::new()
+            .with_diacritic_folding(
+              fold_diacritics
+            ),
+          &source_dir,
+          pattern
+        )?;
+      println!(
+        "compiled {keys} keys into {}",
+        output.display()
+      );
+    }
+    | Command::DictionaryLearn {
+      dataset,
+      min_count,
+      stop_list,
+      store
+    } => {
+      let mut dictionary =
+        Dictionary::try_create(
+          store.config()
+        )?;
+      warn_legacy_keys(&dictionary);
+      let stop_words = match stop_list {
+        | Some(path) => {
+          load_dictionary_terms(&path)?
+        }
+        | None => Vec::new()
+      };
+      let mut learner =
+        DictionaryLearner::new(
+          dictionary.term_normalizer()
+        )
+        .with_min_count(min_count)
+        .with_stop_words(stop_words);
+      let files =
+        collect_files(&dataset)?;
+      if files.is_empty() {
+        anyhow::bail!(
+          "no tagged datasets match \
+           {dataset}"
+        );
+      }
+      for path in &files {
+        learner
+          .add_dataset(
+            &fs::read_to_string(path)?
+          )
+          .with_context(|| {
+            format!(
+              "learn from {}",
+              path.display()
+            )
+          })?;
+      }
+      let entries = learner.entries();
+      let merged = dictionary
+        .import_weighted(
+          entries.iter().cloned()
+        )?;
+      println!(
+        "learned {} terms from {} \
+         sequences; {merged} keys \
+         updated",
+        entries.len(),
+        learner.sequences()
+      );
+    }
+    | Command::DictionaryExport {
+      format,
+      output,
+      store
     } => {
-      let mut config =
+      let dictionary =
+        Dictionary::try_create(
+          store.config()
+        )?;
+      let entries =
+        dictionary.entries()?;
+      let rendered = match format {
+        | DictionaryExportFormat::Plain => {
+          entries
+            .iter()
+            .map(|entry| {
+              format!(
+                "{}\t{}\n",
+                entry.term,
+                weight_labels(
+                  &entry.weights
+                )
+              )
+            })
+            .collect::<String>()
+        }
+        | DictionaryExportFormat::Json => {
+          let values = entries
+            .iter()
+            .map(entry_json)
+            .collect::<Vec<_>>();
+          serde_json::to_string_pretty(
+            &values
+          )? + "\n"
+        }
+      };
+      match output {
+        | Some(path) => {
+          if let Some(parent) =
+            path.parent()
+          {
+            fs::create_dir_all(parent)?;
+          }
+          fs::write(&path, rendered)?;
+          println!(
+            "exported {} terms to {}",
+            entries.len(),
+            path.display()
+          );
+        }
+        | None => print!("{rendered}")
+      }
+    }
+    | Command::DictionaryStats {
+      store
+    } => {
+      let config = store.config();
+      let dictionary =
+        Dictionary::try_create(
+          config.clone()
+        )?;
+      let stats = dictionary.stats()?;
+      println!(
+        "terms: {}",
+        stats.terms
+      );
+      for (code, count) in &stats.codes
+      {
+        println!("{code:?}: {count}");
+      }
+      for (left, right, count) in
+        &stats.overlaps
+      {
+        println!(
+          "{left:?} & {right:?}: \
+           {count}"
+        );
+      }
+      if let Some(path) =
+        config.store_path()
+      {
+        println!(
+          "size: {} bytes ({})",
+          store_size(&path),
+          path.display()
+        );
+      }
+    }
+    | Command::DictionaryDiff {
+      store,
+      other_adapter,
+      other_path,
+      other_redis_url,
+      other_namespace
+    } => {
+      let left =
+        Dictionary::try_create(
+          store.config()
+        )?;
+      let mut other =
         DictionaryConfig::new(
           DictionaryAdapter::from(
-            adapter
+            other_adapter
           )
         );
-      if let Some(path) = gdbm_path {
-        config =
-          config.with_gdbm_path(path);
+      if let Some(path) = other_path {
+        other = match other_adapter {
+          | DictionaryAdapterArg::Gdbm => {
+            other.with_gdbm_path(path)
+          }
+          | DictionaryAdapterArg::Lmdb => {
+            other.with_lmdb_path(path)
+          }
+          | DictionaryAdapterArg::Sqlite => {
+            other.with_sqlite_path(path)
+          }
+          | DictionaryAdapterArg::Fst => {
+            other.with_fst_path(path)
+          }
+          | DictionaryAdapterArg::Memory
+          | DictionaryAdapterArg::Redis => {
+            anyhow::bail!(
+              "--other-path needs a \
+               file-backed adapter"
+            )
+          }
+        };
       }
-      if let Some(path) = lmdb_path {
-        config =
-          config.with_lmdb_path(path);
+      if let Some(url) = other_redis_url
+      {
+        other =
+          other.with_redis_url(url);
       }
-      if let Some(url) = redis_url {
-        config =
-          config.with_redis_url(url);
+      if let Some(name) =
+        other_namespace
+      {
+        other =
+          other.with_namespace(name);
       }
-      if let Some(name) = namespace {
-        config =
-          config.with_namespace(name);
+      let right =
+        Dictionary::try_create(other)?;
+      let diff = left.diff(&right)?;
+      for entry in &diff.only_left {
+        println!(
+          "- {}\t{}",
+          entry.term,
+          weight_labels(&entry.weights)
+        );
       }
-      let patterns =
-        if pattern.is_empty() {
-          vec![
-            "**/*.txt".to_string(),
-            "**/*.txt.gz".to_string(),
-          ]
-        } else {
-          pattern
-        };
-      let files =
-        collect_dictionary_files(
-          &source_dir,
-          &patterns
-        )?;
-      if files.is_empty() {
-        anyhow::bail!(
-          "no dictionary files found \
-           in {}",
-          source_dir.display()
+      for entry in &diff.only_right {
+        println!(
+          "+ {}\t{}",
+          entry.term,
+          weight_labels(&entry.weights)
+        );
+      }
+      for (before, after) in
+        &diff.changed
+      {
+        println!(
+          "~ {}\t{} -> {}",
+          before.term,
+          weight_labels(
+            &before.weights
+          ),
+          weight_labels(&after.weights)
         );
       }
+      println!(
+        "{} only left, {} only right, \
+         {} changed",
+        diff.only_left.len(),
+        diff.only_right.len(),
+        diff.changed.len()
+      );
+    }
+    | Command::DictionaryMigrate {
+      source_dir,
+      pattern,
+      fold_diacritics,
+      store
+    } => {
       let mut dictionary =
-        Dictionary::try_create(config)?;
-      let mut total = 0usize;
-      for file in files {
-        let entries =
-          load_anystyle_entries(&file)?;
-        let inserted = dictionary
-          .import_entries(entries)?;
+        Dictionary::try_create(
+          store.config()
+        )?;
+      let previous =
+        dictionary.key_scheme();
+      let removed = dictionary
+        .migrate_keys(
+          TermNormalizer::new()
+            .with_diacritic_folding(
+              fold_diacritics
+            )
+        )?;
+      println!(
+        "removed {removed} keys \
+         ({previous:?} scheme)"
+      );
+      if let Some(source_dir) =
+        source_dir
+      {
+        let total =
+          sync_dictionary_files(
+            &mut dictionary,
+            &source_dir,
+            pattern
+          )?;
         println!(
-          "synced {inserted} terms \
-           from {}",
-          file.display()
+          "total re-imported: {total}"
         );
-        total += inserted;
       }
-      println!("total synced: {total}");
     }
     | Command::NormalizationSync {
       source_dir,
@@ -859,60 +1658,496 @@ pub fn run() -> anyhow::Result<()> {
     }
   }
 
-  Ok(())
+  Ok(())
+}
+
+fn load_input(
+  input: &str
+) -> anyhow::Result<String> {
+  if input == "-" {
+    let mut buffer = String::new();
+    std::io::stdin()
+      .read_to_string(&mut buffer)?;
+    return Ok(buffer);
+  }
+  let path = Path::new(input);
+  if !path.exists() {
+    return Ok(input.to_string());
+  }
+  Ok(Document::open(path)?.text())
+}
+
+/// References in the requested format.
+fn render(
+  formatter: &Format,
+  references: &[Reference],
+  format: ParseFormat,
+  with_spans: bool
+) -> String {
+  match format {
+    | ParseFormat::Json if with_spans => {
+      formatter
+        .to_json_with_spans(references)
+    }
+    | ParseFormat::Json => {
+      formatter.to_json(references)
+    }
+    | ParseFormat::BibTeX => {
+      formatter.to_bibtex(references)
+    }
+    | ParseFormat::Csl => {
+      formatter.to_csl(references)
+    }
+  }
+}
+
+/// One reference per footnote cite,
+/// parsed from the full citation it
+/// refers to, with the note number,
+/// how it cites and its locator.
+fn footnote_references(
+  parser: &Parser,
+  clean: &CleanText,
+  document: &Document,
+  format: ParseFormat
+) -> Vec<Reference> {
+  let text = clean.text();
+  Finder::footnotes(text)
+    .into_iter()
+    .flat_map(|citation| {
+      parser
+        .parse(
+          &[&citation.source_text(text)],
+          format
+        )
+        .into_iter()
+        .map(move |mut reference| {
+          reference.insert(
+            "footnote",
+            FieldValue::Single(
+              citation.note.clone()
+            )
+          );
+          reference.insert(
+            "citation-kind",
+            FieldValue::Single(
+              citation.kind.as_str().into()
+            )
+          );
+          if let Some(locator) =
+            &citation.locator
+          {
+            reference.insert(
+              "locator",
+              FieldValue::Single(
+                locator.clone()
+              )
+            );
+          }
+          locate(
+            reference,
+            citation.source.start,
+            clean,
+            document
+          )
+        })
+    })
+    .collect()
+}
+
+/// Moves the spans of a reference
+/// parsed from the segment at `start`
+/// of `clean` to document offsets, and
+/// notes the page and line it starts
+/// on.
+fn locate(
+  reference: Reference,
+  start: usize,
+  clean: &CleanText,
+  document: &Document
+) -> Reference {
+  place(
+    reference,
+    |range| {
+      clean.source_range(
+        start + range.start
+          ..start + range.end
+      )
+    },
+    document
+  )
+}
+
+/// Maps the spans of a reference to
+/// document offsets with `map` and
+/// notes the page and line it starts
+/// on.
+fn place(
+  mut reference: Reference,
+  map: impl Fn(Range<usize>) -> Range<usize>,
+  document: &Document
+) -> Reference {
+  let spans = reference.spans_mut();
+  spans.map_ranges(map);
+  if let Some((page, line)) =
+    document.line_at(spans.start)
+  {
+    spans.page = Some(page);
+    spans.line = Some(line.index());
+  }
+  reference
+}
+
+/// Spans and citations are only
+/// written as JSON.
+fn check_json_format(
+  flag: &str,
+  used: bool,
+  format: ParseFormat
+) -> anyhow::Result<()> {
+  if used && format != ParseFormat::Json
+  {
+    anyhow::bail!(
+      "{flag} needs JSON output"
+    );
+  }
+  Ok(())
+}
+
+/// In-text citations with document
+/// offsets, the page and line they
+/// start on, and the indices of the
+/// references each key resolves to.
+fn citation_values(
+  citations: &[InTextCitation],
+  body: &CleanText,
+  document: &Document
+) -> Vec<serde_json::Value> {
+  citations
+    .iter()
+    .map(|citation| {
+      let span = body.source_range(
+        citation.span.clone()
+      );
+      let context = body.source_range(
+        citation.context_span.clone()
+      );
+      let keys = citation
+        .keys
+        .iter()
+        .zip(&citation.resolved)
+        .map(|(key, resolved)| {
+          match key {
+            | CitationKey::Number(
+              number
+            ) => {
+              serde_json::json!({
+                "number": number,
+                "references": resolved
+              })
+            }
+            | CitationKey::AuthorYear {
+              authors,
+              et_al,
+              year
+            } => {
+              serde_json::json!({
+                "authors": authors,
+                "et-al": et_al,
+                "year": year,
+                "references": resolved
+              })
+            }
+          }
+        })
+        .collect::<Vec<_>>();
+      let mut value = serde_json::json!({
+        "text": citation.text,
+        "start": span.start,
+        "end": span.end,
+        "context": citation.context,
+        "context-start": context.start,
+        "context-end": context.end,
+        "keys": keys
+      });
+      if let Some((page, line)) =
+        document.line_at(span.start)
+      {
+        value["page"] = page.into();
+        value["line"] =
+          line.index().into();
+      }
+      value
+    })
+    .collect()
+}
+
+/// "reference section "References":
+/// page 3 line 12 to page 5 line 40",
+/// counting from one.
+fn describe_section(
+  section: &ReferenceSection
+) -> String {
+  let heading = section
+    .heading
+    .as_deref()
+    .map_or_else(
+      || "(no heading)".to_string(),
+      |heading| format!("{heading:?}")
+    );
+  format!(
+    "reference section {heading}: \
+     page {} line {} to page {} line \
+     {}",
+    section.start.page + 1,
+    section.start.line + 1,
+    section.end.page + 1,
+    section.end.line + 1
+  )
 }
 
-fn load_input(
-  input: &str
-) -> anyhow::Result<String> {
-  if input == "-" {
-    let mut buffer = String::new();
-    std::io::stdin()
-      .read_to_string(&mut buffer)?;
-    return Ok(buffer);
-  }
+/// Input as a [`Document`], keeping PDF
+/// pages and layout.
+fn load_document(
+  input: &str,
+  format: Option<InputFormat>
+) -> anyhow::Result<Document> {
   let path = Path::new(input);
-  if path.exists() {
-    Ok(fs::read_to_string(path)?)
-  } else {
-    Ok(input.to_string())
+  if input == "-" || !path.exists() {
+    let text = load_input(input)?;
+    return match format {
+      | Some(format) => {
+        Document::from_bytes_as(
+          text.as_bytes(),
+          format
+        )
+      }
+      | None => {
+        Ok(Document::from_text(&text))
+      }
+    };
   }
+  Document::open_as(
+    path,
+    format.or_else(|| {
+      InputFormat::from_path(path)
+    })
+  )
 }
 
 fn formatter_for_cli(
   cli: &Cli
 ) -> anyhow::Result<Format> {
-  if let Some(dir) =
-    cli.normalization_dir.as_ref()
-  {
-    let config =
-      NormalizationConfig::load_from_dir(
-        dir
-      )?;
-    Ok(Format::with_normalization(
-      config
-    ))
-  } else {
-    Ok(Format::new())
-  }
+  Ok(Format::with_normalization(
+    normalization_for_cli(cli)?
+  ))
 }
 
 fn parser_for_cli(
   cli: &Cli
 ) -> anyhow::Result<Parser> {
-  if let Some(dir) =
-    cli.normalization_dir.as_ref()
+  let input = if cli.raw_input {
+    InputNormalizer::disabled()
+  } else {
+    InputNormalizer::new()
+      .with_form(cli.unicode_form)
+  };
+  let normalization =
+    normalization_for_cli(cli)?;
+  if cli.dictionaries.is_empty()
+    && cli.dictionary_deny.is_empty()
+  {
+    return Ok(
+      Parser::with_normalization(
+        normalization
+      )
+      .with_input_normalizer(input)
+    );
+  }
+  Ok(
+    Parser::with_dictionary_and_normalization(
+      layered_dictionary_for_cli(cli)?,
+      normalization
+    )
+    .with_input_normalizer(input)
+  )
+}
+
+fn layered_dictionary_for_cli(
+  cli: &Cli
+) -> anyhow::Result<LayeredDictionary> {
+  let mut layers = cli
+    .dictionaries
+    .iter()
+    .map(|spec| dictionary_layer(spec));
+  let base = match layers.next() {
+    | Some(base) => base?,
+    | None => {
+      Dictionary::create(
+        DictionaryAdapter::Memory
+      )
+    }
+  };
+  let mut layered =
+    LayeredDictionary::new(base);
+  for overlay in layers {
+    layered =
+      layered.with_layer(overlay?);
+  }
+  for path in &cli.dictionary_deny {
+    for (term, codes) in
+      load_deny_list(path)?
+    {
+      layered.deny(&term, &codes);
+    }
+  }
+  Ok(layered)
+}
+
+/// Opens `lmdb:path`, `redis:url` or a
+/// bare adapter name with its default
+/// store.
+fn dictionary_layer(
+  spec: &str
+) -> anyhow::Result<Dictionary> {
+  let (name, location) =
+    match spec.split_once(':') {
+      | Some((name, location)) => {
+        (name, Some(location))
+      }
+      | None => (spec, None)
+    };
+  let adapter =
+    DictionaryAdapterArg::from_str(
+      name, true
+    )
+    .map_err(|err| {
+      anyhow::anyhow!(
+        "unknown dictionary adapter \
+         in {spec}: {err}"
+      )
+    })?;
+  let mut config =
+    DictionaryConfig::new(
+      DictionaryAdapter::from(adapter)
+    );
+  if let Some(location) = location {
+    config = match adapter {
+      | DictionaryAdapterArg::Gdbm => {
+        config.with_gdbm_path(location)
+      }
+      | DictionaryAdapterArg::Lmdb => {
+        config.with_lmdb_path(location)
+      }
+      | DictionaryAdapterArg::Sqlite => {
+        config.with_sqlite_path(location)
+      }
+      | DictionaryAdapterArg::Fst => {
+        config.with_fst_path(location)
+      }
+      | DictionaryAdapterArg::Redis => {
+        config.with_redis_url(location)
+      }
+      | DictionaryAdapterArg::Memory => {
+        anyhow::bail!(
+          "the memory adapter takes no \
+           path: {spec}"
+        )
+      }
+    };
+  }
+  Dictionary::try_create(config)
+    .with_context(|| {
+      format!("open dictionary {spec}")
+    })
+}
+
+/// Terms to deny; `#! place` headers
+/// limit the following terms to those
+/// codes, terms before any header lose
+/// every code.
+fn load_deny_list(
+  path: &Path
+) -> anyhow::Result<
+  Vec<(String, Vec<DictionaryCode>)>
+> {
+  let content =
+    fs::read_to_string(path)?;
+  let mut codes = Vec::new();
+  let mut denied = Vec::new();
+  for raw_line in content.lines() {
+    let line = raw_line.trim();
+    if let Some(tag) =
+      line.strip_prefix("#!")
+    {
+      let code =
+        DictionaryCode::from_tag(tag)
+          .with_context(|| {
+          format!(
+            "unknown dictionary tag \
+             {tag:?} in {}",
+            path.display()
+          )
+        })?;
+      codes = vec![code];
+      continue;
+    }
+    if line.is_empty()
+      || line.starts_with('#')
+    {
+      continue;
+    }
+    denied.push((
+      line.to_string(),
+      codes.clone()
+    ));
+  }
+  Ok(denied)
+}
+
+fn normalization_for_cli(
+  cli: &Cli
+) -> anyhow::Result<NormalizationConfig>
+{
+  let mut config = match cli
+    .normalization_dir
+    .as_ref()
   {
-    let config =
+    | Some(dir) => {
       NormalizationConfig::load_from_dir(
         dir
-      )?;
-    Ok(Parser::with_normalization(
-      config
-    ))
-  } else {
-    Ok(Parser::new())
+      )?
+    }
+    | None => {
+      NormalizationConfig::default()
+    }
+  };
+  if let Some(path) =
+    cli.protected_words.as_ref()
+  {
+    config = config
+      .with_protected_words(
+        ProtectedWords::load_from_file(
+          path
+        )?
+      );
   }
+  Ok(
+    config
+      .with_title_case(cli.title_case)
+      .with_structured_locations(
+        cli.structured_locations
+      )
+      .with_canonical_publishers(
+        cli.canonical_publishers
+      )
+      .with_imprint_parents(
+        cli.imprint_parents
+      )
+      .with_arabic_volumes(
+        cli.arabic_volumes
+      )
+  )
 }
 
 fn load_dictionary_terms(
@@ -940,6 +2175,219 @@ fn load_dictionary_terms(
   Ok(terms)
 }
 
+/// Month terms with their number,
+/// one `term<TAB>number` or
+/// `term,number` per line.
+fn load_month_terms(
+  path: &Path
+) -> anyhow::Result<
+  Vec<(String, DictionaryCode, f32)>
+> {
+  let content =
+    fs::read_to_string(path)?;
+  let mut entries = Vec::new();
+  for (idx, raw_line) in
+    content.lines().enumerate()
+  {
+    let line = raw_line.trim();
+    if line.is_empty()
+      || line.starts_with('#')
+    {
+      continue;
+    }
+    let mut columns = line
+      .split(['\t', ','])
+      .map(str::trim);
+    let term =
+      columns.next().unwrap_or("");
+    let Some(month) = columns
+      .next()
+      .and_then(|month| {
+        month.parse::<u8>().ok()
+      })
+      .filter(|month| {
+        (1..=12).contains(month)
+      })
+    else {
+      anyhow::bail!(
+        "{}:{}: month term {term:?} \
+         needs its number, as in \
+         \"outubro\t10\"",
+        path.display(),
+        idx + 1
+      );
+    };
+    entries.push((
+      term.to_string(),
+      DictionaryCode::Month,
+      f32::from(month)
+    ));
+  }
+  Ok(entries)
+}
+
+fn sync_dictionary_files(
+  dictionary: &mut Dictionary,
+  source_dir: &Path,
+  pattern: Vec<String>
+) -> anyhow::Result<usize> {
+  let mut total = 0usize;
+  for file in anystyle_dictionary_files(
+    source_dir, pattern
+  )? {
+    let entries =
+      load_anystyle_entries(&file)?;
+    let inserted = dictionary
+      .import_weighted(entries)?;
+    println!(
+      "synced {inserted} terms from {}",
+      file.display()
+    );
+    total += inserted;
+  }
+  Ok(total)
+}
+
+fn compile_dictionary_files(
+  output: &Path,
+  terms: TermNormalizer,
+  source_dir: &Path,
+  pattern: Vec<String>
+) -> anyhow::Result<usize> {
+  let mut entries = Vec::new();
+  for file in anystyle_dictionary_files(
+    source_dir, pattern
+  )? {
+    entries.extend(
+      load_anystyle_entries(&file)?
+    );
+  }
+  Dictionary::compile_weighted(
+    output, terms, entries
+  )
+}
+
+fn anystyle_dictionary_files(
+  source_dir: &Path,
+  pattern: Vec<String>
+) -> anyhow::Result<Vec<PathBuf>> {
+  let patterns = if pattern.is_empty() {
+    vec![
+      "**/*.txt".to_string(),
+      "**/*.txt.gz".to_string(),
+    ]
+  } else {
+    pattern
+  };
+  let files = collect_dictionary_files(
+    source_dir, &patterns
+  )?;
+  if files.is_empty() {
+    anyhow::bail!(
+      "no dictionary files found in {}",
+      source_dir.display()
+    );
+  }
+  Ok(files)
+}
+
+/// "Place" for unscored codes, "Name
+/// 0.8" when the entry carries a score
+/// and "Month(10)" for month terms.
+fn weight_labels(
+  weights: &DictionaryWeights
+) -> String {
+  weights
+    .iter()
+    .map(|(code, weight)| {
+      let label = match weights.month()
+      {
+        | Some(month)
+          if code
+            == DictionaryCode::Month =>
+        {
+          format!("{code:?}({month})")
+        }
+        | _ => format!("{code:?}")
+      };
+      if weight == 1.0 {
+        label
+      } else {
+        format!("{label} {weight}")
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// `{"term", "codes", "weights"}` with
+/// codes by their dictionary tag, plus
+/// `"month"` for month terms.
+fn entry_json(
+  entry: &DictionaryMatch
+) -> serde_json::Value {
+  let weights = entry
+    .weights
+    .iter()
+    .map(|(code, weight)| {
+      (
+        code.tag().to_string(),
+        serde_json::json!(weight)
+      )
+    })
+    .collect::<serde_json::Map<_, _>>();
+  let mut json = serde_json::json!({
+    "term": entry.term,
+    "codes": entry
+      .codes
+      .iter()
+      .map(|code| code.tag())
+      .collect::<Vec<_>>(),
+    "weights": weights
+  });
+  if let Some(month) =
+    entry.weights.month()
+  {
+    json["month"] =
+      serde_json::json!(month);
+  }
+  json
+}
+
+/// Bytes on disk of a store file, or of
+/// every file in an LMDB directory.
+fn store_size(path: &Path) -> u64 {
+  if path.is_dir() {
+    fs::read_dir(path)
+      .into_iter()
+      .flatten()
+      .flatten()
+      .filter_map(|entry| {
+        entry.metadata().ok()
+      })
+      .filter(|meta| meta.is_file())
+      .map(|meta| meta.len())
+      .sum()
+  } else {
+    fs::metadata(path)
+      .map_or(0, |meta| meta.len())
+  }
+}
+
+fn warn_legacy_keys(
+  dictionary: &Dictionary
+) {
+  if dictionary.key_scheme()
+    == KeyScheme::Legacy
+  {
+    eprintln!(
+      "dictionary uses legacy ASCII \
+       keys; run dictionary-migrate \
+       to rewrite it with Unicode keys"
+    );
+  }
+}
+
 fn collect_dictionary_files(
   source_dir: &Path,
   patterns: &[String]
@@ -1065,10 +2513,14 @@ fn sync_normalization_files(
   Ok(copied)
 }
 
+/// Terms of AnyStyle dictionary files
+/// with their `#!` section code and
+/// trailing score, 1.0 when unscored.
 fn load_anystyle_entries(
   path: &Path
-) -> anyhow::Result<Vec<(String, u32)>>
-{
+) -> anyhow::Result<
+  Vec<(String, DictionaryCode, f32)>
+> {
   let file = File::open(path)?;
   let reader: Box<dyn BufRead> =
     match path
@@ -1086,7 +2538,7 @@ fn load_anystyle_entries(
     };
 
   let mut entries = Vec::new();
-  let mut mode = 0u32;
+  let mut mode = None;
   for line in reader.lines() {
     let line = line?;
     let line = line.trim();
@@ -1097,45 +2549,61 @@ fn load_anystyle_entries(
       line.strip_prefix("#!")
     {
       mode =
-        DictionaryCode::from_tag(tag)
-          .map(|code| code.bit())
-          .unwrap_or(0);
+        DictionaryCode::from_tag(tag);
       continue;
     }
     if line.starts_with('#') {
       continue;
     }
-    if mode == 0 {
+    let Some(code) = mode else {
       continue;
-    }
-    let key =
-      strip_trailing_score(line);
+    };
+    let (key, score) =
+      split_trailing_score(line);
     if key.is_empty() {
       continue;
     }
-    entries
-      .push((key.to_string(), mode));
+    // The number of a month term is
+    // its score; January is not a
+    // default.
+    if code == DictionaryCode::Month
+      && score.is_none()
+    {
+      anyhow::bail!(
+        "{}: month term {key:?} needs \
+         its number",
+        path.display()
+      );
+    }
+    entries.push((
+      key.to_string(),
+      code,
+      score.unwrap_or(1.0)
+    ));
   }
 
   Ok(entries)
 }
 
-fn strip_trailing_score(
+fn split_trailing_score(
   line: &str
-) -> &str {
+) -> (&str, Option<f32>) {
   let trimmed = line.trim();
   let Some(idx) =
     trimmed.rfind(char::is_whitespace)
   else {
-    return trimmed;
+    return (trimmed, None);
   };
   let (left, right) =
     trimmed.split_at(idx);
   let right = right.trim();
   if is_score_token(right) {
-    left.trim_end()
+    (
+      left.trim_end(),
+      right.parse().ok()
+    )
   } else {
-    trimmed
+    (trimmed, None)
   }
 }
 
@@ -1950,9 +3418,10 @@ mod tests {
         .expect("load entries");
     let mut map =
       HashMap::<String, u32>::new();
-    for (term, value) in entries {
-      *map.entry(term).or_insert(0) |=
-        value;
+    for (term, code, _) in &entries {
+      *map
+        .entry(term.clone())
+        .or_insert(0) |= code.bit();
     }
 
     assert_eq!(
@@ -1968,6 +3437,172 @@ mod tests {
         | DictionaryCode::Publisher
           .bit();
     assert_eq!(nature, Some(expected));
+    assert!(entries.contains(&(
+      "Nature".to_string(),
+      DictionaryCode::Journal,
+      2.0
+    )));
+  }
+
+  #[test]
+  fn load_month_terms_requires_numbers()
+  {
+    let temp_dir =
+      tempdir().expect("temp dir");
+    let path = temp_dir
+      .path()
+      .join("months.txt");
+    fs::write(
+      &path,
+      [
+        "# Portuguese",
+        "outubro\t10",
+        "novembro,11"
+      ]
+      .join("\n")
+    )
+    .expect("write months");
+    assert_eq!(
+      load_month_terms(&path)
+        .expect("load months"),
+      vec![
+        (
+          "outubro".to_string(),
+          DictionaryCode::Month,
+          10.0
+        ),
+        (
+          "novembro".to_string(),
+          DictionaryCode::Month,
+          11.0
+        ),
+      ]
+    );
+
+    fs::write(&path, "dezembro\n")
+      .expect("write months");
+    let error = load_month_terms(&path)
+      .expect_err("number required");
+    assert!(
+      error
+        .to_string()
+        .contains("needs its number")
+    );
+  }
+
+  #[test]
+  fn dictionary_commands_share_store_args()
+   {
+    let cli = Cli::parse_from([
+      "cite-otter",
+      "dictionary-import",
+      "terms.txt",
+      "--adapter",
+      "sqlite",
+      "--sqlite-path",
+      "terms.sqlite"
+    ]);
+    let Command::DictionaryImport {
+      store,
+      ..
+    } = cli.command
+    else {
+      panic!("expected an import");
+    };
+    assert_eq!(
+      store.config().store_path(),
+      Some(PathBuf::from(
+        "terms.sqlite"
+      ))
+    );
+
+    // Migration rewrites a persistent
+    // store unless told otherwise.
+    let cli = Cli::parse_from([
+      "cite-otter",
+      "dictionary-migrate"
+    ]);
+    let Command::DictionaryMigrate {
+      store,
+      ..
+    } = cli.command
+    else {
+      panic!("expected a migration");
+    };
+    assert!(matches!(
+      store.adapter,
+      DictionaryAdapterArg::Lmdb
+    ));
+  }
+
+  #[test]
+  fn layered_dictionary_for_cli_reads_layers_and_deny_list()
+   {
+    let temp_dir =
+      tempdir().expect("temp dir");
+    let overlay = temp_dir
+      .path()
+      .join("overlay.db");
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Sqlite
+      )
+      .with_sqlite_path(&overlay)
+    )
+    .expect("overlay opens")
+    .import_terms(
+      DictionaryCode::Place,
+      vec!["Jordan".to_string()]
+    )
+    .expect("overlay import");
+    let deny =
+      temp_dir.path().join("deny.txt");
+    fs::write(
+      &deny,
+      "Philippines\n#! place\nJordan\n"
+    )
+    .expect("write deny list");
+
+    let cli = Cli::parse_from([
+      "cite-otter",
+      "--dictionary",
+      "memory",
+      "--dictionary",
+      &format!(
+        "sqlite:{}",
+        overlay.display()
+      ),
+      "--dictionary-deny",
+      &deny.display().to_string(),
+      "parse",
+      "Doe, J. Title. 2020."
+    ]);
+    let layered =
+      layered_dictionary_for_cli(&cli)
+        .expect("layered dictionary");
+
+    assert_eq!(
+      layered.layers().len(),
+      2
+    );
+    assert!(
+      layered
+        .lookup("Jordan")
+        .is_empty()
+    );
+    assert!(
+      layered
+        .lookup("Philippines")
+        .is_empty()
+    );
+    assert_eq!(
+      layered.lookup("Italy"),
+      vec![DictionaryCode::Place]
+    );
+    assert!(
+      dictionary_layer("tape:/tmp/x")
+        .is_err()
+    );
   }
 
   #[test]
@@ -0,0 +1,106 @@
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+)]
+pub enum DictionaryCode {
+  Name,
+  Place,
+  Publisher,
+  Journal,
+  Month,
+  Editor,
+  Translator,
+  Genre,
+  PageLocator,
+  VolumeLocator
+}
+
+impl DictionaryCode {
+  pub const ALL: [DictionaryCode; 10] = [
+    DictionaryCode::Name,
+    DictionaryCode::Place,
+    DictionaryCode::Publisher,
+    DictionaryCode::Journal,
+    DictionaryCode::Month,
+    DictionaryCode::Editor,
+    DictionaryCode::Translator,
+    DictionaryCode::Genre,
+    DictionaryCode::PageLocator,
+    DictionaryCode::VolumeLocator
+  ];
+  /// Codes naming reference fields; the
+  /// others mark keywords (months,
+  /// roles, genres, locators).
+  pub const FIELDS: [DictionaryCode;
+    4] = [
+    DictionaryCode::Name,
+    DictionaryCode::Place,
+    DictionaryCode::Publisher,
+    DictionaryCode::Journal
+  ];
+
+  /// Bit in the stored value mask;
+  /// follows the declaration order so
+  /// new codes only ever append bits.
+  pub fn bit(self) -> u32 {
+    1 << self as u32
+  }
+
+  /// Tag used by AnyStyle-style `#!
+  /// tag` headers and the CLI.
+  pub fn tag(self) -> &'static str {
+    match self {
+      | DictionaryCode::Name => "name",
+      | DictionaryCode::Place => "place",
+      | DictionaryCode::Publisher => {
+        "publisher"
+      }
+      | DictionaryCode::Journal => {
+        "journal"
+      }
+      | DictionaryCode::Month => "month",
+      | DictionaryCode::Editor => {
+        "editor"
+      }
+      | DictionaryCode::Translator => {
+        "translator"
+      }
+      | DictionaryCode::Genre => "genre",
+      | DictionaryCode::PageLocator => {
+        "page-locator"
+      }
+      | DictionaryCode::VolumeLocator => {
+        "volume-locator"
+      }
+    }
+  }
+
+  pub fn from_tag(
+    tag: &str
+  ) -> Option<Self> {
+    let tag = tag
+      .trim()
+      .to_ascii_lowercase()
+      .replace('_', "-");
+    Self::ALL
+      .into_iter()
+      .find(|code| code.tag() == tag)
+  }
+
+  pub fn from_value(
+    value: u32
+  ) -> Vec<Self> {
+    Self::ALL
+      .into_iter()
+      .filter(|code| {
+        value & code.bit() != 0
+      })
+      .collect()
+  }
+}
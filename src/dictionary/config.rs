@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::path::{
+  Path,
+  PathBuf
+};
+
+use anyhow::{
+  Context,
+  Result
+};
+
+use super::fst_backend::FstBackend;
+use super::gdbm_backend::GdbmBackend;
+use super::lmdb_backend::LmdbBackend;
+use super::memory_backend::MemoryBackend;
+use super::redis_backend::RedisBackend;
+use super::sqlite_backend::SqliteBackend;
+use super::{
+  Dictionary,
+  DictionaryAdapter,
+  DictionaryBackend,
+  TermNormalizer
+};
+
+#[derive(Debug, Clone)]
+pub struct DictionaryConfig {
+  adapter:         DictionaryAdapter,
+  lmdb_path:       Option<PathBuf>,
+  gdbm_path:       Option<PathBuf>,
+  sqlite_path:     Option<PathBuf>,
+  fst_path:        Option<PathBuf>,
+  redis_url:       Option<String>,
+  namespace:       Option<String>,
+  fold_diacritics: bool
+}
+
+impl DictionaryConfig {
+  pub fn new(
+    adapter: DictionaryAdapter
+  ) -> Self {
+    Self {
+      adapter,
+      lmdb_path: None,
+      gdbm_path: None,
+      sqlite_path: None,
+      fst_path: None,
+      redis_url: std::env::var(
+        "CITE_OTTER_REDIS_URL"
+      )
+      .ok()
+      .or_else(|| {
+        std::env::var("REDIS_URL").ok()
+      }),
+      namespace: None,
+      fold_diacritics: false
+    }
+  }
+
+  pub fn with_lmdb_path(
+    mut self,
+    path: impl Into<PathBuf>
+  ) -> Self {
+    self.lmdb_path = Some(path.into());
+    self
+  }
+
+  pub fn with_gdbm_path(
+    mut self,
+    path: impl Into<PathBuf>
+  ) -> Self {
+    self.gdbm_path = Some(path.into());
+    self
+  }
+
+  /// Path of the SQLite database file;
+  /// defaults to
+  /// `target/dictionaries/sqlite/
+  /// places.sqlite3`.
+  pub fn with_sqlite_path(
+    mut self,
+    path: impl Into<PathBuf>
+  ) -> Self {
+    self.sqlite_path =
+      Some(path.into());
+    self
+  }
+
+  /// Path of a compiled dictionary;
+  /// defaults to
+  /// `target/dictionaries/fst/places.
+  /// fst`.
+  pub fn with_fst_path(
+    mut self,
+    path: impl Into<PathBuf>
+  ) -> Self {
+    self.fst_path = Some(path.into());
+    self
+  }
+
+  pub fn with_redis_url(
+    mut self,
+    url: impl Into<String>
+  ) -> Self {
+    self.redis_url = Some(url.into());
+    self
+  }
+
+  pub fn with_namespace(
+    mut self,
+    namespace: impl Into<String>
+  ) -> Self {
+    self.namespace =
+      Some(namespace.into());
+    self
+  }
+
+  /// Fold diacritics in keys of new
+  /// stores. Existing stores keep the
+  /// scheme they were written with.
+  pub fn with_diacritic_folding(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.fold_diacritics = enabled;
+    self
+  }
+
+  fn term_normalizer(
+    &self
+  ) -> TermNormalizer {
+    TermNormalizer::new()
+      .with_diacritic_folding(
+        self.fold_diacritics
+      )
+  }
+
+  /// File or directory the store lives
+  /// in; `None` for the memory and
+  /// redis adapters.
+  pub fn store_path(
+    &self
+  ) -> Option<PathBuf> {
+    let (candidate, dir, file) =
+      match self.adapter {
+        | DictionaryAdapter::Memory
+        | DictionaryAdapter::Redis => {
+          return None;
+        }
+        | DictionaryAdapter::Gdbm => {
+          (
+            self.gdbm_path.as_ref(),
+            "gdbm",
+            "places.db"
+          )
+        }
+        | DictionaryAdapter::Lmdb => {
+          (
+            self.lmdb_path.as_ref(),
+            "lmdb",
+            ""
+          )
+        }
+        | DictionaryAdapter::Sqlite => {
+          (
+            self.sqlite_path.as_ref(),
+            "sqlite",
+            "places.sqlite3"
+          )
+        }
+        | DictionaryAdapter::Fst => {
+          (
+            self.fst_path.as_ref(),
+            "fst",
+            "places.fst"
+          )
+        }
+      };
+    Some(resolve_backend_path(
+      candidate, dir, file
+    ))
+  }
+
+  pub fn open(
+    &self
+  ) -> Result<Dictionary> {
+    let path = || {
+      self
+        .store_path()
+        .context("adapter has no path")
+    };
+    let backend = match self.adapter {
+      | DictionaryAdapter::Memory => {
+        DictionaryBackend::Memory(
+          MemoryBackend::new()
+        )
+      }
+      | DictionaryAdapter::Gdbm => {
+        DictionaryBackend::Gdbm(
+          GdbmBackend::open(&path()?)?
+        )
+      }
+      | DictionaryAdapter::Lmdb => {
+        DictionaryBackend::Lmdb(
+          LmdbBackend::open(&path()?)?
+        )
+      }
+      | DictionaryAdapter::Sqlite => {
+        DictionaryBackend::Sqlite(
+          SqliteBackend::open(&path()?)?
+        )
+      }
+      | DictionaryAdapter::Fst => {
+        DictionaryBackend::Fst(
+          FstBackend::open(&path()?)?
+        )
+      }
+      | DictionaryAdapter::Redis => {
+        let url = self
+          .redis_url
+          .clone()
+          .context(
+            "redis adapter requires \
+             CITE_OTTER_REDIS_URL or \
+             REDIS_URL"
+          )?;
+        let namespace = self
+          .namespace
+          .clone()
+          .unwrap_or_else(|| {
+            "cite-otter:place".into()
+          });
+        DictionaryBackend::Redis(
+          RedisBackend::open(
+            &url, namespace
+          )?
+        )
+      }
+    };
+
+    let mut dictionary = Dictionary {
+      adapter: self.adapter,
+      backend,
+      terms: self.term_normalizer(),
+      keywords: HashMap::new()
+    };
+    dictionary.prepare_store()?;
+    Ok(dictionary)
+  }
+
+  pub fn open_or_memory(
+    &self
+  ) -> Dictionary {
+    self.open().unwrap_or_else(|_| {
+      let mut dictionary = Dictionary {
+        adapter:  self.adapter,
+        backend:
+          DictionaryBackend::Memory(
+            MemoryBackend::new()
+          ),
+        terms:    self
+          .term_normalizer(),
+        keywords: HashMap::new()
+      };
+      dictionary
+        .prepare_store()
+        .expect("memory store seeds");
+      dictionary
+    })
+  }
+}
+
+fn resolve_backend_path(
+  candidate: Option<&PathBuf>,
+  default_dir: &str,
+  default_file: &str
+) -> PathBuf {
+  let base = candidate
+    .cloned()
+    .unwrap_or_else(|| {
+      Path::new("target")
+        .join("dictionaries")
+        .join(default_dir)
+    });
+  if base.extension().is_some()
+    || default_file.is_empty()
+  {
+    base
+  } else {
+    base.join(default_file)
+  }
+}
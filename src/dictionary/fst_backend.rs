@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{
+  BufWriter,
+  Write
+};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{
+  Context,
+  Result,
+  bail
+};
+use fst::automaton::{
+  AlwaysMatch,
+  Levenshtein,
+  Str
+};
+use fst::{
+  Automaton,
+  IntoStreamer,
+  Map,
+  MapBuilder,
+  Streamer
+};
+use memmap2::Mmap;
+
+use super::DictionaryValue;
+
+/// Transducer outputs with this bit set
+/// are offsets into the weights
+/// section; all others are bare code
+/// masks.
+const WEIGHTED: u64 = 1 << 63;
+
+/// Set with [`WEIGHTED`] when the
+/// encoded value ends in a month
+/// number.
+const MONTH: u64 = 1 << 62;
+
+/// Read-only dictionary compiled into a
+/// finite state transducer and mapped
+/// into memory. The file holds the
+/// transducer, the encoded weighted
+/// values and the transducer length as
+/// a little-endian `u64`.
+pub(super) struct FstBackend {
+  map:    Map<MappedSlice>,
+  data:   Arc<Mmap>,
+  values: Range<usize>
+}
+
+struct MappedSlice {
+  data:  Arc<Mmap>,
+  range: Range<usize>
+}
+
+impl AsRef<[u8]> for MappedSlice {
+  fn as_ref(&self) -> &[u8] {
+    &self.data[self.range.clone()]
+  }
+}
+
+impl std::fmt::Debug for FstBackend {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>
+  ) -> std::fmt::Result {
+    f.debug_struct("FstBackend")
+      .field("len", &self.map.len())
+      .finish()
+  }
+}
+
+impl FstBackend {
+  pub(super) fn open(
+    path: &Path
+  ) -> Result<Self> {
+    let file = File::open(path)
+      .with_context(|| {
+        format!(
+          "open fst dictionary {}; \
+           build it with \
+           dictionary-compile",
+          path.display()
+        )
+      })?;
+    // Safety: compiled dictionaries are
+    // replaced by rename, never
+    // rewritten in place.
+    let data = Arc::new(
+      unsafe { Mmap::map(&file) }
+        .context(
+          "map fst dictionary"
+        )?
+    );
+    let footer = data
+      .len()
+      .checked_sub(8)
+      .context(
+        "fst dictionary is truncated"
+      )?;
+    let mut length = [0u8; 8];
+    length
+      .copy_from_slice(&data[footer..]);
+    let length = usize::try_from(
+      u64::from_le_bytes(length)
+    )?;
+    if length > footer {
+      bail!(
+        "fst dictionary is truncated"
+      );
+    }
+    let map = Map::new(MappedSlice {
+      data:  Arc::clone(&data),
+      range: 0..length
+    })
+    .context("read fst dictionary")?;
+    Ok(Self {
+      map,
+      data,
+      values: length..footer
+    })
+  }
+
+  /// Writes `entries` to `path`,
+  /// replacing any previous file.
+  /// Returns the number of keys.
+  pub(super) fn write(
+    path: &Path,
+    entries: &BTreeMap<
+      String,
+      DictionaryValue
+    >
+  ) -> Result<usize> {
+    if let Some(parent) = path.parent()
+      && !parent.as_os_str().is_empty()
+    {
+      std::fs::create_dir_all(parent)?;
+    }
+    let staging =
+      path.with_extension("fst.tmp");
+    {
+      let mut values = Vec::new();
+      let mut builder =
+        MapBuilder::memory();
+      for (term, value) in entries {
+        let output = if !value.is_bare()
+        {
+          let offset =
+            values.len() as u64;
+          values.extend(value.bytes());
+          let month = if value
+            .month()
+            .is_some()
+          {
+            MONTH
+          } else {
+            0
+          };
+          WEIGHTED | month | offset
+        } else {
+          u64::from(value.mask())
+        };
+        builder.insert(term, output)?;
+      }
+      let transducer =
+        builder.into_inner()?;
+      let mut writer = BufWriter::new(
+        File::create(&staging)?
+      );
+      writer.write_all(&transducer)?;
+      writer.write_all(&values)?;
+      writer.write_all(
+        &(transducer.len() as u64)
+          .to_le_bytes()
+      )?;
+      writer.flush()?;
+    }
+    std::fs::rename(&staging, path)
+      .context(
+        "replace fst dictionary"
+      )?;
+    Ok(entries.len())
+  }
+
+  pub(super) fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    self.map.get(term).and_then(
+      |output| self.decode(output)
+    )
+  }
+
+  fn decode(
+    &self,
+    output: u64
+  ) -> Option<DictionaryValue> {
+    if output & WEIGHTED == 0 {
+      return Some(
+        DictionaryValue::from_mask(
+          output as u32
+        )
+      );
+    }
+    let start = self.values.start
+      + usize::try_from(
+        output & !(WEIGHTED | MONTH)
+      )
+      .ok()?;
+    let header = self
+      .data
+      .get(start..start + 4)?;
+    let mut mask = [0u8; 4];
+    mask.copy_from_slice(header);
+    let count = u32::from_le_bytes(mask)
+      .count_ones()
+      as usize;
+    let end = start
+      + 4
+      + 4 * count
+      + usize::from(
+        output & MONTH != 0
+      );
+    if end > self.values.end {
+      return None;
+    }
+    DictionaryValue::from_bytes(
+      &self.data[start..end]
+    )
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Vec<(String, DictionaryValue)>
+  {
+    self.search(AlwaysMatch)
+  }
+
+  /// Only accepts entries already in
+  /// the file, so opening seeds nothing
+  /// new.
+  pub(super) fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    for (term, value) in entries {
+      let unchanged = self
+        .get_value(term)
+        .is_some_and(|existing| {
+          existing.merge(value)
+            == existing
+        });
+      if !unchanged {
+        bail!(
+          "fst dictionaries are \
+           read-only; rebuild with \
+           dictionary-compile"
+        );
+      }
+    }
+    Ok(0)
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> Result<usize> {
+    bail!(
+      "fst dictionaries are \
+       read-only; rebuild with \
+       dictionary-compile"
+    )
+  }
+
+  pub(super) fn prefix(
+    &self,
+    prefix: &str
+  ) -> Vec<(String, DictionaryValue)>
+  {
+    self.search(
+      Str::new(prefix).starts_with()
+    )
+  }
+
+  pub(super) fn fuzzy(
+    &self,
+    term: &str,
+    distance: u32
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    let automaton =
+      Levenshtein::new(term, distance)
+        .context(
+          "build levenshtein automaton"
+        )?;
+    Ok(self.search(automaton))
+  }
+
+  fn search<A: Automaton>(
+    &self,
+    automaton: A
+  ) -> Vec<(String, DictionaryValue)>
+  {
+    let mut stream = self
+      .map
+      .search(automaton)
+      .into_stream();
+    let mut matches = Vec::new();
+    while let Some((key, output)) =
+      stream.next()
+    {
+      if let Ok(term) =
+        std::str::from_utf8(key)
+        && let Some(value) =
+          self.decode(output)
+      {
+        matches.push((
+          term.to_string(),
+          value
+        ));
+      }
+    }
+    matches
+  }
+}
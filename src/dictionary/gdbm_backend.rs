@@ -0,0 +1,200 @@
+use std::path::Path;
+
+#[cfg(feature = "gdbm")]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(not(feature = "gdbm"))]
+use anyhow::anyhow;
+#[cfg(feature = "gdbm")]
+use gnudbm::{
+  Error as GdbmError,
+  GdbmOpener
+};
+
+use super::DictionaryValue;
+
+#[cfg(feature = "gdbm")]
+#[derive(Debug)]
+pub(super) struct GdbmBackend {
+  handle: gnudbm::RwHandle
+}
+
+#[cfg(feature = "gdbm")]
+impl GdbmBackend {
+  pub(super) fn open(
+    path: &Path
+  ) -> Result<Self> {
+    if let Some(parent) = path.parent()
+    {
+      std::fs::create_dir_all(parent)?;
+    }
+    let handle = GdbmOpener::new()
+      .create(true)
+      .readwrite(path)
+      .context("open gdbm database")?;
+    Ok(Self {
+      handle
+    })
+  }
+
+  pub(super) fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    match self.handle.fetch(term) {
+      | Ok(bytes) => {
+        DictionaryValue::from_bytes(
+          bytes.as_bytes()
+        )
+      }
+      | Err(GdbmError::NoRecord) => {
+        None
+      }
+      | Err(_) => None
+    }
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    Ok(
+      self
+        .handle
+        .iter()
+        .filter_map(|(key, bytes)| {
+          let term =
+            std::str::from_utf8(
+              key.as_bytes()
+            )
+            .ok()?;
+          DictionaryValue::from_bytes(
+            bytes.as_bytes()
+          )
+          .map(|value| {
+            (term.to_string(), value)
+          })
+        })
+        .collect()
+    )
+  }
+
+  pub(super) fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    let mut updated = 0usize;
+    for (term, value) in entries {
+      let existing =
+        match self.handle.fetch(term) {
+          | Ok(bytes) => {
+            DictionaryValue::from_bytes(
+              bytes.as_bytes()
+            )
+          }
+          | Err(
+            GdbmError::NoRecord
+          ) => None,
+          | Err(_) => None
+        };
+      let merged = existing
+        .as_ref()
+        .map(|existing| {
+          existing.merge(value)
+        })
+        .unwrap_or_else(|| {
+          value.clone()
+        });
+      if existing.as_ref()
+        != Some(&merged)
+      {
+        let encoded = merged.bytes();
+        let _ = self.handle.store(
+          term,
+          encoded.as_slice()
+        );
+        updated += 1;
+      }
+    }
+    Ok(updated)
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> Result<usize> {
+    let keys = self
+      .handle
+      .iter()
+      .map(|(key, _)| {
+        key.as_bytes().to_vec()
+      })
+      .collect::<Vec<_>>();
+    for key in &keys {
+      self
+        .handle
+        .remove(key.as_slice())
+        .context("remove gdbm key")?;
+    }
+    Ok(keys.len())
+  }
+}
+
+#[cfg(not(feature = "gdbm"))]
+#[derive(Debug)]
+pub(super) struct GdbmBackend;
+
+#[cfg(not(feature = "gdbm"))]
+impl GdbmBackend {
+  pub(super) fn open(
+    _path: &Path
+  ) -> Result<Self> {
+    Err(anyhow!(
+      "gdbm support not enabled; \
+       recompile with --features gdbm"
+    ))
+  }
+
+  pub(super) fn get_value(
+    &self,
+    _term: &str
+  ) -> Option<DictionaryValue> {
+    None
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    Err(anyhow!(
+      "gdbm support not enabled; \
+       recompile with --features gdbm"
+    ))
+  }
+
+  pub(super) fn merge_entries(
+    &mut self,
+    _entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    Err(anyhow!(
+      "gdbm support not enabled; \
+       recompile with --features gdbm"
+    ))
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> Result<usize> {
+    Err(anyhow!(
+      "gdbm support not enabled; \
+       recompile with --features gdbm"
+    ))
+  }
+}
@@ -0,0 +1,175 @@
+use std::cmp::Ordering;
+
+use super::{
+  DictionaryCode,
+  DictionaryMatch
+};
+
+/// Counts reported by
+/// `dictionary-stats`.
+#[derive(
+  Debug, Clone, Default, PartialEq,
+)]
+pub struct DictionaryStats {
+  pub terms:    usize,
+  /// Terms carrying each code.
+  pub codes:
+    Vec<(DictionaryCode, usize)>,
+  /// Terms carrying both codes of a
+  /// pair, e.g. names that are also
+  /// places.
+  pub overlaps: Vec<(
+    DictionaryCode,
+    DictionaryCode,
+    usize
+  )>
+}
+
+const CODES: usize =
+  DictionaryCode::ALL.len();
+
+impl DictionaryStats {
+  pub fn from_entries(
+    entries: &[DictionaryMatch]
+  ) -> Self {
+    let all = DictionaryCode::ALL;
+    let mut counts = [0usize; CODES];
+    let mut pairs =
+      [[0usize; CODES]; CODES];
+    for entry in entries {
+      let present = all.map(|code| {
+        entry.codes.contains(&code)
+      });
+      for (left, has_left) in
+        present.iter().enumerate()
+      {
+        if !has_left {
+          continue;
+        }
+        counts[left] += 1;
+        for (right, has_right) in
+          present
+            .iter()
+            .enumerate()
+            .skip(left + 1)
+        {
+          if *has_right {
+            pairs[left][right] += 1;
+          }
+        }
+      }
+    }
+    let codes = all
+      .into_iter()
+      .zip(counts)
+      .filter(|(_, count)| *count > 0)
+      .collect();
+    let mut overlaps = Vec::new();
+    for (left, row) in
+      pairs.iter().enumerate()
+    {
+      for (right, count) in
+        row.iter().enumerate()
+      {
+        if *count > 0 {
+          overlaps.push((
+            all[left], all[right],
+            *count
+          ));
+        }
+      }
+    }
+    Self {
+      terms: entries.len(),
+      codes,
+      overlaps
+    }
+  }
+
+  pub fn count(
+    &self,
+    code: DictionaryCode
+  ) -> usize {
+    self
+      .codes
+      .iter()
+      .find(|(found, _)| *found == code)
+      .map_or(0, |(_, count)| *count)
+  }
+}
+
+/// Differences reported by
+/// `dictionary-diff`.
+#[derive(
+  Debug, Clone, Default, PartialEq,
+)]
+pub struct DictionaryDiff {
+  pub only_left:  Vec<DictionaryMatch>,
+  pub only_right: Vec<DictionaryMatch>,
+  /// Terms in both stores with
+  /// different codes or weights, left
+  /// first.
+  pub changed: Vec<(
+    DictionaryMatch,
+    DictionaryMatch
+  )>
+}
+
+impl DictionaryDiff {
+  /// Compares two key-sorted entry
+  /// lists.
+  pub fn between(
+    left: Vec<DictionaryMatch>,
+    right: Vec<DictionaryMatch>
+  ) -> Self {
+    let mut diff = Self::default();
+    let mut left =
+      left.into_iter().peekable();
+    let mut right =
+      right.into_iter().peekable();
+    loop {
+      let order = match (
+        left.peek(),
+        right.peek()
+      ) {
+        | (Some(l), Some(r)) => {
+          l.term.cmp(&r.term)
+        }
+        | (Some(_), None) => {
+          Ordering::Less
+        }
+        | (None, Some(_)) => {
+          Ordering::Greater
+        }
+        | (None, None) => break
+      };
+      match order {
+        | Ordering::Less => {
+          diff
+            .only_left
+            .extend(left.next());
+        }
+        | Ordering::Greater => {
+          diff
+            .only_right
+            .extend(right.next());
+        }
+        | Ordering::Equal => {
+          if let (Some(l), Some(r)) =
+            (left.next(), right.next())
+            && l.weights != r.weights
+          {
+            diff.changed.push((l, r));
+          }
+        }
+      }
+    }
+    diff
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.only_left.is_empty()
+      && self.only_right.is_empty()
+      && self.changed.is_empty()
+  }
+}
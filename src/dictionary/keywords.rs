@@ -0,0 +1,279 @@
+//! Built-in keyword terms for the
+//! month, role, genre and locator
+//! codes. They seed the keyword layer
+//! every dictionary answers lookups
+//! from, beneath its stored entries;
+//! the parser reads keywords through
+//! the dictionary, so imports extend
+//! these terms and deny lists remove
+//! them.
+
+use std::collections::HashMap;
+
+use super::DictionaryCode;
+use super::terms::TermNormalizer;
+use super::value::DictionaryValue;
+use crate::normalizer::volume::{
+  self,
+  Marker
+};
+
+/// Month names and abbreviations with
+/// their number. Short forms that are
+/// also common names ("Mai", "Mars",
+/// "Julio") are left to imported lists.
+pub static MONTHS: &[(&str, u32)] = &[
+  ("january", 1),
+  ("jan", 1),
+  ("february", 2),
+  ("feb", 2),
+  ("march", 3),
+  ("mar", 3),
+  ("april", 4),
+  ("apr", 4),
+  ("may", 5),
+  ("june", 6),
+  ("jun", 6),
+  ("july", 7),
+  ("jul", 7),
+  ("august", 8),
+  ("aug", 8),
+  ("september", 9),
+  ("sep", 9),
+  ("sept", 9),
+  ("october", 10),
+  ("oct", 10),
+  ("november", 11),
+  ("nov", 11),
+  ("december", 12),
+  ("dec", 12),
+  ("januar", 1),
+  ("februar", 2),
+  ("märz", 3),
+  ("juni", 6),
+  ("juli", 7),
+  ("oktober", 10),
+  ("dezember", 12),
+  ("janvier", 1),
+  ("février", 2),
+  ("avril", 4),
+  ("juin", 6),
+  ("juillet", 7),
+  ("août", 8),
+  ("septembre", 9),
+  ("octobre", 10),
+  ("novembre", 11),
+  ("décembre", 12),
+  ("enero", 1),
+  ("febrero", 2),
+  ("marzo", 3),
+  ("abril", 4),
+  ("junio", 6),
+  ("agosto", 8),
+  ("septiembre", 9),
+  ("setiembre", 9),
+  ("octubre", 10),
+  ("noviembre", 11),
+  ("diciembre", 12)
+];
+
+/// Phrases introducing editors,
+/// searched for anywhere in a
+/// reference.
+pub static EDITOR_PHRASES: &[&str] = &[
+  "edited by",
+  "edited",
+  "editor",
+  "eds",
+  "herausgegeben von"
+];
+
+/// Editor abbreviations that only count
+/// as whole tokens.
+pub static EDITOR_ABBREVIATIONS:
+  &[&str] = &[
+  "ed", "eds", "hrsg", "hg", "éd",
+  "éds"
+];
+
+/// Phrases introducing translators.
+pub static TRANSLATOR_PHRASES:
+  &[&str] = &[
+  "translated by",
+  "translator",
+  "trans.",
+  "übersetzt von",
+  "übers.",
+  "traduit par"
+];
+
+/// Words marking a thesis.
+pub static GENRE_TERMS: &[&str] = &[
+  "thesis",
+  "dissertation",
+  "habilitationsschrift",
+  "thèse",
+  "tesis"
+];
+
+/// Page locators, compared as whole
+/// tokens without their trailing
+/// period.
+pub static PAGE_LOCATORS: &[&str] =
+  &["p", "pp", "page", "pages"];
+
+/// Volume locators searched for
+/// anywhere in a reference; the
+/// language-specific forms live in
+/// [`volume::KEYWORDS`].
+pub static VOLUME_LOCATORS: &[&str] = &[
+  "volume", "vol.", "vol", "v.", "vols"
+];
+
+/// Month number of a month name or
+/// abbreviation ("Sept.", "octobre").
+pub fn month_number(
+  token: &str
+) -> Option<u32> {
+  let lower = token
+    .trim_end_matches(['.', ','])
+    .to_lowercase();
+  MONTHS.iter().find_map(
+    |(word, month)| {
+      (*word == lower).then_some(*month)
+    }
+  )
+}
+
+/// Whether `token` is one of the
+/// built-in `list` entries, ignoring
+/// case and a trailing period.
+pub fn is_keyword(
+  list: &[&str],
+  token: &str
+) -> bool {
+  let lower = token
+    .trim_end_matches('.')
+    .to_lowercase();
+  list.iter().any(|word| {
+    word.trim_end_matches('.') == lower
+  })
+}
+
+/// Whether `token` is a built-in term
+/// of `code`; extraction without a
+/// dictionary reads keywords from
+/// here.
+pub fn is_builtin(
+  code: DictionaryCode,
+  token: &str
+) -> bool {
+  match code {
+    | DictionaryCode::Month => {
+      month_number(token).is_some()
+    }
+    | _ => {
+      builtin_terms(code).any(|word| {
+        is_keyword(&[word], token)
+      })
+    }
+  }
+}
+
+/// Built-in terms of the role, genre
+/// and locator codes.
+fn builtin_terms(
+  code: DictionaryCode
+) -> impl Iterator<Item = &'static str>
+{
+  let lists: Vec<
+    &'static [&'static str]
+  > = match code {
+    | DictionaryCode::Editor => {
+      vec![
+        EDITOR_PHRASES,
+        EDITOR_ABBREVIATIONS,
+      ]
+    }
+    | DictionaryCode::Translator => {
+      vec![TRANSLATOR_PHRASES]
+    }
+    | DictionaryCode::Genre => {
+      vec![GENRE_TERMS]
+    }
+    | DictionaryCode::PageLocator => {
+      vec![PAGE_LOCATORS]
+    }
+    | DictionaryCode::VolumeLocator => {
+      vec![VOLUME_LOCATORS]
+    }
+    | _ => Vec::new()
+  };
+  let volume_terms = volume::KEYWORDS
+    .iter()
+    .filter(move |(_, marker, _)| {
+      code
+        == DictionaryCode::VolumeLocator
+        && *marker == Marker::Volume
+    })
+    .map(|(word, ..)| *word);
+  lists
+    .into_iter()
+    .flat_map(|list| {
+      list.iter().copied()
+    })
+    .chain(volume_terms)
+}
+
+/// Values of the built-in terms keyed
+/// like `terms` keys lookups. Phrases
+/// spanning several keys are skipped;
+/// month terms carry their number.
+pub(super) fn layer(
+  terms: &TermNormalizer
+) -> HashMap<String, DictionaryValue> {
+  let entries = MONTHS
+    .iter()
+    .map(|(word, month)| {
+      (
+        *word,
+        DictionaryValue::for_month(
+          *month as u8
+        )
+      )
+    })
+    .chain(
+      DictionaryCode::ALL
+        .into_iter()
+        .flat_map(|code| {
+          builtin_terms(code).map(
+            move |word| {
+              (
+                word,
+                DictionaryValue::from_mask(
+                  code.bit()
+                )
+              )
+            }
+          )
+        })
+    );
+
+  let mut layer: HashMap<
+    String,
+    DictionaryValue
+  > = HashMap::new();
+  for (word, value) in entries {
+    let keys = terms.terms(word);
+    let [key] = keys.as_slice() else {
+      continue;
+    };
+    layer
+      .entry(key.clone())
+      .and_modify(|merged| {
+        *merged = merged.merge(&value)
+      })
+      .or_insert(value);
+  }
+  layer
+}
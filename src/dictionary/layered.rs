@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use super::{
+  Dictionary,
+  DictionaryCode,
+  DictionaryValue,
+  DictionaryWeights,
+  TermNormalizer
+};
+
+/// Read access shared by [`Dictionary`]
+/// and [`LayeredDictionary`]; the
+/// parser and normalizers only look
+/// terms up.
+pub trait DictionaryLookup {
+  /// Codes of `term` with their
+  /// weights.
+  fn lookup_weights(
+    &self,
+    term: &str
+  ) -> DictionaryWeights;
+
+  fn lookup(
+    &self,
+    term: &str
+  ) -> Vec<DictionaryCode> {
+    self.lookup_weights(term).codes()
+  }
+
+  /// Weights of every term; stores that
+  /// batch reads answer in one round
+  /// trip.
+  fn lookup_many(
+    &self,
+    terms: &[&str]
+  ) -> Vec<DictionaryWeights> {
+    terms
+      .iter()
+      .map(|term| {
+        self.lookup_weights(term)
+      })
+      .collect()
+  }
+}
+
+impl DictionaryLookup for Dictionary {
+  fn lookup_weights(
+    &self,
+    term: &str
+  ) -> DictionaryWeights {
+    Dictionary::lookup_weights(
+      self, term
+    )
+  }
+
+  fn lookup_many(
+    &self,
+    terms: &[&str]
+  ) -> Vec<DictionaryWeights> {
+    Dictionary::lookup_many(self, terms)
+  }
+}
+
+/// A base dictionary with overlays on
+/// top. Every layer adds codes; the
+/// deny list removes codes of a term
+/// from all layers, so a project can
+/// keep "Jordan" a name but not a
+/// place in a shared base.
+#[derive(Debug)]
+pub struct LayeredDictionary {
+  layers: Vec<Dictionary>,
+  terms:  TermNormalizer,
+  /// Denied code masks by the keys of
+  /// a whole term, joined by spaces.
+  denied: HashMap<String, u32>
+}
+
+impl LayeredDictionary {
+  /// Keys of the deny list follow the
+  /// base dictionary's normalizer.
+  pub fn new(base: Dictionary) -> Self {
+    Self {
+      terms:  base.term_normalizer(),
+      layers: vec![base],
+      denied: HashMap::new()
+    }
+  }
+
+  pub fn with_layer(
+    mut self,
+    overlay: Dictionary
+  ) -> Self {
+    self.layers.push(overlay);
+    self
+  }
+
+  /// Suppresses `codes` of `term`, or
+  /// the whole term when `codes` is
+  /// empty. Multi-word terms are denied
+  /// as a whole: denying "New York"
+  /// leaves "York" alone.
+  pub fn with_denied(
+    mut self,
+    term: &str,
+    codes: &[DictionaryCode]
+  ) -> Self {
+    self.deny(term, codes);
+    self
+  }
+
+  pub fn deny(
+    &mut self,
+    term: &str,
+    codes: &[DictionaryCode]
+  ) {
+    let mask = if codes.is_empty() {
+      u32::MAX
+    } else {
+      codes
+        .iter()
+        .fold(0, |mask, code| {
+          mask | code.bit()
+        })
+    };
+    let keys = self.terms.terms(term);
+    if keys.is_empty() {
+      return;
+    }
+    *self
+      .denied
+      .entry(keys.join(" "))
+      .or_default() |= mask;
+  }
+
+  pub fn layers(
+    &self
+  ) -> &[Dictionary] {
+    &self.layers
+  }
+
+  pub fn lookup_weights(
+    &self,
+    term: &str
+  ) -> DictionaryWeights {
+    let keys = self.terms.terms(term);
+    let value = keys
+      .iter()
+      .filter_map(|key| {
+        self.key_value(key)
+      })
+      .reduce(|merged, found| {
+        merged.merge(&found)
+      })
+      .unwrap_or_else(|| {
+        DictionaryValue::from_mask(0)
+      });
+    DictionaryWeights::from_value(
+      &self.allowed(&keys, &value)
+    )
+  }
+
+  pub fn lookup(
+    &self,
+    term: &str
+  ) -> Vec<DictionaryCode> {
+    self.lookup_weights(term).codes()
+  }
+
+  /// [`LayeredDictionary::lookup_weights`]
+  /// of every term with one batch read
+  /// per layer.
+  pub fn lookup_many(
+    &self,
+    terms: &[&str]
+  ) -> Vec<DictionaryWeights> {
+    let term_keys = terms
+      .iter()
+      .map(|term| {
+        self.terms.terms(term)
+      })
+      .collect::<Vec<_>>();
+    let mut values = HashMap::<
+      String,
+      DictionaryValue
+    >::new();
+    for layer in &self.layers {
+      let layer_keys = term_keys
+        .iter()
+        .flatten()
+        .map(|key| {
+          (key, layer.terms.terms(key))
+        })
+        .collect::<Vec<_>>();
+      let found = layer.key_values(
+        &layer_keys
+          .iter()
+          .map(|(_, keys)| keys.clone())
+          .collect::<Vec<_>>()
+      );
+      for (key, keys) in &layer_keys {
+        for value in keys
+          .iter()
+          .filter_map(|layer_key| {
+            found
+              .get(layer_key)?
+              .as_ref()
+          })
+        {
+          let merged =
+            match values.get(*key) {
+              | Some(existing) => {
+                existing.merge(value)
+              }
+              | None => value.clone()
+            };
+          values.insert(
+            (*key).clone(),
+            merged
+          );
+        }
+      }
+    }
+    term_keys
+      .iter()
+      .map(|keys| {
+        let value = keys
+          .iter()
+          .filter_map(|key| {
+            values.get(key).cloned()
+          })
+          .reduce(|merged, found| {
+            merged.merge(&found)
+          })
+          .unwrap_or_else(|| {
+            DictionaryValue::from_mask(
+              0
+            )
+          });
+        DictionaryWeights::from_value(
+          &self.allowed(keys, &value)
+        )
+      })
+      .collect()
+  }
+
+  /// `value` of the term with `keys`
+  /// without its denied codes.
+  fn allowed(
+    &self,
+    keys: &[String],
+    value: &DictionaryValue
+  ) -> DictionaryValue {
+    if self.denied.is_empty() {
+      return value.clone();
+    }
+    match self
+      .denied
+      .get(&keys.join(" "))
+    {
+      | Some(mask) => {
+        value.without(*mask)
+      }
+      | None => value.clone()
+    }
+  }
+
+  /// Merged value of a key over all
+  /// layers, each keyed by its own
+  /// normalizer.
+  fn key_value(
+    &self,
+    key: &str
+  ) -> Option<DictionaryValue> {
+    self
+      .layers
+      .iter()
+      .flat_map(|layer| {
+        layer
+          .terms
+          .terms(key)
+          .into_iter()
+          .filter_map(|layer_key| {
+            layer.key_value(&layer_key)
+          })
+      })
+      .reduce(|merged, found| {
+        merged.merge(&found)
+      })
+  }
+}
+
+impl From<Dictionary>
+  for LayeredDictionary
+{
+  fn from(base: Dictionary) -> Self {
+    Self::new(base)
+  }
+}
+
+impl DictionaryLookup
+  for LayeredDictionary
+{
+  fn lookup_weights(
+    &self,
+    term: &str
+  ) -> DictionaryWeights {
+    LayeredDictionary::lookup_weights(
+      self, term
+    )
+  }
+
+  fn lookup_many(
+    &self,
+    terms: &[&str]
+  ) -> Vec<DictionaryWeights> {
+    LayeredDictionary::lookup_many(
+      self, terms
+    )
+  }
+}
@@ -0,0 +1,200 @@
+//! Harvests dictionary entries from
+//! tagged AnyStyle parser datasets.
+
+use std::collections::{
+  BTreeMap,
+  HashSet
+};
+
+use anyhow::{
+  Context,
+  Result
+};
+
+use super::{
+  DictionaryCode,
+  TermNormalizer
+};
+
+/// Words that never become entries:
+/// connectives of author lists and
+/// function words of journal titles.
+pub static STOP_WORDS: &[&str] = &[
+  "and", "&", "et", "al", "of", "the",
+  "for", "in", "on", "a", "an", "to",
+  "de", "la", "le", "des", "du", "der",
+  "die", "und", "van", "von", "jr",
+  "ed", "eds"
+];
+
+/// Counts the words of labelled
+/// author, editor, location, publisher
+/// and journal spans. Keys follow the
+/// target store's [`TermNormalizer`];
+/// counts become entry weights.
+#[derive(Debug, Clone)]
+pub struct DictionaryLearner {
+  terms:      TermNormalizer,
+  min_count:  usize,
+  stop_words: HashSet<String>,
+  counts: BTreeMap<
+    (String, DictionaryCode),
+    usize
+  >,
+  sequences:  usize
+}
+
+impl DictionaryLearner {
+  pub fn new(
+    terms: TermNormalizer
+  ) -> Self {
+    Self {
+      terms,
+      min_count: 2,
+      stop_words: STOP_WORDS
+        .iter()
+        .map(|word| word.to_string())
+        .collect(),
+      counts: BTreeMap::new(),
+      sequences: 0
+    }
+  }
+
+  /// Drops keys seen fewer than `count`
+  /// times for a code (default 2).
+  pub fn with_min_count(
+    mut self,
+    count: usize
+  ) -> Self {
+    self.min_count = count.max(1);
+    self
+  }
+
+  /// Adds words to the stop list.
+  pub fn with_stop_words(
+    mut self,
+    words: impl IntoIterator<Item = String>
+  ) -> Self {
+    for word in words {
+      self.stop_words.extend(
+        self.terms.terms(&word)
+      );
+    }
+    self
+  }
+
+  /// Reads one `<dataset>` of tagged
+  /// `<sequence>` elements; returns the
+  /// number of sequences read.
+  pub fn add_dataset(
+    &mut self,
+    xml: &str
+  ) -> Result<usize> {
+    let document =
+      roxmltree::Document::parse(xml)
+        .context(
+        "parse tagged dataset"
+      )?;
+    let mut read = 0usize;
+    for sequence in document
+      .descendants()
+      .filter(|node| {
+        node.has_tag_name("sequence")
+      })
+    {
+      for span in
+        sequence.children().filter(
+          |node| node.is_element()
+        )
+      {
+        let Some(code) = span_code(
+          span.tag_name().name()
+        ) else {
+          continue;
+        };
+        let text = span
+          .descendants()
+          .filter(|node| node.is_text())
+          .filter_map(|node| {
+            node.text()
+          })
+          .collect::<Vec<_>>()
+          .join(" ");
+        self.add_span(code, &text);
+      }
+      read += 1;
+    }
+    self.sequences += read;
+    Ok(read)
+  }
+
+  fn add_span(
+    &mut self,
+    code: DictionaryCode,
+    text: &str
+  ) {
+    for key in self.terms.terms(text) {
+      if key.chars().count() < 2
+        || key
+          .chars()
+          .any(|c| c.is_numeric())
+        || self
+          .stop_words
+          .contains(&key)
+      {
+        continue;
+      }
+      *self
+        .counts
+        .entry((key, code))
+        .or_default() += 1;
+    }
+  }
+
+  pub fn sequences(&self) -> usize {
+    self.sequences
+  }
+
+  /// Entries meeting the minimum count,
+  /// weighted by their count.
+  pub fn entries(
+    &self
+  ) -> Vec<(String, DictionaryCode, f32)>
+  {
+    self
+      .counts
+      .iter()
+      .filter(|(_, count)| {
+        **count >= self.min_count
+      })
+      .map(|((key, code), count)| {
+        (
+          key.clone(),
+          *code,
+          *count as f32
+        )
+      })
+      .collect()
+  }
+}
+
+fn span_code(
+  tag: &str
+) -> Option<DictionaryCode> {
+  match tag {
+    | "author" | "editor"
+    | "translator" => {
+      Some(DictionaryCode::Name)
+    }
+    | "location" => {
+      Some(DictionaryCode::Place)
+    }
+    | "publisher" => {
+      Some(DictionaryCode::Publisher)
+    }
+    | "journal" => {
+      Some(DictionaryCode::Journal)
+    }
+    | _ => None
+  }
+}
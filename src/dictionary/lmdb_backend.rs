@@ -0,0 +1,138 @@
+use std::path::Path;
+
+use anyhow::{
+  Context,
+  Result
+};
+use lmdb::{
+  Cursor,
+  Transaction
+};
+
+use super::DictionaryValue;
+
+#[derive(Debug)]
+pub(super) struct LmdbBackend {
+  env: lmdb::Environment,
+  db:  lmdb::Database
+}
+
+impl LmdbBackend {
+  pub(super) fn open(
+    path: &Path
+  ) -> Result<Self> {
+    std::fs::create_dir_all(path)?;
+    let env = lmdb::Environment::new()
+      .set_max_dbs(1)
+      .set_map_size(10 * 1024 * 1024)
+      .open(path)
+      .context(
+        "open lmdb environment"
+      )?;
+    let db = env
+      .create_db(
+        Some("places"),
+        lmdb::DatabaseFlags::empty()
+      )
+      .context("create lmdb db")?;
+    Ok(Self {
+      env,
+      db
+    })
+  }
+
+  pub(super) fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    let txn =
+      self.env.begin_ro_txn().ok()?;
+    let key = term.to_string();
+    let bytes =
+      txn.get(self.db, &key).ok()?;
+    DictionaryValue::from_bytes(bytes)
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    let txn =
+      self.env.begin_ro_txn()?;
+    let mut cursor =
+      txn.open_ro_cursor(self.db)?;
+    let entries = cursor
+      .iter_start()
+      .filter_map(|(key, bytes)| {
+        let term =
+          std::str::from_utf8(key)
+            .ok()?;
+        DictionaryValue::from_bytes(
+          bytes
+        )
+        .map(|value| {
+          (term.to_string(), value)
+        })
+      })
+      .collect();
+    Ok(entries)
+  }
+
+  pub(super) fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    let mut txn =
+      self.env.begin_rw_txn()?;
+    let mut inserted = 0usize;
+    for (term, value) in entries {
+      let existing = txn
+        .get(self.db, term)
+        .ok()
+        .and_then(
+          DictionaryValue::from_bytes
+        );
+      let merged = existing
+        .as_ref()
+        .map(|existing| {
+          existing.merge(value)
+        })
+        .unwrap_or_else(|| {
+          value.clone()
+        });
+      if existing.as_ref()
+        != Some(&merged)
+      {
+        let encoded = merged.bytes();
+        txn.put(
+          self.db,
+          term,
+          &encoded,
+          lmdb::WriteFlags::empty()
+        )?;
+        inserted += 1;
+      }
+    }
+    txn.commit()?;
+    Ok(inserted)
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> Result<usize> {
+    let mut txn =
+      self.env.begin_rw_txn()?;
+    let cleared = {
+      let mut cursor =
+        txn.open_ro_cursor(self.db)?;
+      cursor.iter_start().count()
+    };
+    txn.clear_db(self.db)?;
+    txn.commit()?;
+    Ok(cleared)
+  }
+}
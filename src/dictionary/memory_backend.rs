@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use super::DictionaryValue;
+
+#[derive(Debug)]
+pub(super) struct MemoryBackend {
+  entries:
+    HashMap<String, DictionaryValue>
+}
+
+impl MemoryBackend {
+  pub(super) fn new() -> Self {
+    Self {
+      entries: HashMap::new()
+    }
+  }
+
+  pub(super) fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    self.entries.get(term).cloned()
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Vec<(String, DictionaryValue)>
+  {
+    self
+      .entries
+      .iter()
+      .map(|(term, value)| {
+        (term.clone(), value.clone())
+      })
+      .collect()
+  }
+
+  pub(super) fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> usize {
+    let mut updated = 0usize;
+    for (term, value) in entries {
+      let next =
+        match self.entries.get(term) {
+          | Some(existing) => {
+            existing.merge(value)
+          }
+          | None => value.clone()
+        };
+      if self.entries.get(term)
+        != Some(&next)
+      {
+        self
+          .entries
+          .insert(term.clone(), next);
+        updated += 1;
+      }
+    }
+    updated
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> usize {
+    let cleared = self.entries.len();
+    self.entries.clear();
+    cleared
+  }
+}
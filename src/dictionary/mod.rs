@@ -0,0 +1,884 @@
+use std::collections::{
+  BTreeMap,
+  BTreeSet,
+  HashMap
+};
+use std::path::Path;
+
+use anyhow::Result;
+
+mod code;
+mod config;
+mod fst_backend;
+mod gdbm_backend;
+mod inspect;
+pub mod keywords;
+mod layered;
+mod learn;
+mod lmdb_backend;
+mod memory_backend;
+mod redis_backend;
+mod sqlite_backend;
+mod terms;
+mod value;
+
+pub use code::DictionaryCode;
+pub use config::DictionaryConfig;
+use fst_backend::FstBackend;
+use gdbm_backend::GdbmBackend;
+pub use inspect::{
+  DictionaryDiff,
+  DictionaryStats
+};
+pub use layered::{
+  DictionaryLookup,
+  LayeredDictionary
+};
+pub use learn::{
+  DictionaryLearner,
+  STOP_WORDS
+};
+use lmdb_backend::LmdbBackend;
+use memory_backend::MemoryBackend;
+use redis_backend::RedisBackend;
+use sqlite_backend::SqliteBackend;
+use terms::SCHEME_KEY;
+pub use terms::{
+  KeyScheme,
+  TermNormalizer
+};
+use value::DictionaryValue;
+pub use value::DictionaryWeights;
+
+#[derive(Debug, Clone, Copy)]
+pub enum DictionaryAdapter {
+  Memory,
+  Redis,
+  Lmdb,
+  Gdbm,
+  Sqlite,
+  /// Read-only transducer written by
+  /// [`Dictionary::compile`].
+  Fst
+}
+
+/// A stored key with its codes, as
+/// found by prefix and fuzzy queries or
+/// listed by [`Dictionary::entries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictionaryMatch {
+  pub term:    String,
+  pub codes:   Vec<DictionaryCode>,
+  pub weights: DictionaryWeights
+}
+
+impl DictionaryMatch {
+  fn collect(
+    matches: Vec<(
+      String,
+      DictionaryValue
+    )>
+  ) -> Vec<Self> {
+    matches
+      .into_iter()
+      .filter(|(term, _)| {
+        term != SCHEME_KEY
+      })
+      .map(|(term, value)| {
+        let weights =
+          DictionaryWeights::from_value(
+            &value
+          );
+        Self {
+          term,
+          codes: weights.codes(),
+          weights
+        }
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug)]
+pub struct Dictionary {
+  adapter:  DictionaryAdapter,
+  backend:  DictionaryBackend,
+  terms:    TermNormalizer,
+  /// Built-in keyword terms keyed for
+  /// `terms`; see [`keywords`].
+  keywords:
+    HashMap<String, DictionaryValue>
+}
+
+impl Dictionary {
+  pub fn create(
+    adapter: DictionaryAdapter
+  ) -> Self {
+    DictionaryConfig::new(adapter)
+      .open_or_memory()
+  }
+
+  pub fn try_create(
+    config: DictionaryConfig
+  ) -> Result<Self> {
+    config.open()
+  }
+
+  pub fn open(self) -> Self {
+    self
+  }
+
+  pub fn lookup(
+    &self,
+    term: &str
+  ) -> Vec<DictionaryCode> {
+    self.lookup_weights(term).codes()
+  }
+
+  /// Codes of `term` with their
+  /// weights. A multi-word term
+  /// carries every code of its words
+  /// at the largest weight seen.
+  pub fn lookup_weights(
+    &self,
+    term: &str
+  ) -> DictionaryWeights {
+    let value = self
+      .terms
+      .terms(term)
+      .into_iter()
+      .filter_map(|key| {
+        self.key_value(&key)
+      })
+      .reduce(|merged, found| {
+        merged.merge(&found)
+      })
+      .unwrap_or_else(|| {
+        DictionaryValue::from_mask(0)
+      });
+    DictionaryWeights::from_value(
+      &value
+    )
+  }
+
+  /// [`Dictionary::lookup_weights`] of
+  /// every term, fetching all their
+  /// keys in one batch.
+  pub fn lookup_many(
+    &self,
+    terms: &[&str]
+  ) -> Vec<DictionaryWeights> {
+    let term_keys = terms
+      .iter()
+      .map(|term| {
+        self.terms.terms(term)
+      })
+      .collect::<Vec<_>>();
+    let values =
+      self.key_values(&term_keys);
+    term_keys
+      .iter()
+      .map(|keys| {
+        let value = keys
+          .iter()
+          .filter_map(|key| {
+            values.get(key)?.clone()
+          })
+          .reduce(|merged, found| {
+            merged.merge(&found)
+          })
+          .unwrap_or_else(|| {
+            DictionaryValue::from_mask(
+              0
+            )
+          });
+        DictionaryWeights::from_value(
+          &value
+        )
+      })
+      .collect()
+  }
+
+  /// Built-in keyword and stored values
+  /// of the distinct keys in
+  /// `term_keys`.
+  fn key_values(
+    &self,
+    term_keys: &[Vec<String>]
+  ) -> HashMap<
+    String,
+    Option<DictionaryValue>
+  > {
+    let keys = term_keys
+      .iter()
+      .flatten()
+      .cloned()
+      .collect::<BTreeSet<_>>()
+      .into_iter()
+      .collect::<Vec<_>>();
+    let stored =
+      self.backend.get_values(&keys);
+    keys
+      .into_iter()
+      .zip(stored)
+      .map(|(key, stored)| {
+        let builtin = self
+          .keywords
+          .get(&key)
+          .cloned();
+        let value =
+          match (builtin, stored) {
+            | (
+              Some(builtin),
+              Some(stored)
+            ) => {
+              Some(
+                builtin.merge(&stored)
+              )
+            }
+            | (builtin, stored) => {
+              builtin.or(stored)
+            }
+          };
+        (key, value)
+      })
+      .collect()
+  }
+
+  /// Built-in keyword and stored value
+  /// of a single key.
+  fn key_value(
+    &self,
+    key: &str
+  ) -> Option<DictionaryValue> {
+    let builtin =
+      self.keywords.get(key).cloned();
+    let stored =
+      self.backend.get_value(key);
+    match (builtin, stored) {
+      | (
+        Some(builtin),
+        Some(stored)
+      ) => Some(builtin.merge(&stored)),
+      | (builtin, stored) => {
+        builtin.or(stored)
+      }
+    }
+  }
+
+  pub fn adapter(
+    &self
+  ) -> DictionaryAdapter {
+    self.adapter
+  }
+
+  pub fn key_scheme(
+    &self
+  ) -> KeyScheme {
+    self.terms.scheme()
+  }
+
+  pub fn term_normalizer(
+    &self
+  ) -> TermNormalizer {
+    self.terms
+  }
+
+  /// Keys starting with `prefix`; only
+  /// compiled dictionaries support
+  /// this. The query is normalized like
+  /// a lookup and its first key is
+  /// used.
+  pub fn lookup_prefix(
+    &self,
+    prefix: &str
+  ) -> Result<Vec<DictionaryMatch>> {
+    let Some(key) =
+      self.query_key(prefix)
+    else {
+      return Ok(Vec::new());
+    };
+    match &self.backend {
+      | DictionaryBackend::Fst(
+        backend
+      ) => {
+        Ok(DictionaryMatch::collect(
+          backend.prefix(&key)
+        ))
+      }
+      | _ => {
+        anyhow::bail!(
+          "prefix queries need the \
+           fst adapter"
+        )
+      }
+    }
+  }
+
+  /// Keys within `distance` edits of
+  /// `term`; only compiled dictionaries
+  /// support this.
+  pub fn lookup_fuzzy(
+    &self,
+    term: &str,
+    distance: u32
+  ) -> Result<Vec<DictionaryMatch>> {
+    let Some(key) =
+      self.query_key(term)
+    else {
+      return Ok(Vec::new());
+    };
+    match &self.backend {
+      | DictionaryBackend::Fst(
+        backend
+      ) => {
+        Ok(DictionaryMatch::collect(
+          backend
+            .fuzzy(&key, distance)?
+        ))
+      }
+      | _ => {
+        anyhow::bail!(
+          "fuzzy queries need the fst \
+           adapter"
+        )
+      }
+    }
+  }
+
+  /// Compiles `entries` into an fst
+  /// dictionary at `path`, keyed with
+  /// `terms` and seeded like a new
+  /// store. Returns the number of keys.
+  pub fn compile(
+    path: impl AsRef<Path>,
+    terms: TermNormalizer,
+    entries: impl IntoIterator<
+      Item = (String, u32)
+    >
+  ) -> Result<usize> {
+    Self::compile_values(
+      path.as_ref(),
+      terms,
+      mask_values(entries)?
+    )
+  }
+
+  /// Like [`Dictionary::compile`] with
+  /// a weight per entry.
+  pub fn compile_weighted(
+    path: impl AsRef<Path>,
+    terms: TermNormalizer,
+    entries: impl IntoIterator<
+      Item = (
+        String,
+        DictionaryCode,
+        f32
+      )
+    >
+  ) -> Result<usize> {
+    Self::compile_values(
+      path.as_ref(),
+      terms,
+      weighted_values(entries)?
+    )
+  }
+
+  fn compile_values(
+    path: &Path,
+    terms: TermNormalizer,
+    entries: impl IntoIterator<
+      Item = (String, DictionaryValue)
+    >
+  ) -> Result<usize> {
+    let mut compiled =
+      prepare_values(&terms, entries);
+    compiled.insert(
+      SCHEME_KEY.to_string(),
+      DictionaryValue::from_mask(
+        terms.marker()
+      )
+    );
+    for place in PLACE_NAMES {
+      let seeded =
+        DictionaryValue::from_mask(
+          DictionaryCode::Place.bit()
+        );
+      let merged = compiled
+        .get(*place)
+        .map(|value| {
+          value.merge(&seeded)
+        })
+        .unwrap_or(seeded);
+      compiled.insert(
+        place.to_string(),
+        merged
+      );
+    }
+    FstBackend::write(path, &compiled)
+  }
+
+  fn query_key(
+    &self,
+    query: &str
+  ) -> Option<String> {
+    self
+      .terms
+      .terms(query)
+      .into_iter()
+      .next()
+  }
+
+  /// Every stored term with its codes,
+  /// sorted by key.
+  pub fn entries(
+    &self
+  ) -> Result<Vec<DictionaryMatch>> {
+    Ok(DictionaryMatch::collect(
+      self.backend.entries()?
+    ))
+  }
+
+  /// Term counts per code and the
+  /// overlap between codes.
+  pub fn stats(
+    &self
+  ) -> Result<DictionaryStats> {
+    Ok(DictionaryStats::from_entries(
+      &self.entries()?
+    ))
+  }
+
+  /// Terms only in `self`, only in
+  /// `other`, or stored with different
+  /// codes or weights.
+  pub fn diff(
+    &self,
+    other: &Dictionary
+  ) -> Result<DictionaryDiff> {
+    Ok(DictionaryDiff::between(
+      self.entries()?,
+      other.entries()?
+    ))
+  }
+
+  /// Rewrites the store for `terms`.
+  /// Legacy keys are fragments ("z",
+  /// "rich") that cannot be mapped back
+  /// to their terms, so the store is
+  /// emptied and must be re-imported.
+  /// Returns the number of keys
+  /// removed.
+  pub fn migrate_keys(
+    &mut self,
+    terms: TermNormalizer
+  ) -> Result<usize> {
+    let cleared =
+      self.backend.clear()?;
+    self.terms = terms;
+    self.keywords =
+      keywords::layer(&self.terms);
+    self.write_scheme()?;
+    self.seed_places()?;
+    Ok(cleared)
+  }
+
+  /// Reads the key scheme of a store.
+  /// Stores seeded before the scheme
+  /// marker existed use legacy keys;
+  /// new stores are marked with the
+  /// configured scheme.
+  fn prepare_store(
+    &mut self
+  ) -> Result<()> {
+    match self
+      .backend
+      .get_value(SCHEME_KEY)
+    {
+      | Some(marker) => {
+        self.terms =
+          TermNormalizer::from_marker(
+            marker.mask()
+          );
+      }
+      | None
+        if self
+          .backend
+          .get_value(PLACE_NAMES[0])
+          .is_some() =>
+      {
+        self.terms =
+          TermNormalizer::legacy();
+      }
+      | None => self.write_scheme()?
+    }
+    self.keywords =
+      keywords::layer(&self.terms);
+    self.seed_places()
+  }
+
+  fn write_scheme(
+    &mut self
+  ) -> Result<()> {
+    let marker = self.terms.marker();
+    if marker != 0 {
+      self.backend.merge_entries(&[
+        (
+          SCHEME_KEY.to_string(),
+          DictionaryValue::from_mask(
+            marker
+          )
+        )
+      ])?;
+    }
+    Ok(())
+  }
+
+  fn seed_places(
+    &mut self
+  ) -> Result<()> {
+    let entries = PLACE_NAMES
+      .iter()
+      .map(|place| {
+        (
+          place.to_string(),
+          DictionaryValue::from_mask(
+            DictionaryCode::Place.bit()
+          )
+        )
+      })
+      .collect::<Vec<_>>();
+    self
+      .backend
+      .merge_entries(&entries)?;
+    Ok(())
+  }
+
+  pub fn import_terms(
+    &mut self,
+    code: DictionaryCode,
+    terms: impl IntoIterator<Item = String>
+  ) -> Result<usize> {
+    let entries = terms
+      .into_iter()
+      .map(|term| (term, code.bit()));
+    self.import_entries(entries)
+  }
+
+  pub fn import_entries(
+    &mut self,
+    entries: impl IntoIterator<
+      Item = (String, u32)
+    >
+  ) -> Result<usize> {
+    self.import_values(mask_values(
+      entries
+    )?)
+  }
+
+  /// Imports terms with a weight for
+  /// their code, such as the scores of
+  /// AnyStyle dictionaries. A term seen
+  /// twice for one code keeps the
+  /// larger weight. The weight of a
+  /// month term is its number, which
+  /// is stored apart from the weights;
+  /// see [`DictionaryWeights::month`].
+  pub fn import_weighted(
+    &mut self,
+    entries: impl IntoIterator<
+      Item = (
+        String,
+        DictionaryCode,
+        f32
+      )
+    >
+  ) -> Result<usize> {
+    self.import_values(weighted_values(
+      entries
+    )?)
+  }
+
+  fn import_values(
+    &mut self,
+    entries: impl IntoIterator<
+      Item = (String, DictionaryValue)
+    >
+  ) -> Result<usize> {
+    let prepared = prepare_values(
+      &self.terms,
+      entries
+    )
+    .into_iter()
+    .collect::<Vec<_>>();
+    self
+      .backend
+      .merge_entries(&prepared)
+  }
+}
+
+/// Values of code masks. Month terms
+/// need their number, so masks with
+/// the month code are refused.
+fn mask_values(
+  entries: impl IntoIterator<
+    Item = (String, u32)
+  >
+) -> Result<
+  Vec<(String, DictionaryValue)>
+> {
+  entries
+    .into_iter()
+    .map(|(term, mask)| {
+      if mask
+        & DictionaryCode::Month.bit()
+        != 0
+      {
+        anyhow::bail!(
+          "month term {term:?} needs \
+           its number"
+        );
+      }
+      Ok((
+        term,
+        DictionaryValue::from_mask(
+          mask
+        )
+      ))
+    })
+    .collect()
+}
+
+/// Values of weighted entries; month
+/// terms read their number from the
+/// weight and are refused without a
+/// valid one.
+fn weighted_values(
+  entries: impl IntoIterator<
+    Item = (
+      String,
+      DictionaryCode,
+      f32
+    )
+  >
+) -> Result<
+  Vec<(String, DictionaryValue)>
+> {
+  let mut values = Vec::new();
+  for (term, code, weight) in entries {
+    if code == DictionaryCode::Month {
+      let month = (weight.fract()
+        == 0.0
+        && (1.0..=12.0)
+          .contains(&weight))
+      .then_some(weight as u8);
+      let Some(month) = month else {
+        anyhow::bail!(
+          "month term {term:?} needs \
+           a number from 1 to 12, got \
+           {weight}"
+        );
+      };
+      values.push((
+        term,
+        DictionaryValue::for_month(
+          month
+        )
+      ));
+    } else if weight.is_finite()
+      && weight >= 0.0
+    {
+      values.push((
+        term,
+        DictionaryValue::weighted(
+          code, weight
+        )
+      ));
+    }
+  }
+  Ok(values)
+}
+
+/// Splits terms into keys and merges
+/// the values of repeated keys.
+fn prepare_values(
+  terms: &TermNormalizer,
+  entries: impl IntoIterator<
+    Item = (String, DictionaryValue)
+  >
+) -> BTreeMap<String, DictionaryValue> {
+  let mut prepared = BTreeMap::<
+    String,
+    DictionaryValue
+  >::new();
+  for (term, value) in entries {
+    let term = term.trim();
+    if term.is_empty()
+      || value.mask() == 0
+    {
+      continue;
+    }
+    for key in terms.terms(term) {
+      let merged = prepared
+        .get(&key)
+        .map(|existing| {
+          existing.merge(&value)
+        })
+        .unwrap_or_else(|| {
+          value.clone()
+        });
+      prepared.insert(key, merged);
+    }
+  }
+  prepared
+}
+
+#[derive(Debug)]
+enum DictionaryBackend {
+  Memory(MemoryBackend),
+  Gdbm(GdbmBackend),
+  Lmdb(LmdbBackend),
+  Redis(RedisBackend),
+  Sqlite(SqliteBackend),
+  Fst(FstBackend)
+}
+
+impl DictionaryBackend {
+  fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    match self {
+      | Self::Memory(backend) => {
+        backend.get_value(term)
+      }
+      | Self::Gdbm(backend) => {
+        backend.get_value(term)
+      }
+      | Self::Lmdb(backend) => {
+        backend.get_value(term)
+      }
+      | Self::Redis(backend) => {
+        backend.get_value(term)
+      }
+      | Self::Sqlite(backend) => {
+        backend.get_value(term)
+      }
+      | Self::Fst(backend) => {
+        backend.get_value(term)
+      }
+    }
+  }
+
+  /// Values of several keys; the redis
+  /// adapter answers with one MGET.
+  fn get_values(
+    &self,
+    terms: &[String]
+  ) -> Vec<Option<DictionaryValue>> {
+    match self {
+      | Self::Redis(backend) => {
+        backend.get_values(terms)
+      }
+      | _ => {
+        terms
+          .iter()
+          .map(|term| {
+            self.get_value(term)
+          })
+          .collect()
+      }
+    }
+  }
+
+  /// Every stored key with its value,
+  /// sorted by key.
+  fn entries(
+    &self
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    let mut entries = match self {
+      | Self::Memory(backend) => {
+        backend.entries()
+      }
+      | Self::Gdbm(backend) => {
+        backend.entries()?
+      }
+      | Self::Lmdb(backend) => {
+        backend.entries()?
+      }
+      | Self::Redis(backend) => {
+        backend.entries()?
+      }
+      | Self::Sqlite(backend) => {
+        backend.entries()?
+      }
+      | Self::Fst(backend) => {
+        backend.entries()
+      }
+    };
+    entries.sort_by(|left, right| {
+      left.0.cmp(&right.0)
+    });
+    Ok(entries)
+  }
+
+  fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    match self {
+      | Self::Memory(backend) => {
+        Ok(
+          backend
+            .merge_entries(entries)
+        )
+      }
+      | Self::Gdbm(backend) => {
+        backend.merge_entries(entries)
+      }
+      | Self::Lmdb(backend) => {
+        backend.merge_entries(entries)
+      }
+      | Self::Redis(backend) => {
+        backend.merge_entries(entries)
+      }
+      | Self::Sqlite(backend) => {
+        backend.merge_entries(entries)
+      }
+      | Self::Fst(backend) => {
+        backend.merge_entries(entries)
+      }
+    }
+  }
+
+  fn clear(&mut self) -> Result<usize> {
+    match self {
+      | Self::Memory(backend) => {
+        Ok(backend.clear())
+      }
+      | Self::Gdbm(backend) => {
+        backend.clear()
+      }
+      | Self::Lmdb(backend) => {
+        backend.clear()
+      }
+      | Self::Redis(backend) => {
+        backend.clear()
+      }
+      | Self::Sqlite(backend) => {
+        backend.clear()
+      }
+      | Self::Fst(backend) => {
+        backend.clear()
+      }
+    }
+  }
+}
+
+static PLACE_NAMES: &[&str] =
+  &["philippines", "italy"];
@@ -0,0 +1,314 @@
+use std::sync::{
+  Mutex,
+  MutexGuard
+};
+
+use anyhow::{
+  Context,
+  Result
+};
+use redis::Commands;
+
+use super::DictionaryValue;
+
+/// Keys per MGET/MSET round trip when
+/// merging large imports.
+const BATCH_SIZE: usize = 1000;
+
+/// Keeps one connection for the life of
+/// the backend; it is opened on first
+/// use and reopened after an I/O error.
+pub(super) struct RedisBackend {
+  client:     redis::Client,
+  namespace:  String,
+  connection:
+    Mutex<Option<redis::Connection>>
+}
+
+impl std::fmt::Debug for RedisBackend {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>
+  ) -> std::fmt::Result {
+    f.debug_struct("RedisBackend")
+      .field("client", &self.client)
+      .field(
+        "namespace",
+        &self.namespace
+      )
+      .finish_non_exhaustive()
+  }
+}
+
+impl RedisBackend {
+  pub(super) fn open(
+    url: &str,
+    namespace: String
+  ) -> Result<Self> {
+    let client =
+      redis::Client::open(url)
+        .context("open redis client")?;
+    Ok(Self {
+      client,
+      namespace,
+      connection: Mutex::new(None)
+    })
+  }
+
+  pub(super) fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    let key = self.key(term);
+    let value: Option<String> = self
+      .with_connection(|conn| {
+        conn.get(&key)
+      })
+      .ok()?;
+    DictionaryValue::from_string(
+      value.as_deref().unwrap_or("")
+    )
+  }
+
+  /// Values of `terms` in one MGET.
+  pub(super) fn get_values(
+    &self,
+    terms: &[String]
+  ) -> Vec<Option<DictionaryValue>> {
+    if terms.is_empty() {
+      return Vec::new();
+    }
+    let keys = terms
+      .iter()
+      .map(|term| self.key(term))
+      .collect::<Vec<_>>();
+    match self.mget(&keys) {
+      | Ok(values) => values,
+      | Err(_) => {
+        vec![None; terms.len()]
+      }
+    }
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    let keys: Vec<String> = self
+      .with_connection(|conn| {
+        conn.keys(format!(
+          "{}:*",
+          self.namespace
+        ))
+      })?;
+    let prefix =
+      format!("{}:", self.namespace);
+    let mut entries = Vec::new();
+    for chunk in keys.chunks(BATCH_SIZE)
+    {
+      let values = self.mget(chunk)?;
+      entries.extend(
+        chunk
+          .iter()
+          .zip(values)
+          .filter_map(
+            |(key, value)| {
+              let term = key
+                .strip_prefix(
+                  &prefix
+                )?;
+              Some((
+                term.to_string(),
+                value?
+              ))
+            }
+          )
+      );
+    }
+    Ok(entries)
+  }
+
+  /// Reads the stored values of each
+  /// batch with one MGET and writes the
+  /// changed ones with one pipelined
+  /// MSET.
+  pub(super) fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    let mut inserted = 0usize;
+    for batch in
+      entries.chunks(BATCH_SIZE)
+    {
+      let keys = batch
+        .iter()
+        .map(|(term, _)| self.key(term))
+        .collect::<Vec<_>>();
+      let existing =
+        self.mget(&keys)?;
+      let mut pending = Vec::<(
+        String,
+        DictionaryValue
+      )>::new();
+      for ((key, (_, value)), stored) in
+        keys
+          .into_iter()
+          .zip(batch)
+          .zip(existing)
+      {
+        // A batch may repeat a key, so
+        // merge onto a pending write.
+        let current = pending
+          .iter()
+          .rev()
+          .find(|(pending, _)| {
+            *pending == key
+          })
+          .map(|(_, value)| {
+            value.clone()
+          })
+          .or(stored);
+        let merged = current
+          .as_ref()
+          .map(|current| {
+            current.merge(value)
+          })
+          .unwrap_or_else(|| {
+            value.clone()
+          });
+        if current.as_ref()
+          != Some(&merged)
+        {
+          pending.push((key, merged));
+          inserted += 1;
+        }
+      }
+      if pending.is_empty() {
+        continue;
+      }
+      let items = pending
+        .iter()
+        .map(|(key, value)| {
+          (
+            key.as_str(),
+            value.to_text()
+          )
+        })
+        .collect::<Vec<_>>();
+      self.with_connection(|conn| {
+        redis::pipe()
+          .atomic()
+          .mset(&items)
+          .ignore()
+          .query::<()>(conn)
+      })?;
+    }
+    Ok(inserted)
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> Result<usize> {
+    let pattern =
+      format!("{}:*", self.namespace);
+    let keys: Vec<String> = self
+      .with_connection(|conn| {
+        conn.keys(&pattern)
+      })?;
+    if !keys.is_empty() {
+      self.with_connection(|conn| {
+        conn.del::<_, ()>(&keys)
+      })?;
+    }
+    Ok(keys.len())
+  }
+
+  fn mget(
+    &self,
+    keys: &[String]
+  ) -> Result<
+    Vec<Option<DictionaryValue>>
+  > {
+    // MGET of a single key answers
+    // with a bare value, so always ask
+    // for a list.
+    let values: Vec<Option<String>> =
+      self.with_connection(|conn| {
+        redis::cmd("MGET")
+          .arg(keys)
+          .query(conn)
+      })?;
+    Ok(
+      values
+        .into_iter()
+        .map(|value| {
+          value.as_deref().and_then(
+            DictionaryValue::from_string
+          )
+        })
+        .collect()
+    )
+  }
+
+  /// Runs `command` on the shared
+  /// connection, dropping it after I/O
+  /// errors so the next call
+  /// reconnects.
+  fn with_connection<T>(
+    &self,
+    command: impl FnOnce(
+      &mut redis::Connection
+    ) -> redis::RedisResult<T>
+  ) -> Result<T> {
+    let mut guard = self.lock();
+    if guard.is_none() {
+      *guard = Some(
+        self
+          .client
+          .get_connection()
+          .context(
+            "connect to redis"
+          )?
+      );
+    }
+    let conn = guard.as_mut().expect(
+      "connection opened above"
+    );
+    let result = command(conn);
+    if let Err(err) = &result
+      && (err.is_io_error()
+        || err.is_connection_dropped())
+    {
+      *guard = None;
+    }
+    Ok(result?)
+  }
+
+  fn lock(
+    &self
+  ) -> MutexGuard<
+    '_,
+    Option<redis::Connection>
+  > {
+    self
+      .connection
+      .lock()
+      .unwrap_or_else(|poisoned| {
+        poisoned.into_inner()
+      })
+  }
+
+  fn key(
+    &self,
+    term: &str
+  ) -> String {
+    format!(
+      "{}:{}",
+      self.namespace, term
+    )
+  }
+}
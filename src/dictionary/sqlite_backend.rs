@@ -0,0 +1,199 @@
+use std::path::Path;
+
+use anyhow::{
+  Context,
+  Result
+};
+use rusqlite::types::Value;
+use rusqlite::{
+  Connection,
+  OptionalExtension,
+  params
+};
+
+use super::DictionaryValue;
+
+#[derive(Debug)]
+pub(super) struct SqliteBackend {
+  conn: Connection
+}
+
+impl SqliteBackend {
+  pub(super) fn open(
+    path: &Path
+  ) -> Result<Self> {
+    if let Some(parent) = path.parent()
+      && !parent.as_os_str().is_empty()
+    {
+      std::fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(path)
+      .context(
+      "open sqlite database"
+    )?;
+    // WAL keeps lookups from other
+    // processes readable during bulk
+    // imports.
+    conn
+      .pragma_update(
+        None,
+        "journal_mode",
+        "WAL"
+      )
+      .context("enable sqlite wal")?;
+    conn
+      .pragma_update(
+        None,
+        "synchronous",
+        "NORMAL"
+      )
+      .context(
+        "set sqlite synchronous mode"
+      )?;
+    conn
+      .execute_batch(
+        "CREATE TABLE IF NOT EXISTS \
+         places (term TEXT PRIMARY \
+         KEY NOT NULL, value INTEGER \
+         NOT NULL) WITHOUT ROWID;"
+      )
+      .context("create sqlite table")?;
+    Ok(Self {
+      conn
+    })
+  }
+
+  pub(super) fn get_value(
+    &self,
+    term: &str
+  ) -> Option<DictionaryValue> {
+    read_value(&self.conn, term)
+  }
+
+  pub(super) fn entries(
+    &self
+  ) -> Result<
+    Vec<(String, DictionaryValue)>
+  > {
+    let mut select =
+      self.conn.prepare_cached(
+        "SELECT term, value FROM \
+         places ORDER BY term"
+      )?;
+    let rows = select
+      .query_map([], |row| {
+        Ok((
+          row.get::<_, String>(0)?,
+          row.get::<_, Value>(1)?
+        ))
+      })?
+      .collect::<rusqlite::Result<Vec<_>>>(
+      )?;
+    Ok(
+      rows
+        .into_iter()
+        .filter_map(|(term, value)| {
+          decode_value(value)
+            .map(|value| (term, value))
+        })
+        .collect()
+    )
+  }
+
+  pub(super) fn merge_entries(
+    &mut self,
+    entries: &[(
+      String,
+      DictionaryValue
+    )]
+  ) -> Result<usize> {
+    let txn =
+      self.conn.transaction()?;
+    let mut inserted = 0usize;
+    {
+      let mut upsert = txn
+        .prepare_cached(
+          "INSERT OR REPLACE INTO \
+           places (term, value) \
+           VALUES (?1, ?2)"
+        )?;
+      for (term, value) in entries {
+        let existing =
+          read_value(&txn, term);
+        let merged = existing
+          .as_ref()
+          .map(|existing| {
+            existing.merge(value)
+          })
+          .unwrap_or_else(|| {
+            value.clone()
+          });
+        if existing.as_ref()
+          != Some(&merged)
+        {
+          upsert.execute(params![
+            term,
+            merged.bytes()
+          ])?;
+          inserted += 1;
+        }
+      }
+    }
+    txn.commit()?;
+    Ok(inserted)
+  }
+
+  pub(super) fn clear(
+    &mut self
+  ) -> Result<usize> {
+    let cleared = self.conn.execute(
+      "DELETE FROM places",
+      []
+    )?;
+    Ok(cleared)
+  }
+}
+
+/// Reads integer masks written before
+/// weights as well as encoded blobs.
+fn read_value(
+  conn: &Connection,
+  term: &str
+) -> Option<DictionaryValue> {
+  let value = conn
+    .prepare_cached(
+      "SELECT value FROM places WHERE \
+       term = ?1"
+    )
+    .ok()?
+    .query_row(params![term], |row| {
+      row.get::<_, Value>(0)
+    })
+    .optional()
+    .ok()
+    .flatten()?;
+  decode_value(value)
+}
+
+fn decode_value(
+  value: Value
+) -> Option<DictionaryValue> {
+  match value {
+    | Value::Integer(mask) => {
+      u32::try_from(mask)
+        .ok()
+        .map(DictionaryValue::from_mask)
+    }
+    | Value::Blob(bytes) => {
+      DictionaryValue::from_bytes(
+        &bytes
+      )
+    }
+    | Value::Text(text) => {
+      DictionaryValue::from_string(
+        &text
+      )
+    }
+    | _ => None
+  }
+}
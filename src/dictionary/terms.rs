@@ -0,0 +1,297 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+use unicode_script::{
+  Script,
+  UnicodeScript
+};
+
+/// Reserved key holding the key scheme
+/// of a persistent store. Control
+/// characters never survive term
+/// normalization, so lookups cannot
+/// collide with it.
+pub(super) const SCHEME_KEY: &str =
+  "\u{0}cite-otter:key-scheme";
+
+const UNICODE_BIT: u32 = 1;
+const FOLDED_BIT: u32 = 2;
+
+/// How terms were turned into keys when
+/// a store was written.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum KeyScheme {
+  /// ASCII letters and digits only;
+  /// written by releases before Unicode
+  /// keys. "Zürich" became "z" and
+  /// "rich".
+  Legacy,
+  /// Unicode letters and digits with
+  /// case folding and script-aware
+  /// segmentation.
+  Unicode
+}
+
+/// Turns dictionary terms into lookup
+/// keys. Import and lookup must share
+/// one normalizer.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub struct TermNormalizer {
+  scheme:          KeyScheme,
+  fold_diacritics: bool
+}
+
+impl Default for TermNormalizer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl TermNormalizer {
+  pub fn new() -> Self {
+    Self {
+      scheme:
+        KeyScheme::Unicode,
+      fold_diacritics: false
+    }
+  }
+
+  pub fn legacy() -> Self {
+    Self {
+      scheme:
+        KeyScheme::Legacy,
+      fold_diacritics: false
+    }
+  }
+
+  /// Fold "Kraków" and "Krakow" onto
+  /// one key. Only Latin, Greek and
+  /// Cyrillic letters lose their
+  /// marks; vowel signs in other
+  /// scripts are kept.
+  pub fn with_diacritic_folding(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.fold_diacritics = enabled;
+    self
+  }
+
+  pub fn scheme(&self) -> KeyScheme {
+    self.scheme
+  }
+
+  pub fn folds_diacritics(
+    &self
+  ) -> bool {
+    self.fold_diacritics
+  }
+
+  pub fn terms(
+    &self,
+    term: &str
+  ) -> Vec<String> {
+    match self.scheme {
+      | KeyScheme::Legacy => {
+        legacy_terms(term)
+      }
+      | KeyScheme::Unicode => {
+        segment(&self.fold(term))
+      }
+    }
+  }
+
+  pub(super) fn marker(&self) -> u32 {
+    match self.scheme {
+      | KeyScheme::Legacy => 0,
+      | KeyScheme::Unicode
+        if self.fold_diacritics =>
+      {
+        UNICODE_BIT | FOLDED_BIT
+      }
+      | KeyScheme::Unicode => {
+        UNICODE_BIT
+      }
+    }
+  }
+
+  pub(super) fn from_marker(
+    marker: u32
+  ) -> Self {
+    if marker & UNICODE_BIT == 0 {
+      return Self::legacy();
+    }
+    Self::new().with_diacritic_folding(
+      marker & FOLDED_BIT != 0
+    )
+  }
+
+  fn fold(
+    &self,
+    term: &str
+  ) -> String {
+    let lowered = term
+      .chars()
+      .flat_map(char::to_lowercase)
+      .map(|c| {
+        // Final sigma folds onto sigma,
+        // as in full case folding.
+        if c == 'ς' {
+          'σ'
+        } else {
+          c
+        }
+      })
+      .collect::<String>();
+    if !self.fold_diacritics {
+      return lowered.nfc().collect();
+    }
+    let mut folded = String::new();
+    let mut base_script =
+      Script::Common;
+    for c in lowered.nfd() {
+      if is_combining_mark(c) {
+        if !matches!(
+          base_script,
+          Script::Latin
+            | Script::Greek
+            | Script::Cyrillic
+        ) {
+          folded.push(c);
+        }
+        continue;
+      }
+      base_script = c.script();
+      match fold_letter(c) {
+        | Some(expanded) => {
+          folded.push_str(expanded)
+        }
+        | None => folded.push(c)
+      }
+    }
+    folded.nfc().collect()
+  }
+}
+
+/// Splits on anything that is not a
+/// letter, digit or combining mark, and
+/// where the script changes between
+/// letters ("東京Tokyo" gives "東京"
+/// and "tokyo"). Han, Hiragana and
+/// Katakana count as one script.
+fn segment(text: &str) -> Vec<String> {
+  let mut terms = Vec::new();
+  let mut current = String::new();
+  let mut current_script: Option<
+    ScriptGroup
+  > = None;
+  for c in text.chars() {
+    if !is_term_char(c) {
+      flush(&mut terms, &mut current);
+      current_script = None;
+      continue;
+    }
+    let group = script_group(c);
+    if let (Some(active), Some(next)) =
+      (current_script, group)
+      && active != next
+    {
+      flush(&mut terms, &mut current);
+    }
+    if group.is_some() {
+      current_script = group;
+    }
+    current.push(c);
+  }
+  flush(&mut terms, &mut current);
+  terms
+}
+
+fn flush(
+  terms: &mut Vec<String>,
+  current: &mut String
+) {
+  if !current.is_empty() {
+    terms.push(std::mem::take(current));
+  }
+}
+
+fn is_term_char(c: char) -> bool {
+  c.is_alphanumeric()
+    || is_combining_mark(c)
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq,
+)]
+enum ScriptGroup {
+  Cjk,
+  Other(Script)
+}
+
+/// Script of a letter, or `None` for
+/// digits and marks that join whatever
+/// surrounds them.
+fn script_group(
+  c: char
+) -> Option<ScriptGroup> {
+  match c.script() {
+    | Script::Common
+    | Script::Inherited
+    | Script::Unknown => None,
+    | Script::Han
+    | Script::Hiragana
+    | Script::Katakana
+    | Script::Bopomofo => {
+      Some(ScriptGroup::Cjk)
+    }
+    | script => {
+      Some(ScriptGroup::Other(script))
+    }
+  }
+}
+
+/// Letters without a canonical
+/// decomposition that still carry a
+/// diacritic or ligature.
+fn fold_letter(
+  c: char
+) -> Option<&'static str> {
+  match c {
+    | 'ß' => Some("ss"),
+    | 'ø' => Some("o"),
+    | 'æ' => Some("ae"),
+    | 'œ' => Some("oe"),
+    | 'ł' => Some("l"),
+    | 'đ' | 'ð' => Some("d"),
+    | 'þ' => Some("th"),
+    | 'ı' => Some("i"),
+    | 'ħ' => Some("h"),
+    | _ => None
+  }
+}
+
+fn legacy_terms(
+  term: &str
+) -> Vec<String> {
+  let normalized = term
+    .to_lowercase()
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() {
+        c
+      } else {
+        ' '
+      }
+    })
+    .collect::<String>();
+
+  normalized
+    .split_whitespace()
+    .filter(|item| !item.is_empty())
+    .map(|item| item.to_string())
+    .collect()
+}
@@ -0,0 +1,455 @@
+use super::DictionaryCode;
+
+/// Code mask with one weight per set
+/// bit, in bit order. Values whose
+/// weights are all 1.0 encode as the
+/// bare mask, so stores written before
+/// weights read unchanged. Month terms
+/// carry their number next to the
+/// weights, after them when encoded.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct DictionaryValue {
+  mask:    u32,
+  weights: Vec<f32>,
+  month:   Option<u8>
+}
+
+impl DictionaryValue {
+  pub(super) fn from_mask(
+    mask: u32
+  ) -> Self {
+    Self {
+      mask,
+      weights: vec![
+        1.0;
+        mask.count_ones()
+          as usize
+      ],
+      month: None
+    }
+  }
+
+  pub(super) fn weighted(
+    code: DictionaryCode,
+    weight: f32
+  ) -> Self {
+    Self {
+      mask:    code.bit(),
+      weights: vec![weight],
+      month:   None
+    }
+  }
+
+  /// A month term with its number,
+  /// 1 to 12.
+  pub(super) fn for_month(
+    month: u8
+  ) -> Self {
+    Self {
+      mask:    DictionaryCode::Month
+        .bit(),
+      weights: vec![1.0],
+      month:   Some(month)
+    }
+  }
+
+  pub(super) fn mask(&self) -> u32 {
+    self.mask
+  }
+
+  pub(super) fn month(
+    &self
+  ) -> Option<u8> {
+    self.month
+  }
+
+  /// Whether the value encodes as the
+  /// bare mask.
+  pub(super) fn is_bare(&self) -> bool {
+    !self.is_weighted()
+      && self.month.is_none()
+  }
+
+  fn is_weighted(&self) -> bool {
+    self
+      .weights
+      .iter()
+      .any(|weight| *weight != 1.0)
+  }
+
+  /// Union of both masks; codes present
+  /// on both sides keep the larger
+  /// weight, so re-importing a file
+  /// changes nothing. A month number of
+  /// `other` replaces the one of
+  /// `self`.
+  pub(super) fn merge(
+    &self,
+    other: &Self
+  ) -> Self {
+    let mut merged = Self {
+      mask:    self.mask | other.mask,
+      weights: Vec::new(),
+      month:   other
+        .month
+        .or(self.month)
+    };
+    for idx in 0..u32::BITS {
+      let bit = 1u32 << idx;
+      if merged.mask & bit == 0 {
+        continue;
+      }
+      let weight = match (
+        self.weight_of(bit),
+        other.weight_of(bit)
+      ) {
+        | (Some(left), Some(right)) => {
+          left.max(right)
+        }
+        | (Some(weight), None)
+        | (None, Some(weight)) => {
+          weight
+        }
+        | (None, None) => 1.0
+      };
+      merged.weights.push(weight);
+    }
+    merged
+  }
+
+  /// The value with the codes of
+  /// `mask` removed.
+  pub(super) fn without(
+    &self,
+    mask: u32
+  ) -> Self {
+    let kept = self.mask & !mask;
+    Self {
+      mask:    kept,
+      weights: (0..u32::BITS)
+        .map(|idx| 1u32 << idx)
+        .filter(|bit| kept & bit != 0)
+        .filter_map(|bit| {
+          self.weight_of(bit)
+        })
+        .collect(),
+      month:   self.month.filter(
+        |_| {
+          kept
+            & DictionaryCode::Month
+              .bit()
+            != 0
+        }
+      )
+    }
+  }
+
+  pub(super) fn weight_of(
+    &self,
+    bit: u32
+  ) -> Option<f32> {
+    if self.mask & bit == 0 {
+      return None;
+    }
+    let position =
+      (self.mask & (bit - 1))
+        .count_ones() as usize;
+    self.weights.get(position).copied()
+  }
+
+  pub(super) fn from_bytes(
+    bytes: &[u8]
+  ) -> Option<Self> {
+    if bytes.len() >= 4 {
+      let mut buf = [0u8; 4];
+      buf.copy_from_slice(&bytes[..4]);
+      let mask =
+        u32::from_le_bytes(buf);
+      let count =
+        mask.count_ones() as usize;
+      if bytes.len() == 4 {
+        return Some(Self::from_mask(
+          mask
+        ));
+      }
+      let end = 4 + 4 * count;
+      if bytes.len() == end
+        || bytes.len() == end + 1
+      {
+        let weights = bytes[4..end]
+          .as_chunks::<4>()
+          .0
+          .iter()
+          .map(|chunk| {
+            f32::from_le_bytes(*chunk)
+          })
+          .collect();
+        let month = match bytes.get(end)
+        {
+          | Some(month) => {
+            Some(valid_month(
+              mask, *month
+            )?)
+          }
+          | None => None
+        };
+        return Some(Self {
+          mask,
+          weights,
+          month
+        });
+      }
+    }
+    Self::from_string(
+      std::str::from_utf8(bytes)
+        .ok()?
+    )
+  }
+
+  /// Text form used by Redis: the mask,
+  /// then one weight per set bit and
+  /// `month=N` for month terms.
+  pub(super) fn from_string(
+    value: &str
+  ) -> Option<Self> {
+    let mut parts = value
+      .split_whitespace()
+      .collect::<Vec<_>>();
+    let month = match parts
+      .last()
+      .and_then(|part| {
+        part.strip_prefix("month=")
+      }) {
+      | Some(month) => {
+        let month =
+          month.parse::<u8>().ok()?;
+        parts.pop();
+        Some(month)
+      }
+      | None => None
+    };
+    let (mask, weights) =
+      parts.split_first()?;
+    let mask =
+      mask.parse::<u32>().ok()?;
+    let weights = weights
+      .iter()
+      .map(|part| part.parse::<f32>())
+      .collect::<Result<Vec<_>, _>>()
+      .ok()?;
+    if weights.is_empty()
+      && month.is_none()
+    {
+      return Some(Self::from_mask(
+        mask
+      ));
+    }
+    let month = match month {
+      | Some(month) => {
+        Some(valid_month(mask, month)?)
+      }
+      | None => None
+    };
+    (weights.len()
+      == mask.count_ones() as usize)
+      .then_some(Self {
+        mask,
+        weights,
+        month
+      })
+  }
+
+  pub(super) fn bytes(
+    &self
+  ) -> Vec<u8> {
+    let mut bytes =
+      self.mask.to_le_bytes().to_vec();
+    if !self.is_bare() {
+      for weight in &self.weights {
+        bytes
+          .extend(weight.to_le_bytes());
+      }
+    }
+    bytes.extend(self.month);
+    bytes
+  }
+
+  pub(super) fn to_text(
+    &self
+  ) -> String {
+    let mut text =
+      self.mask.to_string();
+    if !self.is_bare() {
+      for weight in &self.weights {
+        text.push(' ');
+        text.push_str(
+          &weight.to_string()
+        );
+      }
+    }
+    if let Some(month) = self.month {
+      text.push_str(&format!(
+        " month={month}"
+      ));
+    }
+    text
+  }
+}
+
+/// `month` when it is a month number
+/// and `mask` holds the month code.
+fn valid_month(
+  mask: u32,
+  month: u8
+) -> Option<u8> {
+  (mask & DictionaryCode::Month.bit()
+    != 0
+    && (1..=12).contains(&month))
+  .then_some(month)
+}
+
+/// Per-code weights returned by
+/// [`super::Dictionary::lookup_weights`].
+/// Unscored entries weigh 1.0; scored
+/// AnyStyle files carry their own
+/// counts or probabilities. Month terms
+/// also carry their number.
+#[derive(
+  Debug, Clone, Default, PartialEq,
+)]
+pub struct DictionaryWeights {
+  weights: Vec<(DictionaryCode, f32)>,
+  month:   Option<u32>
+}
+
+impl DictionaryWeights {
+  pub(super) fn from_value(
+    value: &DictionaryValue
+  ) -> Self {
+    Self {
+      weights: DictionaryCode::ALL
+        .into_iter()
+        .filter_map(|code| {
+          value
+            .weight_of(code.bit())
+            .map(|weight| {
+              (code, weight)
+            })
+        })
+        .collect(),
+      month:   value
+        .month()
+        .map(u32::from)
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.weights.is_empty()
+  }
+
+  pub fn iter(
+    &self
+  ) -> impl Iterator<
+    Item = (DictionaryCode, f32)
+  > + '_ {
+    self.weights.iter().copied()
+  }
+
+  pub fn codes(
+    &self
+  ) -> Vec<DictionaryCode> {
+    self
+      .weights
+      .iter()
+      .map(|(code, _)| *code)
+      .collect()
+  }
+
+  /// Weight of `code`, or 0.0 when the
+  /// term does not carry it.
+  pub fn weight(
+    &self,
+    code: DictionaryCode
+  ) -> f32 {
+    self
+      .weights
+      .iter()
+      .find(|(found, _)| *found == code)
+      .map(|(_, weight)| *weight)
+      .unwrap_or(0.0)
+  }
+
+  /// Share of `code` in the total
+  /// weight of the term.
+  pub fn probability(
+    &self,
+    code: DictionaryCode
+  ) -> f32 {
+    let total = self
+      .weights
+      .iter()
+      .map(|(_, weight)| weight)
+      .sum::<f32>();
+    if total > 0.0 {
+      self.weight(code) / total
+    } else {
+      0.0
+    }
+  }
+
+  /// The weights of `codes` only, e.g.
+  /// [`DictionaryCode::FIELDS`] before
+  /// weighing field readings against
+  /// each other.
+  pub fn restricted(
+    &self,
+    codes: &[DictionaryCode]
+  ) -> Self {
+    Self {
+      weights: self
+        .weights
+        .iter()
+        .filter(|(code, _)| {
+          codes.contains(code)
+        })
+        .copied()
+        .collect(),
+      month:   self.month.filter(
+        |_| {
+          codes.contains(
+            &DictionaryCode::Month
+          )
+        }
+      )
+    }
+  }
+
+  /// Month number of a month term
+  /// (`outubro` is 10).
+  pub fn month(&self) -> Option<u32> {
+    self.month
+  }
+
+  /// Codes weighing at least `ratio` of
+  /// the heaviest one; "Jordan" scored
+  /// mostly as a name drops its place
+  /// reading.
+  pub fn dominant(
+    &self,
+    ratio: f32
+  ) -> Vec<DictionaryCode> {
+    let best = self
+      .weights
+      .iter()
+      .map(|(_, weight)| *weight)
+      .fold(0.0f32, f32::max);
+    self
+      .weights
+      .iter()
+      .filter(|(_, weight)| {
+        *weight >= best * ratio
+      })
+      .map(|(code, _)| *code)
+      .collect()
+  }
+}
@@ -0,0 +1,311 @@
+//! Removes page furniture: running
+//! headers and footers that repeat
+//! across pages, and bare page numbers.
+
+use std::collections::{
+  HashMap,
+  HashSet
+};
+use std::ops::Range;
+
+use super::{
+  Document,
+  Line
+};
+use crate::normalizer::volume;
+
+/// Lines from each page edge that may
+/// hold a header or footer.
+const EDGE_LINES: usize = 2;
+
+/// Document text without page
+/// furniture. Pages are joined by a
+/// single line break so that a
+/// reference crossing a page break
+/// reads as one paragraph.
+#[derive(Debug, Clone, Default)]
+pub struct CleanText {
+  text:    String,
+  /// Start in `text`, source offset
+  /// and length of each kept line.
+  spans:   Vec<(usize, usize, usize)>,
+  /// Page index and line of each kept
+  /// line.
+  lines:   Vec<(usize, Line)>,
+  removed: Vec<Line>
+}
+
+impl CleanText {
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// Source offset of a byte offset of
+  /// [`CleanText::text`]. Offsets
+  /// between lines map to the end of
+  /// the line before them.
+  pub fn source_offset(
+    &self,
+    offset: usize
+  ) -> usize {
+    let idx =
+      self.spans.partition_point(
+        |(start, ..)| *start <= offset
+      );
+    match idx.checked_sub(1) {
+      | Some(idx) => {
+        let (start, source, len) =
+          self.spans[idx];
+        source
+          + (offset - start).min(len)
+      }
+      | None => offset
+    }
+  }
+
+  /// Source range of a range of
+  /// [`CleanText::text`].
+  pub fn source_range(
+    &self,
+    range: Range<usize>
+  ) -> Range<usize> {
+    let start =
+      self.source_offset(range.start);
+    if range.is_empty() {
+      return start..start;
+    }
+    let end = self
+      .source_offset(range.end - 1)
+      + 1;
+    start..end.max(start)
+  }
+
+  /// Kept lines with the index of their
+  /// page, in text order.
+  pub fn lines(
+    &self
+  ) -> &[(usize, Line)] {
+    &self.lines
+  }
+
+  /// Headers, footers and page numbers
+  /// that were dropped.
+  pub fn removed(&self) -> &[Line] {
+    &self.removed
+  }
+}
+
+impl Document {
+  /// Text with running headers, footers
+  /// and page numbers removed. A line
+  /// is a header or footer when text
+  /// of the same shape (digits
+  /// ignored) sits at the same edge
+  /// position on several
+  /// pages. Single-page documents are
+  /// left as they are.
+  pub fn clean(&self) -> CleanText {
+    self.clean_where(|_, _| true)
+  }
+
+  /// Like [`Document::clean`], keeping
+  /// only lines for which `keep` holds
+  /// given their page index. Lines left
+  /// out end a paragraph.
+  pub fn clean_where(
+    &self,
+    keep: impl Fn(usize, &Line) -> bool
+  ) -> CleanText {
+    let pages = &self.pages;
+    let furniture = if pages.len() > 1 {
+      furniture(self)
+    } else {
+      HashSet::new()
+    };
+    let mut clean =
+      CleanText::default();
+    let mut skipped = false;
+    for (page_idx, page) in
+      pages.iter().enumerate()
+    {
+      let indices = page
+        .lines()
+        .iter()
+        .map(Line::index)
+        .collect::<HashSet<_>>();
+      let mut previous: Option<usize> =
+        None;
+      for (line_idx, line) in
+        page.lines().iter().enumerate()
+      {
+        if furniture.contains(&(
+          page_idx, line_idx
+        )) {
+          clean
+            .removed
+            .push(line.clone());
+          continue;
+        }
+        if !keep(page_idx, line) {
+          skipped = true;
+          continue;
+        }
+        if !clean.text.is_empty() {
+          clean.text.push('\n');
+          // A blank line in the source,
+          // not a removed one, ends a
+          // paragraph.
+          if skipped
+            || previous.is_some_and(
+              |prev| {
+                (prev + 1..line.index())
+                  .any(|idx| {
+                    !indices
+                      .contains(&idx)
+                  })
+              }
+            )
+          {
+            clean.text.push('\n');
+          }
+        }
+        clean.spans.push((
+          clean.text.len(),
+          line.offset(),
+          line.text().len()
+        ));
+        clean
+          .text
+          .push_str(line.text());
+        clean.lines.push((
+          page_idx,
+          line.clone()
+        ));
+        previous = Some(line.index());
+        skipped = false;
+      }
+    }
+    clean
+  }
+}
+
+/// Page and line positions of headers,
+/// footers and page numbers.
+fn furniture(
+  document: &Document
+) -> HashSet<(usize, usize)> {
+  let pages = &document.pages;
+  let mut shapes = HashMap::<
+    (bool, usize, String),
+    Vec<(usize, usize)>
+  >::new();
+  let mut found = HashSet::new();
+  for (page_idx, page) in
+    pages.iter().enumerate()
+  {
+    let lines = page.lines();
+    for (line_idx, line) in
+      lines.iter().enumerate()
+    {
+      let from_bottom =
+        lines.len() - 1 - line_idx;
+      let edge = if line_idx
+        < EDGE_LINES
+      {
+        (false, line_idx)
+      } else if from_bottom < EDGE_LINES
+      {
+        (true, from_bottom)
+      } else {
+        continue;
+      };
+      if is_page_number(line.text()) {
+        found
+          .insert((page_idx, line_idx));
+        continue;
+      }
+      shapes
+        .entry((
+          edge.0,
+          edge.1,
+          shape(line.text())
+        ))
+        .or_default()
+        .push((page_idx, line_idx));
+    }
+  }
+  // Running heads may alternate between
+  // odd and even pages.
+  let min_pages =
+    (pages.len() / 4).max(2);
+  for positions in shapes.into_values()
+  {
+    if positions.len() >= min_pages {
+      found.extend(positions);
+    }
+  }
+  found
+}
+
+/// Lowercased text with digit runs
+/// collapsed, so "Vol 3, p. 12" and
+/// "Vol 3, p. 13" share a shape.
+fn shape(text: &str) -> String {
+  let mut shape = String::new();
+  for c in text.chars() {
+    if c.is_ascii_digit() {
+      if !shape.ends_with('#') {
+        shape.push('#');
+      }
+    } else if !c.is_whitespace() {
+      shape.extend(c.to_lowercase());
+    }
+  }
+  shape
+}
+
+/// "12", "- 12 -", "Page 12 of 84",
+/// "xiv"; words such as "civic" or
+/// "ill" are not roman numerals.
+fn is_page_number(text: &str) -> bool {
+  let words = text
+    .split(|c: char| {
+      c.is_whitespace()
+        || matches!(
+          c,
+          '-' | '–' | '/' | '|'
+        )
+    })
+    .filter(|word| !word.is_empty())
+    .filter(|word| {
+      !matches!(
+        word.to_lowercase().as_str(),
+        "page"
+          | "p."
+          | "seite"
+          | "s."
+          | "of"
+      )
+    })
+    .collect::<Vec<_>>();
+  !words.is_empty()
+    && words.len() <= 2
+    && words.iter().all(|word| {
+      (word.len() <= 4
+        && word
+          .chars()
+          .all(|c| c.is_ascii_digit()))
+        || (word.len() <= 6
+          && word.chars().all(|c| {
+            matches!(
+              c.to_ascii_lowercase(),
+              'i'
+                | 'v'
+                | 'x'
+                | 'l'
+                | 'c'
+            )
+          })
+          && volume::is_roman(word))
+    })
+}
@@ -0,0 +1,124 @@
+//! Decodes plain-text input: UTF-8,
+//! UTF-16 with or without a byte order
+//! mark, Windows-1252 and Latin-1.
+
+use anyhow::{
+  Result,
+  bail
+};
+use encoding_rs::{
+  Encoding,
+  UTF_16BE,
+  UTF_16LE,
+  WINDOWS_1252
+};
+
+/// Share of zero bytes at odd or even
+/// positions that marks UTF-16 text
+/// without a byte order mark.
+const MIN_UTF16_ZEROS: f32 = 0.3;
+
+/// Largest share of zero bytes in the
+/// other lane: characters such as
+/// U+0100 or U+4E00 have a zero low
+/// byte.
+const MAX_UTF16_LOW_ZEROS: f32 = 0.1;
+
+/// Bytes that Windows-1252 leaves
+/// undefined; text holding them is read
+/// as Latin-1.
+const UNDEFINED_1252: [u8; 5] =
+  [0x81, 0x8d, 0x8f, 0x90, 0x9d];
+
+/// `data` transcoded to UTF-8.
+pub(super) fn decode(
+  data: &[u8]
+) -> Result<String> {
+  let (encoding, data) =
+    match Encoding::for_bom(data) {
+      | Some((encoding, len)) => {
+        (Some(encoding), &data[len..])
+      }
+      | None => (utf16(data), data)
+    };
+  let text = match encoding {
+    | Some(encoding) => {
+      let Some(text) = encoding
+        .decode_without_bom_handling_and_without_replacement(
+          data
+        )
+      else {
+        bail!(
+          "invalid {} text",
+          encoding.name()
+        );
+      };
+      text.into_owned()
+    }
+    | None => {
+      match std::str::from_utf8(data) {
+        | Ok(text) => text.to_string(),
+        | Err(_) => single_byte(data)
+      }
+    }
+  };
+  if text.contains('\0') {
+    bail!("not a text file");
+  }
+  Ok(text)
+}
+
+/// UTF-16 without a byte order mark,
+/// told apart by the zero high bytes of
+/// ASCII characters and the few zero
+/// low bytes.
+fn utf16(
+  data: &[u8]
+) -> Option<&'static Encoding> {
+  if data.len() < 2
+    || data.len() % 2 != 0
+  {
+    return None;
+  }
+  let pairs = data.len() / 2;
+  let zeros = |offset: usize| {
+    data
+      .iter()
+      .skip(offset)
+      .step_by(2)
+      .filter(|byte| **byte == 0)
+      .count() as f32
+      / pairs as f32
+  };
+  let (even, odd) =
+    (zeros(0), zeros(1));
+  if odd >= MIN_UTF16_ZEROS
+    && even <= MAX_UTF16_LOW_ZEROS
+  {
+    Some(UTF_16LE)
+  } else if even >= MIN_UTF16_ZEROS
+    && odd <= MAX_UTF16_LOW_ZEROS
+  {
+    Some(UTF_16BE)
+  } else {
+    None
+  }
+}
+
+fn single_byte(data: &[u8]) -> String {
+  if data.iter().any(|byte| {
+    UNDEFINED_1252.contains(byte)
+  }) {
+    // Latin-1 maps each byte to the
+    // code point of the same value.
+    data
+      .iter()
+      .map(|byte| char::from(*byte))
+      .collect()
+  } else {
+    WINDOWS_1252
+      .decode_without_bom_handling(data)
+      .0
+      .into_owned()
+  }
+}
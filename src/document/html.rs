@@ -0,0 +1,302 @@
+//! HTML text extraction. Markup is
+//! dropped; block elements and list
+//! items become paragraphs of their
+//! own.
+
+/// Elements that start and end a
+/// paragraph.
+const BLOCKS: [&str; 33] = [
+  "address",
+  "article",
+  "aside",
+  "blockquote",
+  "caption",
+  "dd",
+  "details",
+  "div",
+  "dl",
+  "dt",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "header",
+  "hr",
+  "li",
+  "main",
+  "ol",
+  "p",
+  "pre",
+  "section",
+  "summary",
+  "table",
+  "tr",
+  "ul"
+];
+
+/// Elements whose content is not text
+/// of the page.
+const SKIPPED: [&str; 9] = [
+  "head", "script", "style",
+  "noscript", "template", "svg",
+  "math", "nav", "iframe"
+];
+
+/// Named character references besides
+/// the numeric ones.
+const ENTITIES: [(&str, &str); 32] = [
+  ("amp", "&"),
+  ("lt", "<"),
+  ("gt", ">"),
+  ("quot", "\""),
+  ("apos", "'"),
+  ("nbsp", " "),
+  ("ndash", "–"),
+  ("mdash", "—"),
+  ("hellip", "…"),
+  ("lsquo", "‘"),
+  ("rsquo", "’"),
+  ("ldquo", "“"),
+  ("rdquo", "”"),
+  ("laquo", "«"),
+  ("raquo", "»"),
+  ("copy", "©"),
+  ("shy", ""),
+  ("auml", "ä"),
+  ("ouml", "ö"),
+  ("uuml", "ü"),
+  ("Auml", "Ä"),
+  ("Ouml", "Ö"),
+  ("Uuml", "Ü"),
+  ("szlig", "ß"),
+  ("aacute", "á"),
+  ("eacute", "é"),
+  ("egrave", "è"),
+  ("iacute", "í"),
+  ("oacute", "ó"),
+  ("uacute", "ú"),
+  ("ccedil", "ç"),
+  ("ntilde", "ñ")
+];
+
+#[derive(Default)]
+struct Writer {
+  paragraphs:   Vec<String>,
+  current:      String,
+  preformatted: bool
+}
+
+impl Writer {
+  fn text(
+    &mut self,
+    text: &str
+  ) {
+    for c in text.chars() {
+      if c == '\n' && self.preformatted
+      {
+        self.line_break();
+      } else if c.is_whitespace() {
+        if !self.current.is_empty()
+          && !self
+            .current
+            .ends_with([' ', '\n'])
+        {
+          self.current.push(' ');
+        }
+      } else {
+        self.current.push(c);
+      }
+    }
+  }
+
+  fn line_break(&mut self) {
+    let trimmed =
+      self.current.trim_end().len();
+    self.current.truncate(trimmed);
+    if !self.current.is_empty() {
+      self.current.push('\n');
+    }
+  }
+
+  fn paragraph(&mut self) {
+    let paragraph = self.current.trim();
+    if !paragraph.is_empty() {
+      self
+        .paragraphs
+        .push(paragraph.to_string());
+    }
+    self.current.clear();
+  }
+}
+
+/// The text of `html`: paragraphs
+/// separated by blank lines, `<br>`
+/// breaking lines within them.
+pub(super) fn text(
+  html: &str
+) -> String {
+  let mut writer = Writer::default();
+  let mut rest = html;
+  while let Some(open) = rest.find('<')
+  {
+    writer.text(&decode(&rest[..open]));
+    rest = &rest[open..];
+    // A "<" that opens no tag is text.
+    if !rest[1..].starts_with(
+      |c: char| {
+        c.is_ascii_alphabetic()
+          || matches!(
+            c,
+            '/' | '!' | '?'
+          )
+      }
+    ) {
+      writer.text("<");
+      rest = &rest[1..];
+      continue;
+    }
+    if let Some(comment) =
+      rest.strip_prefix("<!--")
+    {
+      rest = comment
+        .find("-->")
+        .map_or("", |end| {
+          &comment[end + 3..]
+        });
+      continue;
+    }
+    let Some(close) = rest.find('>')
+    else {
+      rest = "";
+      break;
+    };
+    let tag = &rest[1..close];
+    rest = &rest[close + 1..];
+    let closing = tag.starts_with('/');
+    let name = tag
+      .trim_start_matches('/')
+      .split(|c: char| {
+        c.is_whitespace() || c == '/'
+      })
+      .next()
+      .unwrap_or_default()
+      .to_lowercase();
+    // A self-closing "<svg/>" has no
+    // content to skip.
+    let empty =
+      tag.trim_end().ends_with('/');
+    if !closing
+      && SKIPPED
+        .contains(&name.as_str())
+    {
+      if !empty {
+        rest =
+          skip_element(rest, &name);
+      }
+      continue;
+    }
+    match (name.as_str(), closing) {
+      | ("br", _) => {
+        writer.line_break()
+      }
+      | ("pre", _) => {
+        writer.paragraph();
+        writer.preformatted = !closing;
+      }
+      | ("td" | "th", _) => {
+        writer.text(" ");
+      }
+      | (name, _)
+        if BLOCKS.contains(&name) =>
+      {
+        writer.paragraph();
+      }
+      | _ => {}
+    }
+  }
+  writer.text(&decode(rest));
+  writer.paragraph();
+  writer.paragraphs.join("\n\n")
+}
+
+/// The input after the end tag of a
+/// skipped element.
+fn skip_element<'a>(
+  rest: &'a str,
+  name: &str
+) -> &'a str {
+  let end = format!("</{name}");
+  rest
+    .to_ascii_lowercase()
+    .find(&end)
+    .and_then(|idx| {
+      rest[idx..].find('>').map(
+        |close| {
+          &rest[idx + close + 1..]
+        }
+      )
+    })
+    .unwrap_or("")
+}
+
+/// Text with character references
+/// replaced.
+fn decode(text: &str) -> String {
+  let mut decoded = String::new();
+  let mut rest = text;
+  while let Some(amp) = rest.find('&') {
+    decoded.push_str(&rest[..amp]);
+    rest = &rest[amp..];
+    let reference = rest[1..]
+      .find(';')
+      .filter(|end| *end <= 10)
+      .and_then(|end| {
+        let name = &rest[1..end + 1];
+        character(name)
+          .map(|value| (value, end + 2))
+      });
+    match reference {
+      | Some((value, len)) => {
+        decoded.push_str(&value);
+        rest = &rest[len..];
+      }
+      | None => {
+        decoded.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  decoded.push_str(rest);
+  decoded
+}
+
+fn character(
+  name: &str
+) -> Option<String> {
+  if let Some(number) =
+    name.strip_prefix('#')
+  {
+    let code = match number
+      .strip_prefix(['x', 'X'])
+    {
+      | Some(hex) => {
+        u32::from_str_radix(hex, 16)
+          .ok()
+      }
+      | None => number.parse().ok()
+    }?;
+    return char::from_u32(code)
+      .map(String::from);
+  }
+  ENTITIES
+    .iter()
+    .find(|(entity, _)| *entity == name)
+    .map(|(_, value)| value.to_string())
+}
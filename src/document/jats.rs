@@ -0,0 +1,666 @@
+//! JATS XML text extraction. The title,
+//! abstract and body become paragraphs;
+//! `<ref-list>` entries are kept as the
+//! document's references, with the
+//! fields their markup tags.
+
+use std::ops::Range;
+
+use anyhow::{
+  Context,
+  Result
+};
+use roxmltree::{
+  Node,
+  ParsingOptions
+};
+
+use crate::parser::{
+  Author,
+  FieldValue,
+  Reference
+};
+
+/// Elements read as paragraphs of their
+/// own.
+const BLOCKS: [&str; 8] = [
+  "title",
+  "p",
+  "list-item",
+  "caption",
+  "disp-quote",
+  "def",
+  "term",
+  "statement"
+];
+
+/// Elements without running text.
+const SKIPPED: [&str; 6] = [
+  "table",
+  "graphic",
+  "media",
+  "inline-graphic",
+  "alternatives",
+  "object-id"
+];
+
+/// Citation elements of a `<ref>`.
+const CITATIONS: [&str; 4] = [
+  "mixed-citation",
+  "element-citation",
+  "citation",
+  "nlm-citation"
+];
+
+/// Text of an article, ranges of it
+/// holding the `<ref-list>` entries and
+/// the tagged fields of each entry.
+pub(super) struct Article {
+  pub(super) text:       String,
+  pub(super) references:
+    Vec<Range<usize>>,
+  pub(super) tagged:
+    Vec<Option<Reference>>
+}
+
+/// Reads the text and reference list of
+/// a JATS article.
+pub(super) fn read(
+  xml: &str
+) -> Result<Article> {
+  let document =
+    roxmltree::Document::parse_with_options(
+      xml,
+      ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+      }
+    )
+    .context("parse JATS XML")?;
+  let root = document.root_element();
+  let mut paragraphs = Vec::new();
+  for title in
+    root.descendants().filter(|node| {
+      node.has_tag_name("article-title")
+        && node.ancestors().any(
+          |node| {
+            node.has_tag_name(
+              "title-group"
+            )
+          }
+        )
+    })
+  {
+    paragraphs.push(inline_text(title));
+  }
+  for section in
+    root.descendants().filter(|node| {
+      node.has_tag_name("abstract")
+        || node.has_tag_name("body")
+    })
+  {
+    blocks(section, &mut paragraphs);
+  }
+  let mut text = String::new();
+  let mut references = Vec::new();
+  let mut tagged = Vec::new();
+  for paragraph in
+    paragraphs.iter().filter(
+      |paragraph| !paragraph.is_empty()
+    )
+  {
+    push_paragraph(
+      &mut text, paragraph
+    );
+  }
+  for list in
+    root.descendants().filter(|node| {
+      node.has_tag_name("ref-list")
+    })
+  {
+    if let Some(title) =
+      list.children().find(|node| {
+        node.has_tag_name("title")
+      })
+    {
+      push_paragraph(
+        &mut text,
+        &inline_text(title)
+      );
+    }
+    for entry in
+      list.children().filter(|node| {
+        node.has_tag_name("ref")
+      })
+    {
+      let Some((citation, fields)) =
+        reference(entry)
+      else {
+        continue;
+      };
+      let start = push_paragraph(
+        &mut text, &citation
+      );
+      references
+        .push(start..text.len());
+      tagged.push(fields);
+    }
+  }
+  Ok(Article {
+    text,
+    references,
+    tagged
+  })
+}
+
+/// Appends a paragraph after a blank
+/// line; returns where it starts.
+fn push_paragraph(
+  text: &mut String,
+  paragraph: &str
+) -> usize {
+  if !text.is_empty() {
+    text.push_str("\n\n");
+  }
+  let start = text.len();
+  text.push_str(paragraph);
+  start
+}
+
+/// Paragraphs of the block elements
+/// under `node`, in document order.
+fn blocks(
+  node: Node,
+  paragraphs: &mut Vec<String>
+) {
+  for child in node
+    .children()
+    .filter(Node::is_element)
+  {
+    let name = child.tag_name().name();
+    // References are read on their own.
+    if SKIPPED.contains(&name)
+      || name == "ref-list"
+    {
+      continue;
+    }
+    let nested = child
+      .descendants()
+      .skip(1)
+      .any(|node| {
+        BLOCKS.contains(
+          &node.tag_name().name()
+        )
+      });
+    if BLOCKS.contains(&name) && !nested
+    {
+      paragraphs
+        .push(inline_text(child));
+    } else {
+      blocks(child, paragraphs);
+    }
+  }
+}
+
+/// Text under `node` with whitespace
+/// runs read as single spaces.
+fn inline_text(node: Node) -> String {
+  node
+    .descendants()
+    .filter(|node| {
+      node.is_text()
+        && !node.ancestors().any(
+          |node| {
+            SKIPPED.contains(
+              &node.tag_name().name()
+            )
+          }
+        )
+    })
+    .filter_map(|node| node.text())
+    .collect::<String>()
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// A `<ref>` as a line of text and the
+/// fields its citation tags. Its label
+/// is left out, as numbered citations
+/// resolve by position.
+fn reference(
+  entry: Node
+) -> Option<(String, Option<Reference>)>
+{
+  let citation = entry
+    .descendants()
+    .find(|node| {
+      CITATIONS.contains(
+        &node.tag_name().name()
+      )
+    })?;
+  let text = if citation
+    .has_tag_name("element-citation")
+  {
+    element_citation(citation)
+  } else {
+    inline_text(citation)
+  };
+  if text.is_empty() {
+    return None;
+  }
+  Some((text, tagged_fields(citation)))
+}
+
+/// Fields of a citation read from its
+/// tags, or `None` for an untagged
+/// `<mixed-citation>`, which is left to
+/// the parser.
+fn tagged_fields(
+  citation: Node
+) -> Option<Reference> {
+  let field = |name: &str| {
+    citation
+      .descendants()
+      .find(|node| {
+        node.has_tag_name(name)
+      })
+      .map(inline_text)
+      .filter(|text| !text.is_empty())
+  };
+  let list = |value: String| {
+    FieldValue::List(vec![value])
+  };
+  let mut reference = Reference::new();
+  let authors = names(citation, false)
+    .into_iter()
+    .map(|(family, given)| {
+      Author {
+        family,
+        given
+      }
+    })
+    .collect::<Vec<_>>();
+  if !authors.is_empty() {
+    reference.insert(
+      "author",
+      FieldValue::Authors(authors)
+    );
+  }
+  let editors = names(citation, true)
+    .into_iter()
+    .map(|(family, given)| {
+      if given.is_empty() {
+        family
+      } else {
+        format!("{family}, {given}")
+      }
+    })
+    .collect::<Vec<_>>();
+  if !editors.is_empty() {
+    reference.insert(
+      "editor",
+      FieldValue::List(editors)
+    );
+  }
+  if let Some(year) = field("year") {
+    let digits = year
+      .chars()
+      .take_while(char::is_ascii_digit)
+      .collect::<String>();
+    reference.insert(
+      "date",
+      list(
+        if digits.is_empty() {
+          year
+        } else {
+          digits
+        }
+      )
+    );
+  }
+  let title = field("article-title")
+    .or_else(|| field("chapter-title"));
+  let source = field("source");
+  match (title, source.clone()) {
+    | (Some(title), source) => {
+      reference
+        .insert("title", list(title));
+      if let Some(source) = source {
+        reference.insert(
+          "container-title",
+          list(source)
+        );
+      }
+    }
+    | (None, Some(source)) => {
+      reference
+        .insert("title", list(source));
+    }
+    | (None, None) => {}
+  }
+  let kind = publication_type(
+    citation,
+    field("chapter-title").is_some(),
+    field("article-title").is_some()
+  );
+  if kind == "article-journal"
+    && let Some(source) = source
+  {
+    reference
+      .insert("journal", list(source));
+  }
+  for name in ["volume", "issue"] {
+    if let Some(value) = field(name) {
+      reference
+        .insert(name, list(value));
+    }
+  }
+  match (field("fpage"), field("lpage"))
+  {
+    | (Some(first), Some(last)) => {
+      reference.insert(
+        "pages",
+        list(format!("{first}-{last}"))
+      );
+    }
+    | (Some(first), None) => {
+      reference
+        .insert("pages", list(first));
+    }
+    | _ => {}
+  }
+  if let Some(publisher) =
+    field("publisher-name")
+  {
+    reference.insert(
+      "publisher",
+      list(publisher)
+    );
+  }
+  if let Some(place) =
+    field("publisher-loc")
+  {
+    reference
+      .insert("location", list(place));
+  }
+  let mut identifiers = Vec::new();
+  for id in citation
+    .descendants()
+    .filter(|node| {
+      node.has_tag_name("pub-id")
+    })
+  {
+    let value = inline_text(id);
+    match id.attribute("pub-id-type") {
+      | _ if value.is_empty() => {}
+      | Some("doi") => {
+        reference.insert(
+          "doi",
+          list(format!("doi:{value}"))
+        );
+      }
+      | Some(kind) => {
+        identifiers.push(format!(
+          "{kind}:{value}"
+        ));
+      }
+      | None => identifiers.push(value)
+    }
+  }
+  if !identifiers.is_empty() {
+    reference.insert(
+      "identifier",
+      FieldValue::List(identifiers)
+    );
+  }
+  if reference.fields().is_empty() {
+    return None;
+  }
+  reference.insert(
+    "type",
+    FieldValue::Single(kind.into())
+  );
+  Some(reference)
+}
+
+/// Surname and given names of the
+/// authors, or with `editors` of the
+/// editors, of a citation. Names
+/// outside a `<person-group>` are
+/// authors.
+fn names(
+  citation: Node,
+  editors: bool
+) -> Vec<(String, String)> {
+  citation
+    .descendants()
+    .filter(|node| {
+      node.has_tag_name("name")
+        || node
+          .has_tag_name("string-name")
+        || node.has_tag_name("collab")
+    })
+    .filter(|node| {
+      let group = node
+        .ancestors()
+        .find(|node| {
+          node.has_tag_name(
+            "person-group"
+          )
+        })
+        .and_then(|group| {
+          group.attribute(
+            "person-group-type"
+          )
+        });
+      (group == Some("editor"))
+        == editors
+    })
+    .map(|node| {
+      let part = |name: &str| {
+        node
+          .children()
+          .find(|child| {
+            child.has_tag_name(name)
+          })
+          .map(inline_text)
+      };
+      match part("surname") {
+        | Some(surname) => {
+          (
+            surname,
+            part("given-names")
+              .unwrap_or_default()
+          )
+        }
+        | None => {
+          (
+            inline_text(node),
+            String::new()
+          )
+        }
+      }
+    })
+    .filter(|(family, _)| {
+      !family.is_empty()
+    })
+    .collect()
+}
+
+/// The reference type of a citation,
+/// from its `publication-type` or, when
+/// it has none, from its titles.
+fn publication_type(
+  citation: Node,
+  chapter: bool,
+  article: bool
+) -> &'static str {
+  let kind = citation
+    .attribute("publication-type")
+    .or_else(|| {
+      citation
+        .attribute("citation-type")
+    });
+  match kind {
+    | Some("journal") => {
+      "article-journal"
+    }
+    | Some("book") if chapter => {
+      "chapter"
+    }
+    | Some("book") => "book",
+    | Some(
+      "confproc" | "conf-proc"
+    ) => "paper-conference",
+    | Some("thesis") => "thesis",
+    | Some("report") => "report",
+    | _ if chapter => "chapter",
+    | _ if article => "article-journal",
+    | _ => "book"
+  }
+}
+
+/// An `<element-citation>`, which holds
+/// fields without punctuation, written
+/// out as "Authors (Year). Title.
+/// Source, Volume(Issue), Pages.
+/// Place: Publisher."
+fn element_citation(
+  citation: Node
+) -> String {
+  let field = |name: &str| {
+    citation
+      .descendants()
+      .find(|node| {
+        node.has_tag_name(name)
+      })
+      .map(inline_text)
+      .filter(|text| !text.is_empty())
+  };
+  let mut names = citation
+    .descendants()
+    .filter(|node| {
+      node.has_tag_name("name")
+        || node
+          .has_tag_name("string-name")
+        || node.has_tag_name("collab")
+    })
+    .map(|node| {
+      let part = |name: &str| {
+        node
+          .children()
+          .find(|child| {
+            child.has_tag_name(name)
+          })
+          .map(inline_text)
+      };
+      match (
+        part("surname"),
+        part("given-names")
+      ) {
+        | (
+          Some(surname),
+          Some(given)
+        ) => {
+          format!("{surname}, {given}")
+        }
+        | (Some(surname), None) => {
+          surname
+        }
+        | _ => inline_text(node)
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+  if citation.descendants().any(
+    |node| node.has_tag_name("etal")
+  ) {
+    names.push_str(", et al.");
+  }
+  let mut text = names;
+  if let Some(year) = field("year") {
+    text
+      .push_str(&format!(" ({year})."));
+  } else if !text.is_empty() {
+    text.push('.');
+  }
+  for title in [
+    field("article-title"),
+    field("chapter-title")
+  ]
+  .into_iter()
+  .flatten()
+  {
+    text
+      .push_str(&format!(" {title}."));
+  }
+  let mut details = Vec::new();
+  details.extend(field("source"));
+  match (
+    field("volume"),
+    field("issue")
+  ) {
+    | (Some(volume), Some(issue)) => {
+      details.push(format!(
+        "{volume}({issue})"
+      ));
+    }
+    | (volume, issue) => {
+      details.extend(volume.or(issue));
+    }
+  }
+  match (field("fpage"), field("lpage"))
+  {
+    | (Some(first), Some(last)) => {
+      details.push(format!(
+        "{first}–{last}"
+      ));
+    }
+    | (first, _) => {
+      details.extend(first)
+    }
+  }
+  if !details.is_empty() {
+    text.push_str(&format!(
+      " {}.",
+      details.join(", ")
+    ));
+  }
+  match (
+    field("publisher-loc"),
+    field("publisher-name")
+  ) {
+    | (
+      Some(place),
+      Some(publisher)
+    ) => {
+      text.push_str(&format!(
+        " {place}: {publisher}."
+      ));
+    }
+    | (place, publisher) => {
+      if let Some(value) =
+        place.or(publisher)
+      {
+        text.push_str(&format!(
+          " {value}."
+        ));
+      }
+    }
+  }
+  if let Some(doi) = citation
+    .descendants()
+    .find(|node| {
+      node.has_tag_name("pub-id")
+        && node.attribute("pub-id-type")
+          == Some("doi")
+    })
+    .map(inline_text)
+  {
+    text.push_str(&format!(
+      " https://doi.org/{doi}"
+    ));
+  }
+  text.trim().to_string()
+}
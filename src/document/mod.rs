@@ -0,0 +1,345 @@
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use anyhow::{
+  Context,
+  Result
+};
+use clap::ValueEnum;
+
+mod clean;
+mod encoding;
+mod html;
+mod jats;
+mod page;
+mod pdf;
+
+pub use clean::CleanText;
+pub use page::{
+  Line,
+  Page
+};
+
+use crate::parser::Reference;
+
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+  pages:      Vec<Page>,
+  references: Vec<Range<usize>>,
+  tagged:     Vec<Option<Reference>>
+}
+
+/// How to read an input file.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  ValueEnum,
+)]
+pub enum InputFormat {
+  /// Plain text in any supported
+  /// encoding
+  Text,
+  Pdf,
+  Html,
+  /// JATS XML full text
+  Jats
+}
+
+impl InputFormat {
+  /// The format a file extension
+  /// stands for: `.pdf`, `.html` or
+  /// `.htm`, `.xml` or `.nxml` for
+  /// JATS, and `.txt`.
+  pub fn from_path<P: AsRef<Path>>(
+    path: P
+  ) -> Option<Self> {
+    let extension = path
+      .as_ref()
+      .extension()?
+      .to_str()?
+      .to_ascii_lowercase();
+    match extension.as_str() {
+      | "pdf" => Some(Self::Pdf),
+      | "html" | "htm" | "xhtml" => {
+        Some(Self::Html)
+      }
+      | "xml" | "nxml" | "jats" => {
+        Some(Self::Jats)
+      }
+      | "txt" | "text" => {
+        Some(Self::Text)
+      }
+      | _ => None
+    }
+  }
+}
+
+impl Document {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Pages split at form feeds. Line
+  /// offsets point into `text`.
+  pub fn from_text(text: &str) -> Self {
+    let mut start = 0;
+    let mut pages = Vec::new();
+    for segment in
+      text.split('\u{000C}')
+    {
+      let trimmed = segment.trim();
+      let mut page =
+        Page::from_text(trimmed);
+      page.offset_by(
+        start + segment.len()
+          - segment.trim_start().len()
+      );
+      pages.push(page);
+      start += segment.len() + 1;
+    }
+
+    if pages.is_empty() {
+      Self::default()
+    } else {
+      Self {
+        pages,
+        ..Self::default()
+      }
+    }
+  }
+
+  /// The text of an HTML page without
+  /// markup. Headings, paragraphs and
+  /// other blocks are separated by
+  /// blank lines, `<br>` breaks
+  /// lines, and each list item
+  /// becomes a paragraph of its own.
+  pub fn from_html(html: &str) -> Self {
+    Self::from_text(&html::text(html))
+  }
+
+  /// The title, abstract and body of a
+  /// JATS article followed by its
+  /// reference list. Each `<ref>` is
+  /// one paragraph, listed in
+  /// [`Document::references`], with
+  /// its tagged fields in
+  /// [`Document::tagged_references`].
+  pub fn from_jats(
+    xml: &str
+  ) -> Result<Self> {
+    let article = jats::read(xml)?;
+    Ok(Self {
+      references: article.references,
+      tagged: article.tagged,
+      ..Self::from_text(&article.text)
+    })
+  }
+
+  /// Text of each PDF page, lines in
+  /// reading order and paragraphs
+  /// separated by blank lines. Lines
+  /// keep their position, font size and
+  /// weight; offsets point into
+  /// [`Document::text`].
+  pub fn from_pdf(
+    data: &[u8]
+  ) -> Result<Self> {
+    let mut start = 0;
+    let pages = pdf::extract(data)?
+      .into_iter()
+      .map(|lines| {
+        let mut page = Page::from_lines(
+          pdf::lines(lines)
+        );
+        page.offset_by(start);
+        start += page.text().len() + 1;
+        page
+      })
+      .collect();
+    Ok(Self {
+      pages,
+      ..Self::default()
+    })
+  }
+
+  /// Whether `data` is a PDF file.
+  pub fn is_pdf(data: &[u8]) -> bool {
+    pdf::is_pdf(data)
+  }
+
+  /// Reads a file in the format its
+  /// extension names, see
+  /// [`InputFormat::from_path`], or
+  /// else as [`Document::from_bytes`]
+  /// does.
+  pub fn open<P: AsRef<Path>>(
+    path: P
+  ) -> Result<Self> {
+    let format =
+      InputFormat::from_path(&path);
+    Self::open_as(path, format)
+  }
+
+  /// Reads a file in `format`, or
+  /// telling PDF from text by its
+  /// header when `None`.
+  pub fn open_as<P: AsRef<Path>>(
+    path: P,
+    format: Option<InputFormat>
+  ) -> Result<Self> {
+    let path = path.as_ref();
+    let data = fs::read(path)
+      .with_context(|| {
+        format!(
+          "read {}",
+          path.display()
+        )
+      })?;
+    match format {
+      | Some(format) => {
+        Self::from_bytes_as(
+          &data, format
+        )
+      }
+      | None => Self::from_bytes(&data)
+    }
+    .with_context(|| {
+      format!(
+        "decode {}",
+        path.display()
+      )
+    })
+  }
+
+  /// A PDF, recognised by its header,
+  /// or text in UTF-8, UTF-16,
+  /// Windows-1252 or Latin-1, see
+  /// [`Document::decode`].
+  pub fn from_bytes(
+    data: &[u8]
+  ) -> Result<Self> {
+    if pdf::is_pdf(data) {
+      return Self::from_pdf(data);
+    }
+    Ok(Self::from_text(&Self::decode(
+      data
+    )?))
+  }
+
+  /// `data` read as `format`; text,
+  /// HTML and JATS are transcoded as by
+  /// [`Document::decode`].
+  pub fn from_bytes_as(
+    data: &[u8],
+    format: InputFormat
+  ) -> Result<Self> {
+    match format {
+      | InputFormat::Pdf => {
+        Self::from_pdf(data)
+      }
+      | InputFormat::Text => {
+        Ok(Self::from_text(
+          &Self::decode(data)?
+        ))
+      }
+      | InputFormat::Html => {
+        Ok(Self::from_html(
+          &Self::decode(data)?
+        ))
+      }
+      | InputFormat::Jats => {
+        Self::from_jats(&Self::decode(
+          data
+        )?)
+      }
+    }
+  }
+
+  /// Text file contents transcoded to
+  /// UTF-8. A byte order mark decides
+  /// the encoding; UTF-16 without one
+  /// is recognised by its zero bytes.
+  /// Bytes that are not UTF-8 are
+  /// read as Windows-1252, or as
+  /// Latin-1 when they use codes
+  /// Windows-1252 leaves undefined.
+  /// Binary data is an error.
+  pub fn decode(
+    data: &[u8]
+  ) -> Result<String> {
+    encoding::decode(data)
+  }
+
+  /// Page texts separated by form
+  /// feeds, as read by
+  /// [`Document::from_text`].
+  pub fn text(&self) -> String {
+    self
+      .pages
+      .iter()
+      .map(Page::text)
+      .collect::<Vec<_>>()
+      .join("\u{000C}")
+  }
+
+  pub fn pages(&self) -> Vec<Page> {
+    self.pages.clone()
+  }
+
+  /// Ranges of [`Document::text`] the
+  /// source marks as references, such
+  /// as the entries of a JATS
+  /// `<ref-list>`; empty for other
+  /// input.
+  pub fn references(
+    &self
+  ) -> &[Range<usize>] {
+    &self.references
+  }
+
+  /// The fields the source tags for
+  /// each of [`Document::references`]:
+  /// JATS element citations and tagged
+  /// mixed citations need no parsing.
+  /// `None` for untagged entries; empty
+  /// for other input.
+  pub fn tagged_references(
+    &self
+  ) -> &[Option<Reference>] {
+    &self.tagged
+  }
+
+  /// Page index and line holding source
+  /// offset `offset`: the last line
+  /// starting at or before it.
+  pub fn line_at(
+    &self,
+    offset: usize
+  ) -> Option<(usize, &Line)> {
+    self
+      .pages
+      .iter()
+      .enumerate()
+      .flat_map(|(page_idx, page)| {
+        page.lines().iter().map(
+          move |line| (page_idx, line)
+        )
+      })
+      .take_while(|(_, line)| {
+        line.offset() <= offset
+      })
+      .last()
+  }
+
+  pub fn add_page(
+    &mut self,
+    page: Page
+  ) {
+    self.pages.push(page);
+  }
+}
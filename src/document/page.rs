@@ -0,0 +1,235 @@
+/// A line of a page. PDF lines carry
+/// their position in points, measured
+/// from the top left corner, with font
+/// size and weight when the fonts tell.
+/// Plain-text lines use their line
+/// index as `y` and leading whitespace
+/// as `x` and `indent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+  text:      String,
+  index:     usize,
+  offset:    usize,
+  x:         f32,
+  y:         f32,
+  indent:    f32,
+  font_size: Option<f32>,
+  bold:      Option<bool>
+}
+
+impl Line {
+  /// A line of `text` at line `index`
+  /// of its page.
+  pub fn new(
+    index: usize,
+    text: &str
+  ) -> Self {
+    Self {
+      text: text.to_string(),
+      index,
+      offset: 0,
+      x: 0.0,
+      y: index as f32,
+      indent: 0.0,
+      font_size: None,
+      bold: None
+    }
+  }
+
+  pub fn with_position(
+    mut self,
+    x: f32,
+    y: f32
+  ) -> Self {
+    self.x = x;
+    self.y = y;
+    self
+  }
+
+  pub fn with_indent(
+    mut self,
+    indent: f32
+  ) -> Self {
+    self.indent = indent;
+    self
+  }
+
+  pub fn with_font_size(
+    mut self,
+    size: f32
+  ) -> Self {
+    self.font_size = Some(size);
+    self
+  }
+
+  pub fn with_bold(
+    mut self,
+    bold: bool
+  ) -> Self {
+    self.bold = Some(bold);
+    self
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// Line number within
+  /// [`Page::text`].
+  pub fn index(&self) -> usize {
+    self.index
+  }
+
+  /// Byte offset of the line in the
+  /// document source text (the page
+  /// text for a standalone page).
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+
+  pub fn x(&self) -> f32 {
+    self.x
+  }
+
+  pub fn y(&self) -> f32 {
+    self.y
+  }
+
+  /// Offset from the left edge of the
+  /// line's column.
+  pub fn indent(&self) -> f32 {
+    self.indent
+  }
+
+  pub fn font_size(
+    &self
+  ) -> Option<f32> {
+    self.font_size
+  }
+
+  pub fn bold(&self) -> Option<bool> {
+    self.bold
+  }
+
+  /// Indented by more than half an em,
+  /// or by any whitespace in plain
+  /// text.
+  pub fn is_indented(&self) -> bool {
+    self.indent
+      > self
+        .font_size
+        .map_or(0.5, |size| size * 0.5)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Page {
+  text:  String,
+  lines: Vec<Line>
+}
+
+impl Page {
+  /// A page of plain text; blank lines
+  /// keep their index but get no
+  /// [`Line`].
+  pub fn from_text(text: &str) -> Self {
+    let mut offset = 0;
+    let mut lines = Vec::new();
+    for (index, raw) in text
+      .split_inclusive('\n')
+      .enumerate()
+    {
+      let start = offset;
+      offset += raw.len();
+      let line = raw.trim_end();
+      let content = line.trim_start();
+      if content.is_empty() {
+        continue;
+      }
+      let indent = line
+        .chars()
+        .take_while(|c| {
+          c.is_whitespace()
+        })
+        .count()
+        as f32;
+      let mut line =
+        Line::new(index, content)
+          .with_position(
+            indent,
+            index as f32
+          )
+          .with_indent(indent);
+      line.offset = start + raw.len()
+        - raw.trim_start().len();
+      lines.push(line);
+    }
+    Self {
+      text: text.to_string(),
+      lines
+    }
+  }
+
+  /// A page built from laid-out lines;
+  /// gaps between line indices become
+  /// blank lines of the page text.
+  pub fn from_lines(
+    lines: Vec<Line>
+  ) -> Self {
+    let mut text = String::new();
+    let mut lines = lines;
+    let mut next = 0;
+    for line in &mut lines {
+      let breaks =
+        line.index.saturating_sub(next)
+          + usize::from(next > 0);
+      text
+        .push_str(&"\n".repeat(breaks));
+      line.offset = text.len();
+      text.push_str(&line.text);
+      next = line.index + 1;
+    }
+    Self {
+      text,
+      lines
+    }
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn lines(&self) -> &[Line] {
+    &self.lines
+  }
+
+  /// Moves line offsets from the page
+  /// text to a document starting the
+  /// page at `start`.
+  pub(super) fn offset_by(
+    &mut self,
+    start: usize
+  ) {
+    for line in &mut self.lines {
+      line.offset += start;
+    }
+  }
+
+  /// Median font size of the page's
+  /// lines, when the source gives
+  /// sizes.
+  pub fn body_font_size(
+    &self
+  ) -> Option<f32> {
+    let mut sizes = self
+      .lines
+      .iter()
+      .filter_map(Line::font_size)
+      .collect::<Vec<_>>();
+    if sizes.is_empty() {
+      return None;
+    }
+    sizes.sort_by(f32::total_cmp);
+    Some(sizes[sizes.len() / 2])
+  }
+}
@@ -0,0 +1,586 @@
+//! PDF text extraction. Glyph positions
+//! reported by `pdf-extract` are
+//! grouped into lines, and two-column
+//! pages are read column by column.
+
+use std::collections::BTreeMap;
+use std::panic::{
+  self,
+  AssertUnwindSafe
+};
+
+use anyhow::{
+  Context,
+  Result,
+  anyhow
+};
+use pdf_extract::content::Content;
+use pdf_extract::{
+  Dictionary,
+  MediaBox,
+  Object,
+  OutputDev,
+  OutputError,
+  Transform
+};
+
+use super::Line;
+
+/// Gap between glyphs, relative to the
+/// font size, that separates words.
+const WORD_GAP: f64 = 0.15;
+/// Gap that separates the fragments of
+/// a row, such as two columns.
+const FRAGMENT_GAP: f64 = 1.5;
+/// Baseline distance, relative to the
+/// font size, that starts a paragraph.
+const PARAGRAPH_GAP: f64 = 1.8;
+/// Rows that must have text on both
+/// sides of a gutter before a page is
+/// read as two columns.
+const MIN_COLUMN_ROWS: usize = 2;
+
+/// Whether `data` starts like a PDF
+/// file.
+pub(super) fn is_pdf(
+  data: &[u8]
+) -> bool {
+  data.starts_with(b"%PDF-")
+}
+
+/// A line of a PDF page in points; `y`
+/// is the baseline measured down from
+/// the top of the page and `indent` the
+/// offset from the left edge of its
+/// column.
+#[derive(Debug, Clone)]
+pub(super) struct TextLine {
+  pub(super) text:   String,
+  pub(super) x:      f64,
+  pub(super) end:    f64,
+  pub(super) y:      f64,
+  pub(super) indent: f64,
+  pub(super) size:   f64,
+  pub(super) bold:   Option<bool>
+}
+
+/// Lines of every page, in reading
+/// order.
+pub(super) fn extract(
+  data: &[u8]
+) -> Result<Vec<Vec<TextLine>>> {
+  let mut doc =
+    pdf_extract::Document::load_mem(
+      data
+    )
+    .context("read pdf")?;
+  if doc.is_encrypted() {
+    doc
+      .decrypt("")
+      .context("decrypt pdf")?;
+  }
+  let mut collector = Collector {
+    bold: bold_runs(&doc),
+    ..Collector::default()
+  };
+  // pdf-extract panics on some
+  // malformed fonts; report those as
+  // errors like any other bad input.
+  panic::catch_unwind(
+    AssertUnwindSafe(|| {
+      pdf_extract::output_doc(
+        &doc,
+        &mut collector
+      )
+    })
+  )
+  .map_err(|_| {
+    anyhow!("extract pdf text")
+  })?
+  .context("extract pdf text")?;
+  Ok(collector.pages)
+}
+
+struct Glyph {
+  x:    f64,
+  end:  f64,
+  y:    f64,
+  size: f64,
+  text: String,
+  run:  usize,
+  bold: Option<bool>
+}
+
+/// Receives glyphs from `pdf-extract`.
+/// Each text-showing string starts a
+/// word, which lines glyphs up with the
+/// fonts read by [`bold_runs`].
+#[derive(Default)]
+struct Collector {
+  pages:  Vec<Vec<TextLine>>,
+  glyphs: Vec<Glyph>,
+  bold:   BTreeMap<u32, Vec<bool>>,
+  page:   u32,
+  runs:   usize,
+  left:   f64,
+  top:    f64,
+  width:  f64
+}
+
+impl OutputDev for Collector {
+  fn begin_page(
+    &mut self,
+    page_num: u32,
+    media_box: &MediaBox,
+    _art_box: Option<(
+      f64,
+      f64,
+      f64,
+      f64
+    )>
+  ) -> Result<(), OutputError> {
+    self.glyphs.clear();
+    self.page = page_num;
+    self.runs = 0;
+    self.left = media_box.llx;
+    self.top = media_box.ury;
+    self.width =
+      media_box.urx - media_box.llx;
+    Ok(())
+  }
+
+  fn end_page(
+    &mut self
+  ) -> Result<(), OutputError> {
+    let mut glyphs =
+      std::mem::take(&mut self.glyphs);
+    // Fonts are only trusted when every
+    // run was seen; form XObjects add
+    // runs the page content lacks.
+    if let Some(bold) =
+      self.bold.get(&self.page).filter(
+        |bold| bold.len() == self.runs
+      )
+    {
+      for glyph in &mut glyphs {
+        glyph.bold =
+          bold.get(glyph.run).copied();
+      }
+    }
+    self.pages.push(layout(
+      glyphs, self.left, self.width
+    ));
+    Ok(())
+  }
+
+  fn output_character(
+    &mut self,
+    trm: &Transform,
+    width: f64,
+    _spacing: f64,
+    font_size: f64,
+    char: &str
+  ) -> Result<(), OutputError> {
+    if char.trim().is_empty() {
+      return Ok(());
+    }
+    let scale = (trm.m11 * trm.m22
+      - trm.m12 * trm.m21)
+      .abs()
+      .sqrt();
+    let size = font_size * scale;
+    self.glyphs.push(Glyph {
+      x: trm.m31,
+      end: trm.m31 + width * size,
+      y: self.top - trm.m32,
+      size,
+      text: char.to_string(),
+      run: self.runs.saturating_sub(1),
+      bold: None
+    });
+    Ok(())
+  }
+
+  fn begin_word(
+    &mut self
+  ) -> Result<(), OutputError> {
+    self.runs += 1;
+    Ok(())
+  }
+
+  fn end_word(
+    &mut self
+  ) -> Result<(), OutputError> {
+    Ok(())
+  }
+
+  fn end_line(
+    &mut self
+  ) -> Result<(), OutputError> {
+    Ok(())
+  }
+}
+
+/// Groups glyphs into rows by baseline,
+/// splits rows at wide gaps and orders
+/// the fragments for reading.
+fn layout(
+  mut glyphs: Vec<Glyph>,
+  left: f64,
+  width: f64
+) -> Vec<TextLine> {
+  glyphs.sort_by(|a, b| {
+    a.y
+      .total_cmp(&b.y)
+      .then(a.x.total_cmp(&b.x))
+  });
+  let mut rows =
+    Vec::<Vec<Glyph>>::new();
+  for glyph in glyphs {
+    match rows.last_mut() {
+      | Some(row)
+        if (row[0].y - glyph.y)
+          .abs()
+          <= row[0]
+            .size
+            .max(glyph.size)
+            * 0.5 =>
+      {
+        row.push(glyph);
+      }
+      | _ => rows.push(vec![glyph])
+    }
+  }
+  let rows = rows
+    .into_iter()
+    .map(fragments)
+    .collect::<Vec<_>>();
+  reading_order(rows, left, width)
+}
+
+/// Splits a row into runs of text
+/// separated by more than
+/// [`FRAGMENT_GAP`].
+fn fragments(
+  mut row: Vec<Glyph>
+) -> Vec<TextLine> {
+  row.sort_by(|a, b| {
+    a.x.total_cmp(&b.x)
+  });
+  let mut lines =
+    Vec::<TextLine>::new();
+  for glyph in row {
+    match lines.last_mut() {
+      | Some(line)
+        if glyph.x - line.end
+          <= FRAGMENT_GAP
+            * glyph.size =>
+      {
+        if glyph.x - line.end
+          > WORD_GAP * glyph.size
+        {
+          line.text.push(' ');
+        }
+        line.text.push_str(&glyph.text);
+        line.end =
+          line.end.max(glyph.end);
+        line.size =
+          line.size.max(glyph.size);
+        line.bold = line
+          .bold
+          .zip(glyph.bold)
+          .map(|(line, glyph)| {
+            line && glyph
+          });
+      }
+      | _ => {
+        lines.push(TextLine {
+          text:   glyph.text,
+          x:      glyph.x,
+          end:    glyph.end,
+          y:      glyph.y,
+          indent: 0.0,
+          size:   glyph.size,
+          bold:   glyph.bold
+        })
+      }
+    }
+  }
+  lines
+}
+
+/// Reads two-column stretches left
+/// column first. Rows with text across
+/// the gutter (titles, footers) end a
+/// stretch and keep their place.
+fn reading_order(
+  rows: Vec<Vec<TextLine>>,
+  left: f64,
+  width: f64
+) -> Vec<TextLine> {
+  let gutter =
+    find_gutter(&rows, left, width);
+  let column = |line: &TextLine| {
+    gutter.is_some_and(|gutter| {
+      line.x >= gutter
+    })
+  };
+  let mut ordered = Vec::new();
+  let mut columns =
+    (Vec::new(), Vec::new());
+  for row in rows {
+    if gutter.is_none_or(|gutter| {
+      row.iter().any(|line| {
+        line.x < gutter
+          && line.end > gutter
+      })
+    }) {
+      ordered.append(&mut columns.0);
+      ordered.append(&mut columns.1);
+      ordered.extend(row);
+      continue;
+    }
+    for line in row {
+      if column(&line) {
+        columns.1.push(line);
+      } else {
+        columns.0.push(line);
+      }
+    }
+  }
+  ordered.append(&mut columns.0);
+  ordered.append(&mut columns.1);
+  for side in [false, true] {
+    let starts = ordered
+      .iter()
+      .filter(|line| {
+        column(line) == side
+      })
+      .map(|line| line.x)
+      .collect::<Vec<_>>();
+    let edge = column_edge(&starts)
+      .unwrap_or(left);
+    for line in
+      ordered.iter_mut().filter(
+        |line| column(line) == side
+      )
+    {
+      line.indent =
+        (line.x - edge).max(0.0);
+    }
+  }
+  ordered
+}
+
+/// Leftmost start shared by at least
+/// two lines, so that a lone page
+/// number or centred heading does not
+/// set the column edge.
+fn column_edge(
+  starts: &[f64]
+) -> Option<f64> {
+  let mut counts =
+    BTreeMap::<i64, usize>::new();
+  for x in starts {
+    *counts
+      .entry(x.round() as i64)
+      .or_default() += 1;
+  }
+  counts
+    .iter()
+    .find(|(_, count)| **count >= 2)
+    .map(|(x, _)| *x as f64)
+    .or_else(|| {
+      starts
+        .iter()
+        .copied()
+        .reduce(f64::min)
+    })
+}
+
+/// The x position in the middle of the
+/// page crossed by the fewest lines,
+/// provided enough rows have text on
+/// both sides of it.
+fn find_gutter(
+  rows: &[Vec<TextLine>],
+  left: f64,
+  width: f64
+) -> Option<f64> {
+  let from = (width * 0.3) as usize;
+  let to = (width * 0.7) as usize;
+  (from..=to)
+    .map(|offset| left + offset as f64)
+    .filter_map(|gutter| {
+      let split = rows
+        .iter()
+        .filter(|row| {
+          row.iter().any(|line| {
+            line.end <= gutter
+          }) && row.iter().any(|line| {
+            line.x >= gutter
+          })
+        })
+        .count();
+      let crossing = rows
+        .iter()
+        .flatten()
+        .filter(|line| {
+          line.x < gutter
+            && line.end > gutter
+        })
+        .count();
+      (split >= MIN_COLUMN_ROWS
+        && crossing < split)
+        .then_some((
+          crossing,
+          usize::MAX - split,
+          gutter
+        ))
+    })
+    .min_by(|a, b| {
+      (a.0, a.1).cmp(&(b.0, b.1))
+    })
+    .map(|(_, _, gutter)| gutter)
+}
+
+/// Page lines numbered so that a
+/// vertical gap leaves a blank line,
+/// marking a new paragraph.
+pub(super) fn lines(
+  lines: Vec<TextLine>
+) -> Vec<Line> {
+  let mut index = 0;
+  let mut previous: Option<(f64, f64)> =
+    None;
+  lines
+    .into_iter()
+    .map(|line| {
+      if let Some((y, size)) = previous
+      {
+        let gap = line.y - y;
+        index += 1;
+        if gap
+          > PARAGRAPH_GAP
+            * size.max(line.size)
+        {
+          index += 1;
+        }
+      }
+      previous =
+        Some((line.y, line.size));
+      let mut page_line =
+        Line::new(index, &line.text)
+          .with_position(
+            line.x as f32,
+            line.y as f32
+          )
+          .with_indent(
+            line.indent as f32
+          )
+          .with_font_size(
+            line.size as f32
+          );
+      if let Some(bold) = line.bold {
+        page_line =
+          page_line.with_bold(bold);
+      }
+      page_line
+    })
+    .collect()
+}
+
+/// Whether each text-showing string of
+/// a page is set in a bold font, in
+/// content stream order.
+fn bold_runs(
+  doc: &pdf_extract::Document
+) -> BTreeMap<u32, Vec<bool>> {
+  doc
+    .get_pages()
+    .into_iter()
+    .filter_map(|(page, id)| {
+      let fonts =
+        doc.get_page_fonts(id).ok()?;
+      let content = Content::decode(
+        &doc
+          .get_page_content(id)
+          .ok()?
+      )
+      .ok()?;
+      let mut bold = false;
+      let mut saved = Vec::new();
+      let mut runs = Vec::new();
+      for operation in
+        content.operations
+      {
+        let operand =
+          operation.operands.first();
+        match operation
+          .operator
+          .as_str()
+        {
+          | "q" => saved.push(bold),
+          | "Q" => {
+            bold = saved
+              .pop()
+              .unwrap_or(bold);
+          }
+          | "Tf" => {
+            bold = operand
+              .and_then(|name| {
+                name.as_name().ok()
+              })
+              .and_then(|name| {
+                fonts.get(name)
+              })
+              .is_some_and(|font| {
+                is_bold_font(font)
+              });
+          }
+          | "Tj" => runs.push(bold),
+          | "TJ" => {
+            if let Some(
+              Object::Array(items)
+            ) = operand
+            {
+              runs.extend(
+                items
+                  .iter()
+                  .filter(|item| {
+                    matches!(
+                      item,
+                      Object::String(
+                        ..
+                      )
+                    )
+                  })
+                  .map(|_| bold)
+              );
+            }
+          }
+          | _ => {}
+        }
+      }
+      Some((page, runs))
+    })
+    .collect()
+}
+
+fn is_bold_font(
+  font: &Dictionary
+) -> bool {
+  font
+    .get(b"BaseFont")
+    .and_then(Object::as_name)
+    .is_ok_and(|name| {
+      let name =
+        String::from_utf8_lossy(name)
+          .to_lowercase();
+      ["bold", "black", "heavy"]
+        .iter()
+        .any(|weight| {
+          name.contains(weight)
+        })
+    })
+}
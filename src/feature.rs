@@ -47,3 +47,84 @@ pub mod number {
     }
   }
 }
+
+pub mod layout {
+  use crate::document::Page;
+
+  #[derive(
+    Debug, Clone, Copy, PartialEq, Eq,
+  )]
+  pub enum Observation {
+    /// Set larger than the page body,
+    /// as headings are.
+    LargerFont,
+    Bold,
+    /// Flush line followed by an
+    /// indented one, the shape of a
+    /// reference with a hanging indent.
+    HangingIndent,
+    Indented
+  }
+
+  /// Layout cues of page lines for the
+  /// finder.
+  #[derive(Debug)]
+  pub struct Feature;
+
+  impl Default for Feature {
+    fn default() -> Self {
+      Self
+    }
+  }
+
+  impl Feature {
+    pub fn new() -> Self {
+      Self
+    }
+
+    /// Cues of the `position`-th entry
+    /// of [`Page::lines`].
+    pub fn observe(
+      &self,
+      page: &Page,
+      position: usize
+    ) -> Vec<Observation> {
+      let lines = page.lines();
+      let Some(line) =
+        lines.get(position)
+      else {
+        return Vec::new();
+      };
+      let mut observations = Vec::new();
+      if let (Some(size), Some(body)) = (
+        line.font_size(),
+        page.body_font_size()
+      ) && size > body * 1.15
+      {
+        observations.push(
+          Observation::LargerFont
+        );
+      }
+      if line.bold() == Some(true) {
+        observations
+          .push(Observation::Bold);
+      }
+      if line.is_indented() {
+        observations
+          .push(Observation::Indented);
+      } else if lines
+        .get(position + 1)
+        .is_some_and(|next| {
+          next.index()
+            == line.index() + 1
+            && next.is_indented()
+        })
+      {
+        observations.push(
+          Observation::HangingIndent
+        );
+      }
+      observations
+    }
+  }
+}
@@ -0,0 +1,820 @@
+//! Finds in-text citations, numeric
+//! ("[12]", "[3–5]") and author-year
+//! ("(Smith & Doe, 2020a)", "Smith
+//! (2020)"), and links them to parsed
+//! references.
+
+use std::ops::Range;
+
+use crate::parser::{
+  FieldValue,
+  Reference
+};
+
+/// Largest range expanded from a
+/// numeric citation such as "[3–5]".
+const MAX_RANGE: usize = 50;
+
+/// Words closing a sentence only in
+/// appearance, lowercased.
+const ABBREVIATIONS: [&str; 12] = [
+  "al.", "e.g.", "i.e.", "cf.", "p.",
+  "pp.", "vol.", "no.", "fig.", "eq.",
+  "ch.", "ed."
+];
+
+/// Words introducing a citation inside
+/// parentheses, lowercased.
+const SIGNALS: [&str; 6] = [
+  "see also", "see", "e.g.", "cf.",
+  "vgl.", "compare"
+];
+
+/// Lowercase words that may belong to
+/// a surname ("van der Berg").
+const PARTICLES: [&str; 8] = [
+  "van", "von", "der", "den", "de",
+  "da", "del", "le"
+];
+
+/// Longest context on either side of a
+/// citation, in bytes.
+const MAX_CONTEXT: usize = 400;
+
+/// What a citation points at.
+#[derive(
+  Debug, Clone, PartialEq, Eq,
+)]
+pub enum CitationKey {
+  /// A reference number.
+  Number(usize),
+  /// Author surnames and a year such
+  /// as "2020", "2020a" or "n.d.".
+  AuthorYear {
+    authors: Vec<String>,
+    et_al:   bool,
+    year:    String
+  }
+}
+
+/// A citation in running text.
+#[derive(
+  Debug, Clone, PartialEq, Eq,
+)]
+pub struct InTextCitation {
+  /// The citation as written.
+  pub text:         String,
+  pub span:         Range<usize>,
+  pub keys:         Vec<CitationKey>,
+  /// The sentence around the
+  /// citation, line breaks read as
+  /// spaces.
+  pub context:      String,
+  pub context_span: Range<usize>,
+  /// Indices of the references each
+  /// key resolves to, in key order;
+  /// filled by
+  /// [`Finder::link_citations`](super::Finder::link_citations).
+  pub resolved:     Vec<Vec<usize>>
+}
+
+pub(super) fn find(
+  input: &str
+) -> Vec<InTextCitation> {
+  let mut citations = Vec::new();
+  for (open, close) in
+    [('[', ']'), ('(', ')')]
+  {
+    for span in
+      brackets(input, open, close)
+    {
+      let inner = &input
+        [span.start + 1..span.end - 1];
+      let found = if open == '[' {
+        numbers(inner).map(|keys| {
+          (span.start, keys)
+        })
+      } else if let Some(keys) =
+        author_years(inner)
+      {
+        Some((span.start, keys))
+      } else {
+        // "Smith (2020)" starts with
+        // its author.
+        narrative(
+          &input[..span.start],
+          inner
+        )
+      };
+      if let Some((start, keys)) = found
+      {
+        citations.push(citation(
+          input,
+          start..span.end,
+          keys
+        ));
+      }
+    }
+  }
+  citations.sort_by_key(|citation| {
+    citation.span.start
+  });
+  citations
+}
+
+/// Sets [`InTextCitation::resolved`]
+/// from `references`. Numbers match a
+/// reference's `citation-number`, or
+/// its position when no reference has
+/// one. Author-year keys match the
+/// first author's family name and the
+/// year; a year suffix ("2020b") picks
+/// among several matches in order.
+pub(super) fn link(
+  citations: &mut [InTextCitation],
+  references: &[Reference]
+) {
+  let numbered = references.iter().any(
+    |reference| {
+      citation_number(reference)
+        .is_some()
+    }
+  );
+  for citation in citations {
+    citation.resolved = citation
+      .keys
+      .iter()
+      .map(|key| match key {
+        | CitationKey::Number(number) => {
+          if numbered {
+            references
+              .iter()
+              .enumerate()
+              .filter(|(_, reference)| {
+                citation_number(reference)
+                  == Some(*number)
+              })
+              .map(|(idx, _)| idx)
+              .collect()
+          } else {
+            (1..=references.len())
+              .contains(number)
+              .then(|| number - 1)
+              .into_iter()
+              .collect()
+          }
+        }
+        | CitationKey::AuthorYear {
+          authors,
+          et_al,
+          year
+        } => {
+          author_year_matches(
+            references, authors, *et_al,
+            year
+          )
+        }
+      })
+      .collect();
+  }
+}
+
+fn citation(
+  input: &str,
+  span: Range<usize>,
+  keys: Vec<CitationKey>
+) -> InTextCitation {
+  let context_span =
+    sentence(input, span.clone());
+  InTextCitation {
+    text: flatten(&input[span.clone()]),
+    span,
+    keys,
+    context: flatten(
+      &input[context_span.clone()]
+    ),
+    context_span,
+    resolved: Vec::new()
+  }
+}
+
+/// Ranges of bracketed text, brackets
+/// included, without line breaks
+/// between blank lines.
+fn brackets(
+  input: &str,
+  open: char,
+  close: char
+) -> Vec<Range<usize>> {
+  let mut ranges = Vec::new();
+  let mut start = None;
+  for (idx, c) in input.char_indices() {
+    if c == open {
+      start = Some(idx);
+    } else if c == close
+      && let Some(begin) = start.take()
+    {
+      ranges.push(begin..idx + 1);
+    } else if c == '\n'
+      && start.is_some_and(|begin| {
+        input[begin..idx]
+          .ends_with('\n')
+      })
+    {
+      start = None;
+    }
+  }
+  ranges
+}
+
+/// "12", "3–5", "1, 4-6; 9".
+fn numbers(
+  inner: &str
+) -> Option<Vec<CitationKey>> {
+  let mut keys = Vec::new();
+  for part in inner.split([',', ';']) {
+    let part = part.trim();
+    let bounds = part
+      .split(['-', '–', '—'])
+      .map(|bound| {
+        let bound = bound.trim();
+        (!bound.is_empty()
+          && bound.len() <= 3
+          && bound.chars().all(|c| {
+            c.is_ascii_digit()
+          }))
+        .then(|| {
+          bound.parse::<usize>().ok()
+        })
+        .flatten()
+      })
+      .collect::<Option<Vec<_>>>()?;
+    match bounds.as_slice() {
+      | [number] => {
+        keys.push(CitationKey::Number(
+          *number
+        ));
+      }
+      | [from, to]
+        if from <= to
+          && to - from <= MAX_RANGE =>
+      {
+        keys.extend(
+          (*from..=*to)
+            .map(CitationKey::Number)
+        );
+      }
+      | _ => return None
+    }
+  }
+  (!keys.is_empty()).then_some(keys)
+}
+
+/// "Smith & Doe, 2020a; Roe et al.
+/// 2019, 2021".
+fn author_years(
+  inner: &str
+) -> Option<Vec<CitationKey>> {
+  let mut keys = Vec::new();
+  for group in inner.split(';') {
+    let group =
+      strip_signal(group.trim());
+    let (authors, years) =
+      split_years(group)?;
+    let (authors, et_al) =
+      surnames(authors)?;
+    keys.extend(years.into_iter().map(
+      |year| {
+        CitationKey::AuthorYear {
+          authors: authors.clone(),
+          et_al,
+          year
+        }
+      }
+    ));
+  }
+  (!keys.is_empty()).then_some(keys)
+}
+
+/// "(2020)" or "(2020a, p. 4)" after
+/// an author, as in "Smith and Doe
+/// (2020)"; the start of the author
+/// names and the keys.
+fn narrative(
+  before: &str,
+  inner: &str
+) -> Option<(usize, Vec<CitationKey>)> {
+  let years = years(inner)?;
+  let start = narrative_start(before)?;
+  let (authors, et_al) =
+    surnames(&before[start..])?;
+  let keys = years
+    .into_iter()
+    .map(|year| {
+      CitationKey::AuthorYear {
+        authors: authors.clone(),
+        et_al,
+        year
+      }
+    })
+    .collect();
+  Some((start, keys))
+}
+
+/// Start of the author names that end
+/// `before`: "Smith", "Smith and Doe",
+/// "Smith, Doe and Roe", "van Dijk",
+/// "Smith et al.".
+fn narrative_start(
+  before: &str
+) -> Option<usize> {
+  let mut start = None;
+  let mut expect_name = true;
+  let mut in_list = false;
+  let mut end = before.trim_end().len();
+  while end > 0 {
+    let word_start = before[..end]
+      .rfind(char::is_whitespace)
+      .map_or(0, |idx| idx + 1);
+    let word = &before[word_start..end];
+    if start.is_some()
+      && PARTICLES.contains(&word)
+    {
+      start = Some(word_start);
+    } else if start.is_none()
+      && matches!(word, "al." | "et")
+    {
+      // "et al." before the year.
+    } else if !expect_name
+      && matches!(
+        word,
+        "and" | "&" | "und"
+      )
+    {
+      expect_name = true;
+      in_list = true;
+    } else if is_name(word)
+      && (expect_name
+        || (in_list
+          && word.ends_with(',')))
+    {
+      start = Some(word_start);
+      expect_name = false;
+    } else {
+      break;
+    }
+    end = before[..word_start]
+      .trim_end()
+      .len();
+  }
+  start
+}
+
+fn is_name(word: &str) -> bool {
+  let word = word.trim_end_matches(',');
+  let word = word
+    .strip_suffix("'s")
+    .or_else(|| word.strip_suffix("’s"))
+    .unwrap_or(word);
+  word
+    .chars()
+    .next()
+    .is_some_and(char::is_uppercase)
+    && word.chars().all(|c| {
+      c.is_alphabetic()
+        || matches!(c, '-' | '\'' | '’')
+    })
+}
+
+/// Author text and the years of a
+/// group such as "Smith, 2019, 2020a".
+fn split_years(
+  group: &str
+) -> Option<(&str, Vec<String>)> {
+  let at = group
+    .char_indices()
+    .find(|(idx, _)| {
+      year_at(&group[*idx..]).is_some()
+        && (*idx == 0
+          || group[..*idx].ends_with(
+            |c: char| {
+              c.is_whitespace()
+                || matches!(
+                  c,
+                  ',' | '('
+                )
+            }
+          ))
+    })
+    .map(|(idx, _)| idx)?;
+  let authors = group[..at]
+    .trim_end()
+    .trim_end_matches(',')
+    .trim_end();
+  Some((authors, years(&group[at..])?))
+}
+
+/// The years of "2019, 2020a, p. 4",
+/// ignoring locators.
+fn years(
+  text: &str
+) -> Option<Vec<String>> {
+  let years = text
+    .split(',')
+    .filter_map(|part| {
+      year_at(part.trim())
+    })
+    .collect::<Vec<_>>();
+  let first = text.split(',').next()?;
+  year_at(first.trim())?;
+  Some(years)
+}
+
+/// "2020", "2020a" or "n.d." opening
+/// `text` and standing alone.
+fn year_at(
+  text: &str
+) -> Option<String> {
+  let lower = text.to_lowercase();
+  if lower.starts_with("n.d.") {
+    return Some("n.d.".into());
+  }
+  let digits = text
+    .chars()
+    .take_while(char::is_ascii_digit)
+    .collect::<String>();
+  if digits.len() != 4
+    || !matches!(
+      &digits[..2],
+      "16" | "17" | "18" | "19" | "20"
+    )
+  {
+    return None;
+  }
+  let rest = &text[4..];
+  let suffix = rest
+    .chars()
+    .next()
+    .filter(|c| c.is_ascii_lowercase())
+    .filter(|_| {
+      !rest.chars().nth(1).is_some_and(
+        char::is_alphanumeric
+      )
+    });
+  let after = &rest[suffix
+    .map_or(0, char::len_utf8)..];
+  if after
+    .chars()
+    .next()
+    .is_some_and(char::is_alphanumeric)
+  {
+    return None;
+  }
+  Some(match suffix {
+    | Some(suffix) => {
+      format!("{digits}{suffix}")
+    }
+    | None => digits
+  })
+}
+
+/// Family names of "Smith & Doe",
+/// "Smith, Doe, and Roe" or "Smith et
+/// al.", and whether "et al." follows.
+fn surnames(
+  text: &str
+) -> Option<(Vec<String>, bool)> {
+  let text = text.trim();
+  let (text, et_al) = match text
+    .strip_suffix("et al.")
+    .or_else(|| {
+      text.strip_suffix("et al")
+    }) {
+    | Some(rest) => (rest.trim(), true),
+    | None => (text, false)
+  };
+  let authors = text
+    .split([',', '&'])
+    .flat_map(|part| {
+      part.split(" and ")
+    })
+    .map(|part| {
+      let part = part
+        .trim()
+        .trim_start_matches("and ")
+        .trim();
+      part
+        .strip_suffix("'s")
+        .or_else(|| {
+          part.strip_suffix("’s")
+        })
+        .unwrap_or(part)
+    })
+    .filter(|part| !part.is_empty())
+    .map(str::to_string)
+    .collect::<Vec<_>>();
+  let valid = !authors.is_empty()
+    && authors.len() <= 6
+    && authors.iter().all(|author| {
+      author.split_whitespace().count()
+        <= 3
+        && author
+          .split_whitespace()
+          .last()
+          .is_some_and(|word| {
+            word
+              .chars()
+              .next()
+              .is_some_and(
+                char::is_uppercase
+              )
+          })
+    });
+  valid.then_some((authors, et_al))
+}
+
+fn strip_signal(group: &str) -> &str {
+  let lower = group.to_lowercase();
+  SIGNALS
+    .iter()
+    .find(|signal| {
+      lower.starts_with(*signal)
+        && lower[signal.len()..]
+          .starts_with([' ', ','])
+    })
+    .map_or(group, |signal| {
+      group[signal.len()..]
+        .trim_start_matches(',')
+        .trim_start()
+    })
+}
+
+/// The sentence holding `span`, within
+/// its paragraph.
+fn sentence(
+  input: &str,
+  span: Range<usize>
+) -> Range<usize> {
+  let floor = span
+    .start
+    .saturating_sub(MAX_CONTEXT);
+  let ceiling = (span.end
+    + MAX_CONTEXT)
+    .min(input.len());
+  let mut start = floor;
+  for (idx, _) in input[..span.start]
+    .char_indices()
+    .rev()
+  {
+    if idx < floor {
+      break;
+    }
+    if input[..idx].ends_with("\n\n")
+      || sentence_ends(input, idx)
+    {
+      start = idx;
+      break;
+    }
+  }
+  let mut end = ceiling;
+  for (idx, _) in
+    input[span.end..].char_indices()
+  {
+    let idx = span.end + idx;
+    if idx > ceiling {
+      break;
+    }
+    if input[idx..].starts_with("\n\n")
+    {
+      end = idx;
+      break;
+    }
+    if sentence_ends(input, idx) {
+      end = idx;
+      break;
+    }
+  }
+  let start = start
+    + input[start..span.start].len()
+    - input[start..span.start]
+      .trim_start()
+      .len();
+  let start = floor_char(input, start);
+  let end = floor_char(input, end);
+  start.min(span.start)
+    ..end.max(span.end)
+}
+
+/// Whether a sentence ends just before
+/// `idx`: a period, question or
+/// exclamation mark after a word that
+/// is not an abbreviation or initial,
+/// then whitespace.
+fn sentence_ends(
+  input: &str,
+  idx: usize
+) -> bool {
+  let before = &input[..idx];
+  if !input[idx..]
+    .starts_with(char::is_whitespace)
+    || !before
+      .ends_with(['.', '!', '?'])
+  {
+    return false;
+  }
+  let word = before
+    .rsplit(char::is_whitespace)
+    .next()
+    .unwrap_or_default()
+    .to_lowercase();
+  let mut stem = word
+    .trim_end_matches(['.', '!', '?'])
+    .chars();
+  let initial = stem
+    .next()
+    .is_some_and(char::is_alphabetic)
+    && stem.next().is_none();
+  !initial
+    && !ABBREVIATIONS
+      .contains(&word.as_str())
+}
+
+fn floor_char(
+  input: &str,
+  mut idx: usize
+) -> usize {
+  while !input.is_char_boundary(idx) {
+    idx -= 1;
+  }
+  idx
+}
+
+fn citation_number(
+  reference: &Reference
+) -> Option<usize> {
+  match reference
+    .fields()
+    .get("citation-number")?
+  {
+    | FieldValue::Single(number) => {
+      number
+        .trim_matches(|c: char| {
+          !c.is_ascii_digit()
+        })
+        .parse()
+        .ok()
+    }
+    | _ => None
+  }
+}
+
+fn author_year_matches(
+  references: &[Reference],
+  authors: &[String],
+  et_al: bool,
+  year: &str
+) -> Vec<usize> {
+  let digits = year
+    .chars()
+    .filter(char::is_ascii_digit)
+    .collect::<String>();
+  let suffix = year
+    .chars()
+    .last()
+    .filter(|c| c.is_ascii_lowercase())
+    .filter(|_| !digits.is_empty());
+  let cited = authors
+    .iter()
+    .map(|author| key(author))
+    .collect::<Vec<_>>();
+  let candidates = references
+    .iter()
+    .enumerate()
+    .filter(|(_, reference)| {
+      let families =
+        families(reference);
+      families.first() == cited.first()
+        && reference_year(reference)
+          == digits
+    })
+    .collect::<Vec<_>>();
+  // Narrow by the other authors named.
+  let narrowed = candidates
+    .iter()
+    .filter(|(_, reference)| {
+      let families =
+        families(reference);
+      cited.iter().all(|author| {
+        families.contains(author)
+      }) && (!et_al
+        || families.len() > cited.len())
+    })
+    .copied()
+    .collect::<Vec<_>>();
+  let candidates =
+    if narrowed.is_empty() {
+      candidates
+    } else {
+      narrowed
+    };
+  let Some(suffix) = suffix else {
+    return candidates
+      .into_iter()
+      .map(|(idx, _)| idx)
+      .collect();
+  };
+  let written =
+    format!("{digits}{suffix}");
+  if let Some((idx, _)) = candidates
+    .iter()
+    .find(|(_, reference)| {
+      source(reference).is_some_and(
+        |source| {
+          source.contains(&written)
+        }
+      )
+    })
+  {
+    return vec![*idx];
+  }
+  candidates
+    .get((suffix as u8 - b'a') as usize)
+    .map(|(idx, _)| *idx)
+    .into_iter()
+    .collect()
+}
+
+fn families(
+  reference: &Reference
+) -> Vec<String> {
+  match reference.fields().get("author")
+  {
+    | Some(FieldValue::Authors(
+      authors
+    )) => {
+      authors
+        .iter()
+        .map(|author| {
+          key(&author.family)
+        })
+        .collect()
+    }
+    | _ => Vec::new()
+  }
+}
+
+fn reference_year(
+  reference: &Reference
+) -> String {
+  match reference.fields().get("date") {
+    | Some(FieldValue::List(dates)) => {
+      dates
+        .iter()
+        .map(|date| {
+          date
+            .chars()
+            .filter(
+              char::is_ascii_digit
+            )
+            .take(4)
+            .collect::<String>()
+        })
+        .next()
+        .unwrap_or_default()
+    }
+    | _ => String::new()
+  }
+}
+
+fn source(
+  reference: &Reference
+) -> Option<&str> {
+  match reference
+    .fields()
+    .get("__source")?
+  {
+    | FieldValue::List(sources) => {
+      sources
+        .first()
+        .map(String::as_str)
+    }
+    | _ => None
+  }
+}
+
+/// Last word of a name, lowercased,
+/// letters only.
+fn key(name: &str) -> String {
+  name
+    .split_whitespace()
+    .last()
+    .unwrap_or_default()
+    .chars()
+    .filter(|c| c.is_alphabetic())
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+fn flatten(text: &str) -> String {
+  text.replace(['\n', '\r'], " ")
+}
@@ -0,0 +1,610 @@
+//! Reads citations out of footnotes:
+//! numbered notes such as "3. Smith,
+//! History, 45; ibid., 47." are split
+//! at semicolons, and "ibid.", "op.
+//! cit." and short-title cites are
+//! resolved against earlier full
+//! citations.
+
+use std::ops::Range;
+
+use crate::parser::has_year;
+
+/// Words before a citation that only
+/// introduce it, lowercased.
+const SIGNALS: [&str; 9] = [
+  "see also",
+  "see",
+  "cf.",
+  "compare",
+  "e.g.",
+  "vgl. auch",
+  "vgl.",
+  "siehe",
+  "s. auch"
+];
+
+/// Openings of a cite to the citation
+/// just before it, lowercased.
+const IBID: [&str; 4] =
+  ["ibid", "ibidem", "ebd", "id."];
+
+/// Marks of a cite to an author's
+/// earlier citation, lowercased.
+const OP_CIT: [&str; 3] =
+  ["op. cit", "op cit", "a.a.o"];
+
+/// Words that open a locator,
+/// lowercased.
+const LOCATOR_PREFIXES: [&str; 8] = [
+  "p.", "pp.", "s.", "at", "col.",
+  "n.", "fol.", "vol."
+];
+
+/// Largest jump between consecutive
+/// note numbers.
+const MAX_NOTE_STEP: usize = 5;
+
+/// How a footnote cites its source.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum CitationKind {
+  /// A complete citation.
+  Full,
+  /// "ibid.", "ebd.": the citation
+  /// just before.
+  Ibid,
+  /// "Smith, op. cit.": the author's
+  /// earlier citation.
+  OpCit,
+  /// Author and short title of an
+  /// earlier citation.
+  Short
+}
+
+impl CitationKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      | CitationKind::Full => "full",
+      | CitationKind::Ibid => "ibid",
+      | CitationKind::OpCit => "op-cit",
+      | CitationKind::Short => "short"
+    }
+  }
+}
+
+/// A citation read from a footnote.
+#[derive(
+  Debug, Clone, PartialEq, Eq,
+)]
+pub struct FootnoteCitation {
+  /// Number of the footnote.
+  pub note:    String,
+  pub kind:    CitationKind,
+  /// The citation as written, line
+  /// breaks read as spaces.
+  pub text:    String,
+  /// Where `text` sits in the input.
+  pub span:    Range<usize>,
+  /// Input range of the full citation
+  /// cited, without its locator. A
+  /// cite that resolves to nothing
+  /// points at itself.
+  pub source:  Range<usize>,
+  /// Pages or other place cited
+  /// ("45", "pp. 12-14").
+  pub locator: Option<String>
+}
+
+impl FootnoteCitation {
+  /// Text of [`FootnoteCitation::source`]
+  /// in `input`, ready to parse.
+  pub fn source_text(
+    &self,
+    input: &str
+  ) -> String {
+    flatten(&input[self.source.clone()])
+  }
+}
+
+/// Citations of the footnotes of
+/// `input`, in order.
+pub(super) fn find(
+  input: &str
+) -> Vec<FootnoteCitation> {
+  let mut citations =
+    Vec::<FootnoteCitation>::new();
+  for (note, range) in notes(input) {
+    for piece in pieces(input, range) {
+      let citation = resolve(
+        input, &note, piece, &citations
+      );
+      citations.extend(citation);
+    }
+  }
+  citations
+}
+
+/// Number and text range of each
+/// footnote that cites something. A
+/// note opens on a line starting with
+/// its number and a capital letter,
+/// and continues over lines until a
+/// blank line, a page break or a line
+/// after a finished sentence.
+fn notes(
+  input: &str
+) -> Vec<(String, Range<usize>)> {
+  let mut notes =
+    Vec::<(String, Range<usize>)>::new(
+    );
+  let mut previous: Option<usize> =
+    None;
+  let mut open = false;
+  let mut start = 0;
+  for raw in input.split_inclusive('\n')
+  {
+    let line_start = start;
+    start += raw.len();
+    let line = raw.trim_end();
+    let content = line.trim_start();
+    let offset = line_start
+      + line.len()
+      - content.len();
+    if raw.contains('\u{000C}') {
+      open = false;
+    }
+    if content.is_empty() {
+      open = false;
+      continue;
+    }
+    if let Some((number, rest)) =
+      note_label(content)
+      && previous.is_none_or(
+        |previous| {
+          number == 1
+            || (number > previous
+              && number - previous
+                <= MAX_NOTE_STEP)
+        }
+      )
+      && rest.starts_with(|c: char| {
+        c.is_uppercase()
+          || matches!(
+            c,
+            '"' | '“' | '\''
+          )
+      })
+    {
+      let rest_start = offset
+        + content.len()
+        - rest.len();
+      notes.push((
+        number.to_string(),
+        rest_start
+          ..offset + content.len()
+      ));
+      previous = Some(number);
+      open = true;
+      continue;
+    }
+    let Some((_, range)) =
+      notes.last_mut()
+    else {
+      continue;
+    };
+    if open
+      && (!input[range.clone()]
+        .ends_with('.')
+        || content
+          .chars()
+          .next()
+          .is_some_and(
+            char::is_lowercase
+          ))
+    {
+      range.end =
+        offset + content.len();
+    } else {
+      open = false;
+    }
+  }
+  notes.retain(|(_, range)| {
+    cites(&input[range.clone()])
+  });
+  notes
+}
+
+/// "3.", "3)", "3" or superscript
+/// "³" followed by text.
+fn note_label(
+  line: &str
+) -> Option<(usize, &str)> {
+  let digits = line
+    .chars()
+    .take_while(|c| {
+      c.is_ascii_digit()
+        || superscript(*c).is_some()
+    })
+    .collect::<String>();
+  if digits.is_empty()
+    || digits.chars().count() > 3
+  {
+    return None;
+  }
+  let number = digits
+    .chars()
+    .map(|c| {
+      superscript(c).unwrap_or(c)
+    })
+    .collect::<String>()
+    .parse()
+    .ok()?;
+  let rest = &line[digits.len()..];
+  let rest = rest
+    .strip_prefix(['.', ')'])
+    .unwrap_or(rest);
+  let trimmed = rest.trim_start();
+  (trimmed.len() < rest.len()
+    || digits.chars().all(|c| {
+      superscript(c).is_some()
+    }))
+  .then_some((number, trimmed))
+}
+
+fn superscript(
+  c: char
+) -> Option<char> {
+  let digit = match c {
+    | '⁰' => '0',
+    | '¹' => '1',
+    | '²' => '2',
+    | '³' => '3',
+    | '⁴' => '4',
+    | '⁵' => '5',
+    | '⁶' => '6',
+    | '⁷' => '7',
+    | '⁸' => '8',
+    | '⁹' => '9',
+    | _ => return None
+  };
+  Some(digit)
+}
+
+/// Note text that cites something: a
+/// number after a comma, or an
+/// "ibid." or "op. cit.".
+fn cites(text: &str) -> bool {
+  let lower = text.to_lowercase();
+  (text.contains(',')
+    && text
+      .chars()
+      .any(|c| c.is_ascii_digit()))
+    || IBID
+      .iter()
+      .chain(OP_CIT.iter())
+      .any(|mark| lower.contains(mark))
+}
+
+/// Ranges of the citations of a note,
+/// split at semicolons outside
+/// brackets, without signals such as
+/// "See" and closing punctuation.
+fn pieces(
+  input: &str,
+  note: Range<usize>
+) -> Vec<Range<usize>> {
+  let text = &input[note.clone()];
+  let mut pieces = Vec::new();
+  let mut depth = 0usize;
+  let mut start = 0;
+  for (idx, c) in text
+    .char_indices()
+    .chain(std::iter::once((
+      text.len(),
+      ';'
+    )))
+  {
+    match c {
+      | '(' | '[' => depth += 1,
+      | ')' | ']' => {
+        depth = depth.saturating_sub(1)
+      }
+      | ';'
+        if depth == 0
+          || idx == text.len() =>
+      {
+        let piece =
+          trim(text, start..idx);
+        if !piece.is_empty() {
+          pieces.push(
+            note.start + piece.start
+              ..note.start + piece.end
+          );
+        }
+        start = idx + c.len_utf8();
+      }
+      | _ => {}
+    }
+  }
+  pieces
+}
+
+fn trim(
+  text: &str,
+  range: Range<usize>
+) -> Range<usize> {
+  let piece = &text[range.clone()];
+  let mut start = range.start
+    + piece.len()
+    - piece.trim_start().len();
+  let rest = text[start..range.end]
+    .to_lowercase();
+  if let Some(signal) =
+    SIGNALS.iter().find(|signal| {
+      rest.starts_with(*signal)
+        && rest[signal.len()..]
+          .starts_with(
+            char::is_whitespace
+          )
+    })
+  {
+    start += signal.len();
+    start += text[start..range.end]
+      .len()
+      - text[start..range.end]
+        .trim_start()
+        .len();
+  }
+  let end = start
+    + text[start..range.end]
+      .trim_end()
+      .trim_end_matches(['.', ','])
+      .len();
+  // Keep the period of a closing
+  // abbreviation such as "ibid.".
+  let end = if text[start..end]
+    .split_whitespace()
+    .last()
+    .is_some_and(|word| {
+      word.len() <= 4
+        && word
+          .chars()
+          .all(char::is_alphabetic)
+    })
+    && text[end..range.end]
+      .starts_with('.')
+  {
+    end + 1
+  } else {
+    end
+  };
+  start..end
+}
+
+fn resolve(
+  input: &str,
+  note: &str,
+  span: Range<usize>,
+  earlier: &[FootnoteCitation]
+) -> Option<FootnoteCitation> {
+  let text =
+    flatten(&input[span.clone()]);
+  let lower = text.to_lowercase();
+  let citation =
+    |kind, source, locator| {
+      FootnoteCitation {
+        note: note.to_string(),
+        kind,
+        text: text.clone(),
+        span: span.clone(),
+        source,
+        locator
+      }
+    };
+  if IBID
+    .iter()
+    .any(|mark| lower.starts_with(mark))
+  {
+    let previous = earlier.last();
+    let rest = text
+      .split_once(char::is_whitespace)
+      .map_or("", |(_, rest)| rest);
+    let locator = locator(rest)
+      .or_else(|| {
+        previous.and_then(|previous| {
+          previous.locator.clone()
+        })
+      });
+    let source = previous.map_or(
+      span.clone(),
+      |previous| {
+        previous.source.clone()
+      }
+    );
+    return Some(citation(
+      CitationKind::Ibid,
+      source,
+      locator
+    ));
+  }
+  if let Some((at, mark)) =
+    OP_CIT.iter().find_map(|mark| {
+      lower
+        .find(mark)
+        .map(|at| (at, mark))
+    })
+  {
+    let author = &text[..at];
+    let rest = text[at + mark.len()..]
+      .trim_start_matches('.');
+    let source = latest_full(
+      input, earlier, author, None
+    )
+    .unwrap_or_else(|| span.clone());
+    return Some(citation(
+      CitationKind::OpCit,
+      source,
+      locator(rest)
+    ));
+  }
+  let (body, locator) =
+    split_locator(&text);
+  let source =
+    span.start..span.start + body;
+  if has_year(&text) {
+    return Some(citation(
+      CitationKind::Full,
+      source,
+      locator
+    ));
+  }
+  let mut parts =
+    text[..body].split(',');
+  let author = parts.next()?;
+  let title = parts.next();
+  let source = latest_full(
+    input, earlier, author, title
+  )
+  .unwrap_or(source);
+  Some(citation(
+    CitationKind::Short,
+    source,
+    locator
+  ))
+}
+
+/// Source of the latest full citation
+/// naming `author`'s surname and, when
+/// given, the words of a short title.
+fn latest_full(
+  input: &str,
+  earlier: &[FootnoteCitation],
+  author: &str,
+  title: Option<&str>
+) -> Option<Range<usize>> {
+  let surname = author
+    .trim_matches(|c: char| {
+      !c.is_alphanumeric()
+    })
+    .split_whitespace()
+    .last()
+    .map(key)
+    .filter(|surname| {
+      !surname.is_empty()
+    })?;
+  let words = title
+    .map(|title| {
+      title
+        .split_whitespace()
+        .map(key)
+        .filter(|word| word.len() > 3)
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  earlier
+    .iter()
+    .rev()
+    .filter(|citation| {
+      citation.kind
+        == CitationKind::Full
+    })
+    .find(|citation| {
+      let keys = input
+        [citation.source.clone()]
+      .split_whitespace()
+      .map(key)
+      .collect::<Vec<_>>();
+      keys.contains(&surname)
+        && words.iter().all(|word| {
+          keys.contains(word)
+        })
+    })
+    .map(|citation| {
+      citation.source.clone()
+    })
+}
+
+/// Byte length of `text` without a
+/// trailing locator, and the locator.
+fn split_locator(
+  text: &str
+) -> (usize, Option<String>) {
+  match text.rsplit_once(',') {
+    // A closing year dates the work.
+    | Some((body, last))
+      if !has_year(last)
+        || has_year(body) =>
+    {
+      match locator(last) {
+        | Some(locator) => {
+          (body.len(), Some(locator))
+        }
+        | None => (text.len(), None)
+      }
+    }
+    | _ => (text.len(), None)
+  }
+}
+
+/// "45", "45-47", "45f.", "p. 45",
+/// "pp. 12–14", "xiv".
+fn locator(
+  text: &str
+) -> Option<String> {
+  let text = text
+    .trim()
+    .trim_start_matches(',')
+    .trim()
+    .trim_end_matches('.');
+  let lower = text.to_lowercase();
+  let pages = LOCATOR_PREFIXES
+    .iter()
+    .find_map(|prefix| {
+      lower.strip_prefix(prefix).filter(
+        |rest| rest.starts_with(' ')
+      )
+    })
+    .unwrap_or(&lower)
+    .trim();
+  let pages = pages
+    .trim_end_matches("ff")
+    .trim_end_matches('f')
+    .trim_end();
+  let is_pages = !pages.is_empty()
+    && pages.chars().any(|c| {
+      c.is_ascii_digit()
+        || matches!(c, 'i' | 'v' | 'x')
+    })
+    && pages.chars().all(|c| {
+      c.is_ascii_digit()
+        || matches!(
+          c,
+          '-'
+            | '–'
+            | ','
+            | ' '
+            | 'i'
+            | 'v'
+            | 'x'
+            | 'l'
+            | 'c'
+        )
+    });
+  is_pages.then(|| text.to_string())
+}
+
+fn key(word: &str) -> String {
+  word
+    .chars()
+    .filter(|c| c.is_alphanumeric())
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+/// Line breaks read as spaces; byte
+/// offsets stay the same.
+fn flatten(text: &str) -> String {
+  text.replace(['\n', '\r'], " ")
+}
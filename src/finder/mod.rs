@@ -0,0 +1,212 @@
+use std::ops::Range;
+
+mod citations;
+mod footnotes;
+mod sections;
+
+pub use citations::{
+  CitationKey,
+  InTextCitation
+};
+pub use footnotes::{
+  CitationKind,
+  FootnoteCitation
+};
+pub use sections::{
+  ReferenceSection,
+  SectionPosition
+};
+
+use crate::document::{
+  CleanText,
+  Document
+};
+use crate::parser::{
+  Parser,
+  Reference,
+  sequence_signature
+};
+use crate::sequence_model::SequenceModel;
+
+#[derive(Debug, Clone)]
+pub struct Finder {
+  signatures: SequenceModel
+}
+
+impl Default for Finder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Finder {
+  pub fn new() -> Self {
+    Self {
+      signatures:
+        SequenceModel::default()
+    }
+  }
+
+  pub fn with_signatures(
+    signatures: SequenceModel
+  ) -> Self {
+    Self {
+      signatures
+    }
+  }
+
+  /// Bibliography sections of
+  /// `document`, found under their
+  /// headings or, when no heading opens
+  /// one, as dense runs of
+  /// reference-like paragraphs.
+  pub fn sections(
+    document: &Document
+  ) -> Vec<ReferenceSection> {
+    sections::find(document)
+  }
+
+  /// Cleaned text of `sections`
+  /// without their headings.
+  pub fn section_text(
+    document: &Document,
+    sections: &[ReferenceSection]
+  ) -> CleanText {
+    document.clean_where(
+      |page, line| {
+        sections.iter().any(|section| {
+          section.contains(
+            page,
+            line.index()
+          ) && !section.is_heading(
+            page,
+            line.index()
+          )
+        })
+      }
+    )
+  }
+
+  /// Citations of the footnotes of
+  /// `input`, with "ibid.", "op. cit."
+  /// and short-title cites pointing at
+  /// the full citation they refer to.
+  pub fn footnotes(
+    input: &str
+  ) -> Vec<FootnoteCitation> {
+    footnotes::find(input)
+  }
+
+  /// Numeric and author-year citations
+  /// in the running text of `input`,
+  /// with the sentence around each.
+  pub fn citations(
+    input: &str
+  ) -> Vec<InTextCitation> {
+    citations::find(input)
+  }
+
+  /// Resolves `citations` to indices of
+  /// `references`, by citation number
+  /// or by first author and year.
+  pub fn link_citations(
+    citations: &mut [InTextCitation],
+    references: &[Reference]
+  ) {
+    citations::link(
+      citations, references
+    );
+  }
+
+  pub fn segments(
+    input: &str
+  ) -> Vec<String> {
+    Self::segment_ranges(input)
+      .into_iter()
+      .map(|range| {
+        input[range].to_string()
+      })
+      .collect()
+  }
+
+  /// Byte ranges of
+  /// [`Finder::segments`] in `input`.
+  pub fn segment_ranges(
+    input: &str
+  ) -> Vec<Range<usize>> {
+    split_into_references(input)
+  }
+
+  pub fn label(
+    &self,
+    input: &str
+  ) -> Vec<Document> {
+    let segments =
+      Self::segments(input);
+    if segments.is_empty() {
+      return vec![Document::from_text(
+        input
+      )];
+    }
+
+    let parser = Parser::new();
+    let mut results = Vec::new();
+    let has_signatures =
+      self.signatures.has_signatures();
+
+    for segment in segments {
+      let sequences = parser
+        .prepare(&segment, true)
+        .0;
+      let mut matched = false;
+
+      for sequence in &sequences {
+        let signature =
+          sequence_signature(sequence);
+        if has_signatures
+          && self
+            .signatures
+            .should_match(&signature, 1)
+        {
+          matched = true;
+          break;
+        }
+      }
+
+      if matched || !has_signatures {
+        results.push(
+          Document::from_text(&segment)
+        );
+      }
+    }
+
+    if results.is_empty() {
+      vec![Document::from_text(input)]
+    } else {
+      results
+    }
+  }
+}
+
+fn split_into_references(
+  input: &str
+) -> Vec<Range<usize>> {
+  let mut ranges = Vec::new();
+  let mut start = 0;
+  for part in input.split("\n\n") {
+    let seg = part.trim();
+    let begin = start + part.len()
+      - part.trim_start().len();
+    start += part.len() + 2;
+    if !seg.is_empty()
+      && seg
+        .chars()
+        .any(|c| c.is_ascii_digit())
+      && seg.len() > 20
+    {
+      ranges
+        .push(begin..begin + seg.len());
+    }
+  }
+  ranges
+}
@@ -0,0 +1,552 @@
+//! Locates bibliographies: the lines
+//! under a heading such as
+//! "References", "Bibliography" or
+//! "Literatur", or failing a heading,
+//! dense runs of reference-like
+//! paragraphs.
+
+use std::ops::Range;
+
+use crate::document::{
+  Document,
+  Line
+};
+use crate::feature::layout::{
+  Feature,
+  Observation
+};
+use crate::parser::{
+  has_year,
+  starts_entry,
+  starts_with_label
+};
+
+/// Headings that open a bibliography,
+/// lowercased.
+const HEADINGS: [&str; 32] = [
+  "references",
+  "reference list",
+  "list of references",
+  "bibliography",
+  "select bibliography",
+  "selected bibliography",
+  "works cited",
+  "literature cited",
+  "cited literature",
+  "literature",
+  "sources",
+  "literatur",
+  "literaturverzeichnis",
+  "literaturangaben",
+  "quellen",
+  "quellenverzeichnis",
+  "quellen und literatur",
+  "quellen- und literaturverzeichnis",
+  "bibliographie",
+  "références",
+  "références bibliographiques",
+  "bibliografía",
+  "referencias",
+  "referencias bibliográficas",
+  "bibliografia",
+  "riferimenti bibliografici",
+  "referências",
+  "referências bibliográficas",
+  "literatuur",
+  "literatuurlijst",
+  "litteratur",
+  "referenser"
+];
+
+/// Headings of sections that follow a
+/// bibliography, lowercased.
+const END_HEADINGS: [&str; 30] = [
+  "notes",
+  "endnotes",
+  "footnotes",
+  "anmerkungen",
+  "fußnoten",
+  "appendix",
+  "appendices",
+  "anhang",
+  "annex",
+  "annexe",
+  "index",
+  "register",
+  "acknowledgements",
+  "acknowledgments",
+  "danksagung",
+  "about the author",
+  "about the authors",
+  "curriculum vitae",
+  "lebenslauf",
+  "glossary",
+  "glossar",
+  "abbreviations",
+  "abkürzungsverzeichnis",
+  "abbildungsverzeichnis",
+  "tabellenverzeichnis",
+  "list of figures",
+  "list of tables",
+  "supplementary material",
+  "funding",
+  "conflict of interest"
+];
+
+/// Words that open numbered headings
+/// after a bibliography ("Appendix B").
+const END_PREFIXES: [&str; 6] = [
+  "appendix", "anhang", "annex",
+  "chapter", "kapitel", "part"
+];
+
+/// Words that mark a line as part of a
+/// reference.
+const MARKERS: [&str; 12] = [
+  "pp.", "p.", "vol.", "no.", "ed.",
+  "eds.", "hrsg.", "hg.", "s.", "bd.",
+  "doi", "http"
+];
+
+/// Longest paragraph read as a single
+/// reference.
+const MAX_ENTRY_LINES: usize = 6;
+
+/// Longest heading, in words.
+const MAX_HEADING_WORDS: usize = 6;
+
+/// Paragraphs without references that
+/// end a bibliography.
+const MAX_GAP: usize = 3;
+
+/// Share of reference-like paragraphs a
+/// bibliography needs.
+const MIN_DENSITY: f32 = 0.5;
+
+/// Reference-like lines a run of
+/// paragraphs needs to count as a
+/// bibliography without a heading.
+const MIN_RUN_LINES: usize = 6;
+
+/// Where a section starts or ends: the
+/// index of the page in
+/// [`Document::pages`] and the line's
+/// [`Line::index`] on it.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+)]
+pub struct SectionPosition {
+  pub page: usize,
+  pub line: usize
+}
+
+/// A bibliography found in a document.
+/// The span includes the heading line
+/// and ends with the last
+/// reference-like paragraph.
+#[derive(
+  Debug, Clone, PartialEq, Eq,
+)]
+pub struct ReferenceSection {
+  /// Heading text; `None` for a
+  /// section found by its density
+  /// alone.
+  pub heading: Option<String>,
+  pub start:   SectionPosition,
+  pub end:     SectionPosition
+}
+
+impl ReferenceSection {
+  /// Whether line `line` of page `page`
+  /// lies in the section.
+  pub fn contains(
+    &self,
+    page: usize,
+    line: usize
+  ) -> bool {
+    let position = SectionPosition {
+      page,
+      line
+    };
+    self.start <= position
+      && position <= self.end
+  }
+
+  /// Whether the line opens the section
+  /// with its heading.
+  pub fn is_heading(
+    &self,
+    page: usize,
+    line: usize
+  ) -> bool {
+    self.heading.is_some()
+      && self.start
+        == SectionPosition {
+          page,
+          line
+        }
+  }
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq,
+)]
+enum Heading {
+  Bibliography,
+  Other
+}
+
+pub(super) fn find(
+  document: &Document
+) -> Vec<ReferenceSection> {
+  let clean = document.clean();
+  let lines = clean.lines();
+  let pages = document.pages();
+  let feature = Feature::new();
+  let headings = lines
+    .iter()
+    .map(|(page_idx, line)| {
+      let page = &pages[*page_idx];
+      let cues = page
+        .lines()
+        .iter()
+        .position(|other| {
+          other.index() == line.index()
+        })
+        .map(|position| {
+          feature
+            .observe(page, position)
+        })
+        .unwrap_or_default();
+      heading(line.text(), &cues)
+    })
+    .collect::<Vec<_>>();
+  let mut sections = Vec::new();
+  for (start, kind) in
+    headings.iter().enumerate()
+  {
+    if *kind
+      != Some(Heading::Bibliography)
+    {
+      continue;
+    }
+    let body = start + 1;
+    let stop = headings[body..]
+      .iter()
+      .position(Option::is_some)
+      .map_or(lines.len(), |idx| {
+        body + idx
+      });
+    if let Some(end) =
+      reference_end(&lines[body..stop])
+    {
+      sections.push(ReferenceSection {
+        heading: Some(
+          lines[start]
+            .1
+            .text()
+            .trim()
+            .to_string()
+        ),
+        start:   position(
+          &lines[start]
+        ),
+        end:     position(
+          &lines[body + end]
+        )
+      });
+    }
+  }
+  if sections.is_empty() {
+    sections = dense_runs(lines);
+  }
+  sections
+}
+
+fn position(
+  (page, line): &(usize, Line)
+) -> SectionPosition {
+  SectionPosition {
+    page: *page,
+    line: line.index()
+  }
+}
+
+/// Classifies a line as a heading from
+/// its words, or from its size when the
+/// layout sets it apart.
+fn heading(
+  text: &str,
+  cues: &[Observation]
+) -> Option<Heading> {
+  let normalized = normalize(text);
+  let words = normalized
+    .split_whitespace()
+    .count();
+  if words == 0
+    || words > MAX_HEADING_WORDS
+  {
+    return None;
+  }
+  let emphasized =
+    cues.iter().any(|cue| {
+      matches!(
+        cue,
+        Observation::LargerFont
+          | Observation::Bold
+      )
+    });
+  if HEADINGS
+    .contains(&normalized.as_str())
+    || (emphasized
+      && HEADINGS.iter().any(
+        |heading| {
+          normalized
+            .strip_prefix(heading)
+            .is_some_and(|rest| {
+              rest.starts_with(' ')
+            })
+        }
+      ))
+  {
+    return Some(Heading::Bibliography);
+  }
+  let first = normalized
+    .split_whitespace()
+    .next()
+    .unwrap_or_default();
+  if END_HEADINGS
+    .contains(&normalized.as_str())
+    || (END_PREFIXES.contains(&first)
+      && words <= 4
+      && normalized.ends_with(
+        char::is_alphanumeric
+      ))
+    || (cues.contains(
+      &Observation::LargerFont
+    ) && !has_year(text))
+  {
+    return Some(Heading::Other);
+  }
+  None
+}
+
+/// Lowercased heading text without
+/// section numbers ("7.", "VII",
+/// "A.") or a trailing colon.
+fn normalize(text: &str) -> String {
+  let lower =
+    text.trim().to_lowercase();
+  let mut words = lower
+    .split_whitespace()
+    .collect::<Vec<_>>();
+  if words.len() > 1
+    && is_section_number(words[0])
+  {
+    words.remove(0);
+  }
+  words
+    .join(" ")
+    .trim_end_matches([':', '.'])
+    .to_string()
+}
+
+fn is_section_number(
+  word: &str
+) -> bool {
+  let number =
+    word.trim_end_matches(['.', ')']);
+  !number.is_empty()
+    && (number.split('.').all(|part| {
+      !part.is_empty()
+        && part
+          .chars()
+          .all(|c| c.is_ascii_digit())
+    }) || number.chars().all(|c| {
+      matches!(
+        c,
+        'i' | 'v' | 'x' | 'l' | 'c'
+      )
+    }) || (number.chars().count()
+      == 1
+      && word.ends_with('.')))
+}
+
+/// Index of the last line of the
+/// bibliography starting at `lines`,
+/// when reference-like paragraphs are
+/// dense enough.
+fn reference_end(
+  lines: &[(usize, Line)]
+) -> Option<usize> {
+  let mut last = None;
+  let (mut seen, mut matched, mut gap) =
+    (0, 0, 0);
+  let (
+    mut counted,
+    mut counted_matched
+  ) = (0, 0);
+  for block in blocks(lines) {
+    seen += 1;
+    if reference_like(
+      &lines[block.clone()]
+    ) {
+      matched += 1;
+      gap = 0;
+      last = Some(block.end - 1);
+      (counted, counted_matched) =
+        (seen, matched);
+    } else {
+      gap += 1;
+      if gap >= MAX_GAP {
+        break;
+      }
+    }
+  }
+  let density = counted_matched as f32
+    / counted as f32;
+  last
+    .filter(|_| density >= MIN_DENSITY)
+}
+
+/// Runs of reference-like paragraphs
+/// with enough reference-like lines.
+fn dense_runs(
+  lines: &[(usize, Line)]
+) -> Vec<ReferenceSection> {
+  let mut sections = Vec::new();
+  let mut run: Option<Range<usize>> =
+    None;
+  let blocks = blocks(lines);
+  for block in blocks
+    .iter()
+    .map(Some)
+    .chain(std::iter::once(None))
+  {
+    match block {
+      | Some(block)
+        if reference_like(
+          &lines[block.clone()]
+        ) =>
+      {
+        let start = run
+          .as_ref()
+          .map_or(block.start, |run| {
+            run.start
+          });
+        run = Some(start..block.end);
+      }
+      | _ => {
+        if let Some(run) = run.take()
+          && lines[run.clone()]
+            .iter()
+            .filter(|(_, line)| {
+              reference_line(
+                line.text()
+              )
+            })
+            .count()
+            >= MIN_RUN_LINES
+        {
+          sections.push(
+            ReferenceSection {
+              heading: None,
+              start:   position(
+                &lines[run.start]
+              ),
+              end:     position(
+                &lines[run.end - 1]
+              )
+            }
+          );
+        }
+      }
+    }
+  }
+  sections
+}
+
+/// Paragraphs of `lines`: runs without
+/// a blank line between them. A page
+/// break does not end a paragraph.
+fn blocks(
+  lines: &[(usize, Line)]
+) -> Vec<Range<usize>> {
+  let mut blocks = Vec::new();
+  let mut start = 0;
+  for idx in 1..=lines.len() {
+    let ends = lines
+      .get(idx)
+      .is_none_or(|(page, line)| {
+        let (previous_page, previous) =
+          &lines[idx - 1];
+        page == previous_page
+          && line.index()
+            > previous.index() + 1
+      });
+    if ends && start < idx {
+      blocks.push(start..idx);
+      start = idx;
+    }
+  }
+  blocks
+}
+
+/// At least half of the lines look like
+/// parts of references, or a short
+/// paragraph opens like a reference and
+/// has a year.
+fn reference_like(
+  lines: &[(usize, Line)]
+) -> bool {
+  let opens_entry = lines
+    .first()
+    .is_some_and(|(_, line)| {
+      starts_with_label(line.text())
+        || starts_entry(line.text())
+    });
+  if opens_entry
+    && lines.len() <= MAX_ENTRY_LINES
+    && lines.iter().any(|(_, line)| {
+      has_year(line.text())
+    })
+  {
+    return true;
+  }
+  let matched = lines
+    .iter()
+    .filter(|(_, line)| {
+      reference_line(line.text())
+    })
+    .count();
+  matched > 0
+    && matched as f32
+      >= lines.len() as f32
+        * MIN_DENSITY
+}
+
+/// A year, a label, an author opening
+/// the line, or an editorial marker
+/// such as "pp." or a DOI.
+fn reference_line(text: &str) -> bool {
+  has_year(text)
+    || starts_with_label(text)
+    || starts_entry(text)
+    || text.split_whitespace().any(
+      |word| {
+        let word = word.to_lowercase();
+        MARKERS.iter().any(|marker| {
+          word == *marker
+            || (marker.len() > 3
+              && word
+                .starts_with(marker))
+        })
+      }
+    )
+}
@@ -89,6 +89,11 @@ impl Format {
         self
           .normalization
           .apply_to_map(&mut map);
+        self
+          .normalization
+          .protect_bibtex_title(
+            &mut map
+          );
         normalize_bibtex_entry(
           &mut map
         );
@@ -125,6 +130,56 @@ impl Format {
     .unwrap_or_else(|_| "[]".into())
   }
 
+  /// Like [`Format::to_json`], adding a
+  /// `spans` object to each reference:
+  /// where it was found and the source
+  /// token spans of every field value.
+  pub fn to_json_with_spans(
+    &self,
+    references: &[Reference]
+  ) -> String {
+    serde_json::to_string_pretty(
+      &self.to_value_with_spans(
+        references
+      )
+    )
+    .unwrap_or_else(|_| "[]".into())
+  }
+
+  /// [`Format::to_value`] with the
+  /// `spans` object of
+  /// [`Format::to_json_with_spans`].
+  pub fn to_value_with_spans(
+    &self,
+    references: &[Reference]
+  ) -> Value {
+    let values = references
+      .iter()
+      .map(|reference| {
+        let mut value =
+          serde_json::to_value(
+            strip_internal_fields(
+              reference
+            )
+          )
+          .unwrap_or(Value::Null);
+        if let Value::Object(map) =
+          &mut value
+        {
+          map.insert(
+            "spans".into(),
+            serde_json::to_value(
+              reference.spans()
+            )
+            .unwrap_or(Value::Null)
+          );
+        }
+        value
+      })
+      .collect::<Vec<_>>();
+    Value::Array(values)
+  }
+
   pub fn to_csl(
     &self,
     references: &[Reference]
@@ -275,7 +330,8 @@ fn reference_source(
         .first()
         .map(String::as_str)
     }
-    | FieldValue::Authors(_) => None
+    | FieldValue::Authors(_)
+    | FieldValue::Places(_) => None
   }
 }
 
@@ -461,6 +517,15 @@ fn field_value_strings(
         })
         .collect()
     }
+    | FieldValue::Places(places) => {
+      places
+        .iter()
+        .map(|place| place.label())
+        .filter(|label| {
+          !label.is_empty()
+        })
+        .collect()
+    }
   }
 }
 
@@ -476,6 +541,7 @@ fn normalize_bibtex_entry(
   normalize_bibtex_date(map);
   map.remove("language");
   map.remove("scripts");
+  map.remove("structured-location");
 
   if let Some(value) =
     map.remove("isbn")
@@ -731,10 +797,11 @@ fn csl_entry(
   }
 
   if let Some(value) =
-    extract_first_value_from_map(
+    extract_values_from_map(
       &map,
       "publisher-place"
     )
+    .map(|places| places.join("; "))
     .or_else(|| {
       extract_first_value_from_map(
         &map, "address"
@@ -9,3 +9,5 @@ pub mod model;
 pub mod normalizer;
 pub mod parser;
 pub mod sequence_model;
+#[cfg(feature = "test-support")]
+pub mod test_support;
@@ -0,0 +1,39 @@
+#[derive(Debug, Clone)]
+pub struct Normalizer;
+
+impl Default for Normalizer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Normalizer {
+  pub fn new() -> Self {
+    Self
+  }
+
+  pub fn normalize(
+    &self,
+    input: &str
+  ) -> String {
+    let mut value = input.trim();
+    for prefix in &[
+      "in ",
+      "In ",
+      "of ",
+      "Presented at ",
+      "presented at "
+    ] {
+      if value.starts_with(prefix) {
+        value =
+          value[prefix.len()..].trim();
+      }
+    }
+    value
+      .trim_end_matches(|c: char| {
+        c == ',' || c == '.'
+      })
+      .trim()
+      .to_string()
+  }
+}
@@ -0,0 +1,575 @@
+use crate::dictionary::{
+  DictionaryCode,
+  DictionaryLookup
+};
+use crate::parser::Place;
+
+#[derive(Debug, Clone)]
+pub struct Normalizer;
+
+/// Places and trailing publisher found
+/// in a location segment such as
+/// "Cambridge, MA: MIT Press".
+#[derive(
+  Debug, Clone, Default, PartialEq, Eq,
+)]
+pub struct ParsedLocation {
+  pub places:    Vec<Place>,
+  pub publisher: Option<String>
+}
+
+impl Default for Normalizer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Normalizer {
+  pub fn new() -> Self {
+    Self
+  }
+
+  pub fn normalize(
+    &self,
+    input: &str
+  ) -> (String, Option<String>) {
+    let trimmed = input.trim();
+    if let Some((before, after)) =
+      trimmed.split_once(':')
+    {
+      (
+        before.trim().to_string(),
+        Some(after.trim().to_string())
+      )
+    } else {
+      (trimmed.to_string(), None)
+    }
+  }
+
+  /// Splits a location into places with
+  /// city, region and country. Place
+  /// entries in `dictionary` keep
+  /// places joined by "and" whole;
+  /// publisher entries end the
+  /// location early.
+  pub fn parse(
+    &self,
+    input: &str,
+    dictionary: Option<
+      &dyn DictionaryLookup
+    >
+  ) -> ParsedLocation {
+    let (location, mut publisher) =
+      self.normalize(input);
+    let mut places = Vec::new();
+    for group in split_place_groups(
+      &location, dictionary
+    ) {
+      let components = group
+        .split(',')
+        .map(clean_component)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+      let total = components.len();
+      // "City, Region, Country": an
+      // unknown middle component is the
+      // region once the tail is a
+      // country. Dictionary places are
+      // cities as often as countries,
+      // so only the country table
+      // decides.
+      let ends_with_country = total
+        >= 3
+        && components
+          .last()
+          .is_some_and(|last| {
+            lookup_country(last)
+              .is_some()
+          });
+      let mut current: Option<Place> =
+        None;
+      for (idx, component) in
+        components.iter().enumerate()
+      {
+        let codes = dictionary
+          .map(|dict| {
+            dict.lookup(component)
+          })
+          .unwrap_or_default();
+        if idx > 0
+          && publisher.is_none()
+          && codes.contains(
+            &DictionaryCode::Publisher
+          )
+          && !codes.contains(
+            &DictionaryCode::Place
+          )
+        {
+          publisher = Some(
+            components[idx..]
+              .join(", ")
+          );
+          break;
+        }
+        let Some(place) =
+          current.as_mut()
+        else {
+          current =
+            Some(match lookup_country(
+              component
+            ) {
+              | Some(country)
+                if total == 1 =>
+              {
+                Place {
+                  country: Some(
+                    country.to_string()
+                  ),
+                  ..Place::default()
+                }
+              }
+              | _ => {
+                Place::city(component)
+              }
+            });
+          continue;
+        };
+        if place.region.is_none()
+          && place.country.is_none()
+          && let Some((region, country)) =
+            lookup_region(component)
+        {
+          place.region =
+            Some(region.to_string());
+          place.country =
+            Some(country.to_string());
+        } else if let Some(country) =
+          lookup_country(component)
+        {
+          place.country =
+            Some(country.to_string());
+        } else if ends_with_country
+          && idx + 1 < total
+          && place.region.is_none()
+          && place.country.is_none()
+        {
+          place.region =
+            Some(component.clone());
+        } else {
+          places.extend(current.take());
+          current = Some(Place::city(
+            component
+          ));
+        }
+      }
+      places.extend(current);
+    }
+    ParsedLocation {
+      places,
+      publisher: publisher.filter(
+        |value| !value.is_empty()
+      )
+    }
+  }
+}
+
+/// Places joined by "&", ";", "/" or
+/// "and". A component that is itself a
+/// known place, such as "Newfoundland
+/// and Labrador", is not split at its
+/// "and".
+fn split_place_groups(
+  location: &str,
+  dictionary: Option<
+    &dyn DictionaryLookup
+  >
+) -> Vec<String> {
+  location
+    .replace(" & ", "&")
+    .split(['&', ';', '/'])
+    .flat_map(|part| {
+      let mut groups =
+        vec![String::new()];
+      for (idx, component) in
+        part.split(',').enumerate()
+      {
+        let Some(group) =
+          groups.last_mut()
+        else {
+          continue;
+        };
+        if idx > 0 {
+          group.push(',');
+        }
+        if known_place(
+          component, dictionary
+        ) {
+          group.push_str(component);
+          continue;
+        }
+        let mut pieces =
+          component.split(" and ");
+        group.push_str(
+          pieces
+            .next()
+            .unwrap_or_default()
+        );
+        groups.extend(
+          pieces.map(str::to_string)
+        );
+      }
+      groups
+    })
+    .map(|group| {
+      group.trim().to_string()
+    })
+    .filter(|group| !group.is_empty())
+    .collect()
+}
+
+/// A region, a country or a place in
+/// `dictionary`. Dictionary keys are
+/// single words, so every word must be
+/// a place: "New York and London" is
+/// two places unless "and" belongs to
+/// an imported place name.
+fn known_place(
+  component: &str,
+  dictionary: Option<
+    &dyn DictionaryLookup
+  >
+) -> bool {
+  let component =
+    clean_component(component);
+  lookup_region(&component).is_some()
+    || lookup_country(&component)
+      .is_some()
+    || dictionary.is_some_and(|dict| {
+      let words = component
+        .split_whitespace()
+        .collect::<Vec<_>>();
+      !words.is_empty()
+        && dict
+          .lookup_many(&words)
+          .iter()
+          .all(|weights| {
+            weights.weight(
+              DictionaryCode::Place
+            ) > 0.0
+          })
+    })
+}
+
+fn clean_component(
+  component: &str
+) -> String {
+  component
+    .trim()
+    .trim_start_matches(['(', '['])
+    .trim_end_matches([')', ']', ','])
+    .trim()
+    .to_string()
+}
+
+fn compact_key(value: &str) -> String {
+  value
+    .chars()
+    .filter(|c| {
+      !c.is_whitespace() && *c != '.'
+    })
+    .collect::<String>()
+    .to_lowercase()
+}
+
+/// Resolves US states and Canadian
+/// provinces written as postal codes,
+/// traditional abbreviations or full
+/// names, returning the postal code and
+/// country.
+fn lookup_region(
+  component: &str
+) -> Option<(&'static str, &'static str)>
+{
+  let trimmed = component.trim();
+  let key = compact_key(trimmed);
+  let full = trimmed.to_lowercase();
+  REGIONS.iter().find_map(
+    |(code, name, abbrevs, country)| {
+      let postal = trimmed
+        .trim_end_matches('.')
+        == *code;
+      let abbreviated =
+        abbrevs.iter().any(|abbrev| {
+          compact_key(abbrev) == key
+        });
+      let named =
+        name.to_lowercase() == full;
+      (postal || abbreviated || named)
+        .then_some((*code, *country))
+    }
+  )
+}
+
+fn lookup_country(
+  component: &str
+) -> Option<&'static str> {
+  let key = compact_key(component);
+  COUNTRIES.iter().find_map(
+    |(name, aliases)| {
+      (compact_key(name) == key
+        || aliases.iter().any(
+          |alias| {
+            compact_key(alias) == key
+          }
+        ))
+      .then_some(*name)
+    }
+  )
+}
+
+const US: &str = "United States";
+const CA: &str = "Canada";
+
+static REGIONS: &[(
+  &str,
+  &str,
+  &[&str],
+  &str
+)] = &[
+  ("AL", "Alabama", &["Ala."], US),
+  ("AK", "Alaska", &["Alas."], US),
+  ("AZ", "Arizona", &["Ariz."], US),
+  ("AR", "Arkansas", &["Ark."], US),
+  ("CA", "California", &["Calif."], US),
+  ("CO", "Colorado", &["Colo."], US),
+  ("CT", "Connecticut", &["Conn."], US),
+  ("DE", "Delaware", &["Del."], US),
+  (
+    "DC",
+    "District of Columbia",
+    &["D.C."],
+    US
+  ),
+  ("FL", "Florida", &["Fla."], US),
+  ("GA", "Georgia", &["Ga."], US),
+  ("HI", "Hawaii", &[], US),
+  ("ID", "Idaho", &[], US),
+  ("IL", "Illinois", &["Ill."], US),
+  ("IN", "Indiana", &["Ind."], US),
+  ("IA", "Iowa", &[], US),
+  (
+    "KS",
+    "Kansas",
+    &["Kan.", "Kans."],
+    US
+  ),
+  ("KY", "Kentucky", &["Ky."], US),
+  ("LA", "Louisiana", &["La."], US),
+  ("ME", "Maine", &["Me."], US),
+  ("MD", "Maryland", &["Md."], US),
+  (
+    "MA",
+    "Massachusetts",
+    &["Mass."],
+    US
+  ),
+  ("MI", "Michigan", &["Mich."], US),
+  ("MN", "Minnesota", &["Minn."], US),
+  ("MS", "Mississippi", &["Miss."], US),
+  ("MO", "Missouri", &["Mo."], US),
+  ("MT", "Montana", &["Mont."], US),
+  (
+    "NE",
+    "Nebraska",
+    &["Neb.", "Nebr."],
+    US
+  ),
+  ("NV", "Nevada", &["Nev."], US),
+  (
+    "NH",
+    "New Hampshire",
+    &["N.H."],
+    US
+  ),
+  ("NJ", "New Jersey", &["N.J."], US),
+  (
+    "NM",
+    "New Mexico",
+    &["N.M.", "N.Mex."],
+    US
+  ),
+  ("NY", "New York", &["N.Y."], US),
+  (
+    "NC",
+    "North Carolina",
+    &["N.C."],
+    US
+  ),
+  (
+    "ND",
+    "North Dakota",
+    &["N.D.", "N.Dak."],
+    US
+  ),
+  ("OH", "Ohio", &[], US),
+  ("OK", "Oklahoma", &["Okla."], US),
+  (
+    "OR",
+    "Oregon",
+    &["Ore.", "Oreg."],
+    US
+  ),
+  (
+    "PA",
+    "Pennsylvania",
+    &["Pa.", "Penn."],
+    US
+  ),
+  ("RI", "Rhode Island", &["R.I."], US),
+  (
+    "SC",
+    "South Carolina",
+    &["S.C."],
+    US
+  ),
+  (
+    "SD",
+    "South Dakota",
+    &["S.D.", "S.Dak."],
+    US
+  ),
+  ("TN", "Tennessee", &["Tenn."], US),
+  ("TX", "Texas", &["Tex."], US),
+  ("UT", "Utah", &[], US),
+  ("VT", "Vermont", &["Vt."], US),
+  ("VA", "Virginia", &["Va."], US),
+  ("WA", "Washington", &["Wash."], US),
+  (
+    "WV",
+    "West Virginia",
+    &["W.Va."],
+    US
+  ),
+  (
+    "WI",
+    "Wisconsin",
+    &["Wis.", "Wisc."],
+    US
+  ),
+  ("WY", "Wyoming", &["Wyo."], US),
+  ("AB", "Alberta", &["Alta."], CA),
+  (
+    "BC",
+    "British Columbia",
+    &["B.C."],
+    CA
+  ),
+  ("MB", "Manitoba", &["Man."], CA),
+  (
+    "NB",
+    "New Brunswick",
+    &["N.B."],
+    CA
+  ),
+  (
+    "NL",
+    "Newfoundland and Labrador",
+    &["Nfld.", "Newfoundland"],
+    CA
+  ),
+  ("NS", "Nova Scotia", &["N.S."], CA),
+  (
+    "NT",
+    "Northwest Territories",
+    &["N.W.T."],
+    CA
+  ),
+  ("NU", "Nunavut", &[], CA),
+  ("ON", "Ontario", &["Ont."], CA),
+  (
+    "PE",
+    "Prince Edward Island",
+    &["P.E.I."],
+    CA
+  ),
+  (
+    "QC",
+    "Quebec",
+    &["Que.", "Qué.", "Québec"],
+    CA
+  ),
+  (
+    "SK",
+    "Saskatchewan",
+    &["Sask."],
+    CA
+  ),
+  ("YT", "Yukon", &["Y.T."], CA)
+];
+
+static COUNTRIES: &[(&str, &[&str])] =
+  &[
+    ("Argentina", &[]),
+    ("Australia", &[]),
+    ("Austria", &["Österreich"]),
+    ("Belgium", &[
+      "Belgique", "België"
+    ]),
+    ("Brazil", &["Brasil"]),
+    ("Canada", &[]),
+    ("Chile", &[]),
+    ("China", &["PRC"]),
+    ("Czech Republic", &["Czechia"]),
+    ("Denmark", &["Danmark"]),
+    ("England", &[]),
+    ("Finland", &["Suomi"]),
+    ("France", &[]),
+    ("Germany", &[
+      "Deutschland",
+      "FRG"
+    ]),
+    ("Greece", &[]),
+    ("Hungary", &[]),
+    ("India", &[]),
+    ("Ireland", &["Éire"]),
+    ("Israel", &[]),
+    ("Italy", &["Italia"]),
+    ("Japan", &[]),
+    ("Mexico", &["México"]),
+    ("Netherlands", &[
+      "The Netherlands",
+      "Holland"
+    ]),
+    ("New Zealand", &["NZ"]),
+    ("Norway", &["Norge"]),
+    ("Philippines", &[
+      "The Philippines"
+    ]),
+    ("Poland", &["Polska"]),
+    ("Portugal", &[]),
+    ("Russia", &["Russian Federation"]),
+    ("Scotland", &[]),
+    ("Singapore", &[]),
+    ("South Africa", &[]),
+    ("South Korea", &["Korea"]),
+    ("Spain", &["España"]),
+    ("Sweden", &["Sverige"]),
+    ("Switzerland", &[
+      "Schweiz", "Suisse"
+    ]),
+    ("Turkey", &["Türkiye"]),
+    ("United Kingdom", &[
+      "UK",
+      "U.K.",
+      "Great Britain",
+      "GB"
+    ]),
+    ("United States", &[
+      "USA",
+      "U.S.A.",
+      "US",
+      "U.S.",
+      "United States of America"
+    ]),
+    ("Wales", &[])
+  ];
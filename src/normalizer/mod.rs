@@ -0,0 +1,575 @@
+pub mod abbreviations;
+pub mod container;
+pub mod journal;
+pub mod location;
+pub mod names;
+pub mod publisher;
+pub mod title;
+pub mod volume;
+
+use std::path::Path;
+
+use abbreviations::AbbreviationMap;
+use journal::Normalizer as JournalNormalizer;
+use publisher::{
+  Normalizer as PublisherNormalizer,
+  PublisherTable
+};
+use serde_json::{
+  Map,
+  Value
+};
+use title::{
+  Normalizer as TitleNormalizer,
+  ProtectedWords,
+  TitleCase
+};
+use volume::Normalizer as VolumeNormalizer;
+
+use crate::parser::FieldValue;
+
+#[derive(Debug, Clone, Default)]
+pub struct NormalizationConfig {
+  journal:              AbbreviationMap,
+  publisher:            AbbreviationMap,
+  container:            AbbreviationMap,
+  language:             AbbreviationMap,
+  scripts:              AbbreviationMap,
+  title:                TitleNormalizer,
+  publisher_names: PublisherNormalizer,
+  canonical_publishers: bool,
+  volume: VolumeNormalizer,
+  structured_locations: bool
+}
+
+impl NormalizationConfig {
+  pub fn load_from_dir(
+    dir: &Path
+  ) -> std::io::Result<Self> {
+    Ok(Self {
+      journal:
+        load_abbrev(
+          dir,
+          "journal-abbrev.txt"
+        )?,
+      publisher:
+        load_abbrev(
+          dir,
+          "publisher-abbrev.txt"
+        )?,
+      container:
+        load_abbrev(
+          dir,
+          "container-abbrev.txt"
+        )?,
+      language:
+        load_abbrev(
+          dir,
+          "language-locale.txt"
+        )?,
+      scripts:
+        load_abbrev(
+          dir,
+          "script-locale.txt"
+        )?,
+      title:
+        TitleNormalizer::new()
+          .with_protected_words(
+            load_protected_words(dir)?
+          ),
+      publisher_names:
+        PublisherNormalizer::new()
+          .with_canonical_names(
+            load_publisher_table(
+              dir,
+              "publisher-canonical.txt"
+            )?
+          )
+          .with_imprints(
+            load_publisher_table(
+              dir,
+              "publisher-imprint.txt"
+            )?
+          ),
+      canonical_publishers: false,
+      volume:
+        VolumeNormalizer::new(),
+      structured_locations: false
+    })
+  }
+
+  pub fn with_journal_abbrev(
+    mut self,
+    abbreviations: AbbreviationMap
+  ) -> Self {
+    self.journal = abbreviations;
+    self
+  }
+
+  pub fn with_publisher_abbrev(
+    mut self,
+    abbreviations: AbbreviationMap
+  ) -> Self {
+    self.publisher = abbreviations;
+    self
+  }
+
+  pub fn with_container_abbrev(
+    mut self,
+    abbreviations: AbbreviationMap
+  ) -> Self {
+    self.container = abbreviations;
+    self
+  }
+
+  pub fn with_language_locale(
+    mut self,
+    abbreviations: AbbreviationMap
+  ) -> Self {
+    self.language = abbreviations;
+    self
+  }
+
+  pub fn with_script_locale(
+    mut self,
+    abbreviations: AbbreviationMap
+  ) -> Self {
+    self.scripts = abbreviations;
+    self
+  }
+
+  pub fn with_title_case(
+    mut self,
+    case: TitleCase
+  ) -> Self {
+    self.title =
+      self.title.with_case(case);
+    self
+  }
+
+  pub fn with_protected_words(
+    mut self,
+    protected: ProtectedWords
+  ) -> Self {
+    self.title = self
+      .title
+      .with_protected_words(protected);
+    self
+  }
+
+  pub fn title(
+    &self
+  ) -> &TitleNormalizer {
+    &self.title
+  }
+
+  pub fn with_publisher_names(
+    mut self,
+    publisher_names: PublisherNormalizer
+  ) -> Self {
+    self.publisher_names =
+      publisher_names;
+    self
+  }
+
+  /// Collapse publisher variants into
+  /// the names of the canonical table.
+  pub fn with_canonical_publishers(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.canonical_publishers = enabled;
+    self
+  }
+
+  /// Report imprints under their parent
+  /// publisher; implies canonical
+  /// publisher names.
+  pub fn with_imprint_parents(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.publisher_names = self
+      .publisher_names
+      .with_imprint_parents(enabled);
+    self
+  }
+
+  pub fn publisher_names(
+    &self
+  ) -> &PublisherNormalizer {
+    &self.publisher_names
+  }
+
+  fn publisher_names_active(
+    &self
+  ) -> bool {
+    self.canonical_publishers
+      || self
+        .publisher_names
+        .imprint_parents()
+  }
+
+  /// Rewrite roman numeral volumes and
+  /// issues as arabic numbers.
+  pub fn with_arabic_volumes(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.volume = self
+      .volume
+      .with_arabic_numerals(enabled);
+    self
+  }
+
+  pub fn volume(
+    &self
+  ) -> &VolumeNormalizer {
+    &self.volume
+  }
+
+  /// Emit `structured-location` with
+  /// the city, region and country of
+  /// each publication place.
+  pub fn with_structured_locations(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.structured_locations = enabled;
+    self
+  }
+
+  pub fn structured_locations(
+    &self
+  ) -> bool {
+    self.structured_locations
+  }
+
+  pub fn apply_to_map(
+    &self,
+    map: &mut Map<String, Value>
+  ) {
+    JournalNormalizer::new()
+      .normalize_with_abbrev(
+        map,
+        &self.journal
+      );
+    expand_field(
+      map,
+      "publisher",
+      &self.publisher
+    );
+    if self.publisher_names_active() {
+      map_field(
+        map,
+        "publisher",
+        |text| {
+          self
+            .publisher_names
+            .normalize(text)
+        }
+      );
+    }
+    expand_field(
+      map,
+      "container-title",
+      &self.container
+    );
+    expand_field(
+      map,
+      "language",
+      &self.language
+    );
+    expand_field(
+      map,
+      "scripts",
+      &self.scripts
+    );
+    map_field(map, "title", |text| {
+      self.title.normalize(text)
+    });
+    map_field(map, "volume", |text| {
+      self.volume.normalize_volume(text)
+    });
+    map_field(map, "issue", |text| {
+      self.volume.normalize_issue(text)
+    });
+  }
+
+  /// Braces protected words in the
+  /// title so BibTeX styles cannot
+  /// recase them.
+  pub fn protect_bibtex_title(
+    &self,
+    map: &mut Map<String, Value>
+  ) {
+    if !self.title.is_active() {
+      return;
+    }
+    map_field(map, "title", |text| {
+      self
+        .title
+        .protect_for_bibtex(text)
+    });
+  }
+
+  pub fn apply_to_fields(
+    &self,
+    map: &mut std::collections::BTreeMap<
+      String,
+      FieldValue
+    >
+  ) {
+    expand_field_value(
+      map,
+      "journal",
+      &self.journal
+    );
+    expand_field_value(
+      map,
+      "publisher",
+      &self.publisher
+    );
+    if self.publisher_names_active() {
+      map_field_value(
+        map,
+        "publisher",
+        |text| {
+          self
+            .publisher_names
+            .normalize(text)
+        }
+      );
+    }
+    expand_field_value(
+      map,
+      "container-title",
+      &self.container
+    );
+    expand_field_value(
+      map,
+      "language",
+      &self.language
+    );
+    expand_field_value(
+      map,
+      "scripts",
+      &self.scripts
+    );
+    map_field_value(
+      map,
+      "title",
+      |text| self.title.normalize(text)
+    );
+    map_field_value(
+      map,
+      "volume",
+      |text| {
+        self
+          .volume
+          .normalize_volume(text)
+      }
+    );
+    map_field_value(
+      map,
+      "issue",
+      |text| {
+        self
+          .volume
+          .normalize_issue(text)
+      }
+    );
+  }
+}
+
+fn load_abbrev(
+  dir: &Path,
+  filename: &str
+) -> std::io::Result<AbbreviationMap> {
+  let path = dir.join(filename);
+  if path.exists() {
+    AbbreviationMap::load_from_file(
+      &path
+    )
+  } else {
+    Ok(AbbreviationMap::default())
+  }
+}
+
+fn load_publisher_table(
+  dir: &Path,
+  filename: &str
+) -> std::io::Result<PublisherTable> {
+  let path = dir.join(filename);
+  if path.exists() {
+    PublisherTable::load_from_file(
+      &path
+    )
+  } else {
+    Ok(PublisherTable::default())
+  }
+}
+
+fn load_protected_words(
+  dir: &Path
+) -> std::io::Result<ProtectedWords> {
+  let path =
+    dir.join("protected-words.txt");
+  if path.exists() {
+    ProtectedWords::load_from_file(
+      &path
+    )
+  } else {
+    Ok(ProtectedWords::default())
+  }
+}
+
+fn expand_field(
+  map: &mut Map<String, Value>,
+  key: &str,
+  abbreviations: &AbbreviationMap
+) {
+  map_field(map, key, |text| {
+    abbreviations.expand(text)
+  });
+}
+
+fn map_field(
+  map: &mut Map<String, Value>,
+  key: &str,
+  apply: impl Fn(&str) -> String
+) {
+  let Some(value) = map.get_mut(key)
+  else {
+    return;
+  };
+  match value {
+    | Value::String(text) => {
+      *text = apply(text);
+    }
+    | Value::Array(items) => {
+      for item in items {
+        if let Value::String(text) =
+          item
+        {
+          *text = apply(text);
+        }
+      }
+    }
+    | _ => {}
+  }
+}
+
+fn expand_field_value(
+  map: &mut std::collections::BTreeMap<
+    String,
+    FieldValue
+  >,
+  key: &str,
+  abbreviations: &AbbreviationMap
+) {
+  map_field_value(map, key, |text| {
+    abbreviations.expand(text)
+  });
+}
+
+fn map_field_value(
+  map: &mut std::collections::BTreeMap<
+    String,
+    FieldValue
+  >,
+  key: &str,
+  apply: impl Fn(&str) -> String
+) {
+  let Some(value) = map.get_mut(key)
+  else {
+    return;
+  };
+  match value {
+    | FieldValue::Single(text) => {
+      *text = apply(text);
+    }
+    | FieldValue::List(items) => {
+      for item in items {
+        *item = apply(item);
+      }
+    }
+    | FieldValue::Authors(_)
+    | FieldValue::Places(_) => {}
+  }
+}
+
+fn is_repeater(value: &str) -> bool {
+  let allowed = ['-', '.', ',', ' '];
+  !value.is_empty()
+    && value.chars().all(|c| {
+      c.is_whitespace()
+        || allowed.contains(&c)
+    })
+}
+
+/// Value of a roman numeral in
+/// canonical subtractive notation,
+/// case-insensitive.
+pub(crate) fn roman_numeral_value(
+  value: &str
+) -> Option<u32> {
+  if value.is_empty() {
+    return None;
+  }
+  let mut total = 0u32;
+  let mut previous = 0u32;
+  for ch in value.chars().rev() {
+    let digit =
+      match ch.to_ascii_uppercase() {
+        | 'I' => 1,
+        | 'V' => 5,
+        | 'X' => 10,
+        | 'L' => 50,
+        | 'C' => 100,
+        | 'D' => 500,
+        | 'M' => 1000,
+        | _ => return None
+      };
+    if digit < previous {
+      total =
+        total.checked_sub(digit)?;
+    } else {
+      total += digit;
+      previous = digit;
+    }
+  }
+  // Reject non-canonical spellings such
+  // as "IIII" or "VX".
+  (to_roman_numeral(total)
+    == value.to_ascii_uppercase())
+  .then_some(total)
+}
+
+pub(crate) fn to_roman_numeral(
+  mut value: u32
+) -> String {
+  const TABLE: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I")
+  ];
+  let mut output = String::new();
+  for (amount, numeral) in TABLE {
+    while value >= amount {
+      output.push_str(numeral);
+      value -= amount;
+    }
+  }
+  output
+}
@@ -0,0 +1,481 @@
+use std::collections::{
+  BTreeMap,
+  HashMap
+};
+use std::fs;
+use std::path::Path;
+
+/// Canonical publisher names keyed by
+/// the cluster key of each variant.
+///
+/// Table files list one `variant<TAB>
+/// canonical` pair per line (`=` also
+/// separates); a line holding only a
+/// name registers it as canonical.
+#[derive(Debug, Clone, Default)]
+pub struct PublisherTable {
+  entries: HashMap<String, String>
+}
+
+impl PublisherTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn load_from_str(
+    text: &str
+  ) -> Self {
+    let mut table = Self::new();
+    for line in text.lines() {
+      let trimmed = line.trim();
+      if trimmed.is_empty()
+        || trimmed.starts_with('#')
+      {
+        continue;
+      }
+      match trimmed
+        .split_once(['\t', '='])
+      {
+        | Some((
+          variant,
+          canonical
+        )) => {
+          table.insert(
+            variant.trim(),
+            canonical.trim()
+          );
+        }
+        | None => {
+          table
+            .insert(trimmed, trimmed);
+        }
+      }
+    }
+    table
+  }
+
+  pub fn load_from_file(
+    path: &Path
+  ) -> std::io::Result<Self> {
+    let content =
+      fs::read_to_string(path)?;
+    Ok(Self::load_from_str(&content))
+  }
+
+  pub fn insert(
+    &mut self,
+    variant: &str,
+    canonical: &str
+  ) {
+    let key = cluster_key(variant);
+    if key.is_empty()
+      || canonical.is_empty()
+    {
+      return;
+    }
+    self.entries.insert(
+      key,
+      canonical.to_string()
+    );
+  }
+
+  /// Adds every entry of `other`,
+  /// replacing entries with the same
+  /// key.
+  pub fn extend(
+    &mut self,
+    other: PublisherTable
+  ) {
+    self.entries.extend(other.entries);
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn get(
+    &self,
+    name: &str
+  ) -> Option<&str> {
+    self
+      .entries
+      .get(&cluster_key(name))
+      .map(String::as_str)
+  }
+
+  fn seeded(
+    pairs: &[(&str, &str)]
+  ) -> Self {
+    let mut table = Self::new();
+    for (variant, canonical) in pairs {
+      table.insert(variant, canonical);
+    }
+    table
+  }
+}
+
+/// Collapses publisher variants such as
+/// "Oxford Univ. Press", "OUP" and
+/// "Oxford University Press, Inc." into
+/// one canonical name.
+#[derive(Debug, Clone)]
+pub struct Normalizer {
+  canonical: PublisherTable,
+  imprints:  PublisherTable,
+  parents:   bool
+}
+
+impl Default for Normalizer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Normalizer {
+  pub fn new() -> Self {
+    Self {
+      canonical: PublisherTable::seeded(
+        CANONICAL_NAMES
+      ),
+      imprints:  PublisherTable::seeded(
+        IMPRINT_PARENTS
+      ),
+      parents:   false
+    }
+  }
+
+  /// Adds canonical names on top of the
+  /// built-in table.
+  pub fn with_canonical_names(
+    mut self,
+    table: PublisherTable
+  ) -> Self {
+    self.canonical.extend(table);
+    self
+  }
+
+  /// Adds imprint-to-parent entries on
+  /// top of the built-in table.
+  pub fn with_imprints(
+    mut self,
+    table: PublisherTable
+  ) -> Self {
+    self.imprints.extend(table);
+    self
+  }
+
+  /// Replace imprints such as
+  /// "Clarendon Press" with their
+  /// parent publisher.
+  pub fn with_imprint_parents(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.parents = enabled;
+    self
+  }
+
+  pub fn imprint_parents(
+    &self
+  ) -> bool {
+    self.parents
+  }
+
+  pub fn normalize(
+    &self,
+    input: &str
+  ) -> String {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+      return String::new();
+    }
+    let canonical = self
+      .canonical
+      .get(trimmed)
+      .map(str::to_string)
+      .unwrap_or_else(|| {
+        let cleaned = strip_suffixes(
+          &expand_words(drop_article(
+            trimmed
+          ))
+        );
+        self
+          .canonical
+          .get(&cleaned)
+          .map(str::to_string)
+          .unwrap_or(cleaned)
+      });
+    if self.parents
+      && let Some(parent) =
+        self.imprints.get(&canonical)
+    {
+      return parent.to_string();
+    }
+    canonical
+  }
+
+  /// Groups names by canonical form,
+  /// listing the distinct spellings
+  /// seen for each.
+  pub fn cluster<'a>(
+    &self,
+    names: impl IntoIterator<Item = &'a str>
+  ) -> BTreeMap<String, Vec<String>> {
+    let mut clusters = BTreeMap::<
+      String,
+      Vec<String>
+    >::new();
+    for name in names {
+      let canonical =
+        self.normalize(name);
+      if canonical.is_empty() {
+        continue;
+      }
+      let variants = clusters
+        .entry(canonical)
+        .or_default();
+      let variant =
+        name.trim().to_string();
+      if !variants.contains(&variant) {
+        variants.push(variant);
+      }
+    }
+    clusters
+  }
+}
+
+/// Lowercase alphanumeric words with a
+/// leading "The" dropped; variants that
+/// differ only in punctuation share it.
+pub fn cluster_key(
+  name: &str
+) -> String {
+  let words = name
+    .split(|c: char| {
+      !c.is_alphanumeric() && c != '&'
+    })
+    .filter(|word| !word.is_empty())
+    .map(str::to_lowercase)
+    .collect::<Vec<_>>();
+  let words = match words.first() {
+    | Some(first)
+      if first == "the"
+        && words.len() > 1 =>
+    {
+      &words[1..]
+    }
+    | _ => &words[..]
+  };
+  words
+    .iter()
+    .map(|word| {
+      if word == "&" {
+        "and"
+      } else {
+        word.as_str()
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn drop_article(name: &str) -> &str {
+  match name.split_once(' ') {
+    | Some((first, rest))
+      if first
+        .eq_ignore_ascii_case(
+          "the"
+        )
+        && !rest.trim().is_empty() =>
+    {
+      rest.trim_start()
+    }
+    | _ => name
+  }
+}
+
+fn expand_words(name: &str) -> String {
+  name
+    .split_whitespace()
+    .map(|word| {
+      let (core, trailing) = word
+        .find([',', ';'])
+        .map(|idx| word.split_at(idx))
+        .unwrap_or((word, ""));
+      let expanded = WORD_EXPANSIONS
+        .iter()
+        .find(|(short, _)| {
+          core
+            .eq_ignore_ascii_case(short)
+        })
+        .map(|(_, long)| *long)
+        .unwrap_or(core);
+      format!("{expanded}{trailing}")
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Drops trailing corporate suffixes
+/// ("Publishers", "Ltd.", "& Co.") as
+/// long as a name remains.
+fn strip_suffixes(
+  name: &str
+) -> String {
+  let mut current = name
+    .trim()
+    .trim_end_matches([',', ';'])
+    .trim()
+    .to_string();
+  loop {
+    let lower = current.to_lowercase();
+    let stripped = SUFFIXES
+      .iter()
+      .find_map(|suffix| {
+        let rest =
+          lower.strip_suffix(suffix)?;
+        let boundary = rest
+          .chars()
+          .last()
+          .is_some_and(|c| {
+            c.is_whitespace()
+              || c == ','
+          });
+        if !boundary {
+          return None;
+        }
+        current.get(..rest.len()).map(
+          |kept| {
+            kept
+              .trim_end_matches(
+                |c: char| {
+                  c.is_whitespace()
+                    || c == ','
+                }
+              )
+              .to_string()
+          }
+        )
+      });
+    match stripped {
+      | Some(rest)
+        if rest.chars().any(
+          char::is_alphanumeric
+        ) =>
+      {
+        current = rest;
+      }
+      | _ => break
+    }
+  }
+  current
+    .trim_end_matches([',', ';'])
+    .trim()
+    .to_string()
+}
+
+const WORD_EXPANSIONS: &[(
+  &str,
+  &str
+)] = &[
+  ("Univ.", "University"),
+  ("Univ", "University"),
+  ("Pr.", "Press"),
+  ("Pub.", "Publishing"),
+  ("Publ.", "Publishing"),
+  ("Inst.", "Institute"),
+  ("Assoc.", "Association"),
+  ("Soc.", "Society")
+];
+
+// Longer suffixes first so "& co." wins
+// over "co.".
+const SUFFIXES: &[&str] = &[
+  "publishers",
+  "publisher",
+  "& company",
+  "and company",
+  "& co.",
+  "& co",
+  "and co.",
+  "co.",
+  "incorporated",
+  "inc.",
+  "inc",
+  "limited",
+  "ltd.",
+  "ltd",
+  "llc",
+  "plc",
+  "gmbh",
+  "corp.",
+  "corporation",
+  "s.a."
+];
+
+const CANONICAL_NAMES: &[(
+  &str,
+  &str
+)] = &[
+  ("OUP", "Oxford University Press"),
+  ("CUP", "Cambridge University Press"),
+  ("HUP", "Harvard University Press"),
+  ("PUP", "Princeton University Press"),
+  ("YUP", "Yale University Press"),
+  (
+    "UCP",
+    "University of Chicago Press"
+  ),
+  (
+    "Univ. of Chicago Press",
+    "University of Chicago Press"
+  ),
+  (
+    "Massachusetts Institute of \
+     Technology Press",
+    "MIT Press"
+  ),
+  ("Springer-Verlag", "Springer"),
+  ("Springer Verlag", "Springer"),
+  ("Wiley-Blackwell", "Wiley"),
+  ("John Wiley & Sons", "Wiley"),
+  ("John Wiley and Sons", "Wiley"),
+  (
+    "Routledge & Kegan Paul",
+    "Routledge"
+  ),
+  ("Elsevier Science", "Elsevier")
+];
+
+const IMPRINT_PARENTS: &[(
+  &str,
+  &str
+)] = &[
+  (
+    "Clarendon Press",
+    "Oxford University Press"
+  ),
+  (
+    "Belknap Press",
+    "Harvard University Press"
+  ),
+  ("Academic Press", "Elsevier"),
+  ("Pergamon Press", "Elsevier"),
+  ("North-Holland", "Elsevier"),
+  ("Apress", "Springer"),
+  ("Birkhäuser", "Springer"),
+  (
+    "Vintage Books",
+    "Penguin Random House"
+  ),
+  (
+    "Alfred A. Knopf",
+    "Penguin Random House"
+  ),
+  ("Knopf", "Penguin Random House"),
+  ("Jossey-Bass", "Wiley")
+];
@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use super::roman_numeral_value;
+
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  ValueEnum,
+)]
+pub enum TitleCase {
+  #[default]
+  AsIs,
+  Sentence,
+  Title
+}
+
+/// Words whose spelling must survive
+/// case conversion, keyed by their
+/// lowercase form. Entries may span
+/// several words ("New York").
+#[derive(Debug, Clone, Default)]
+pub struct ProtectedWords {
+  entries:   HashMap<String, String>,
+  max_words: usize
+}
+
+impl ProtectedWords {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn load_from_str(
+    text: &str
+  ) -> Self {
+    let mut words = Self::new();
+    for line in text.lines() {
+      let trimmed = line.trim();
+      if trimmed.is_empty()
+        || trimmed.starts_with('#')
+      {
+        continue;
+      }
+      words.insert(trimmed);
+    }
+    words
+  }
+
+  pub fn load_from_file(
+    path: &Path
+  ) -> std::io::Result<Self> {
+    let content =
+      fs::read_to_string(path)?;
+    Ok(Self::load_from_str(&content))
+  }
+
+  pub fn insert(
+    &mut self,
+    word: impl Into<String>
+  ) {
+    let word = word
+      .into()
+      .split_whitespace()
+      .collect::<Vec<_>>()
+      .join(" ");
+    if word.is_empty() {
+      return;
+    }
+    let count = word.split(' ').count();
+    self.max_words =
+      self.max_words.max(count);
+    self.entries.insert(
+      word.to_lowercase(),
+      word
+    );
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn get(
+    &self,
+    word: &str
+  ) -> Option<&str> {
+    self
+      .entries
+      .get(&word.to_lowercase())
+      .map(String::as_str)
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+  case:      TitleCase,
+  protected: ProtectedWords
+}
+
+impl Normalizer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_case(
+    mut self,
+    case: TitleCase
+  ) -> Self {
+    self.case = case;
+    self
+  }
+
+  pub fn with_protected_words(
+    mut self,
+    protected: ProtectedWords
+  ) -> Self {
+    self.protected = protected;
+    self
+  }
+
+  pub fn case(&self) -> TitleCase {
+    self.case
+  }
+
+  pub fn protected_words(
+    &self
+  ) -> &ProtectedWords {
+    &self.protected
+  }
+
+  /// Whether the normalizer changes
+  /// anything at all; the default
+  /// configuration leaves titles
+  /// verbatim.
+  pub fn is_active(&self) -> bool {
+    self.case != TitleCase::AsIs
+      || !self.protected.is_empty()
+  }
+
+  pub fn normalize(
+    &self,
+    input: &str
+  ) -> String {
+    if self.case == TitleCase::AsIs {
+      return input.to_string();
+    }
+    let words = split_words(input);
+    let shouting = is_shouting(&words);
+    let mut output =
+      Vec::with_capacity(words.len());
+    let mut idx = 0usize;
+    let mut starts_clause = true;
+    while idx < words.len() {
+      if let Some((span, phrase)) = self
+        .match_protected(&words, idx)
+      {
+        output.push(join_phrase(
+          &words[idx..idx + span],
+          phrase
+        ));
+        starts_clause = ends_clause(
+          &words[idx + span - 1]
+        );
+        idx += span;
+        continue;
+      }
+      let word = &words[idx];
+      let is_last =
+        idx + 1 == words.len();
+      let core = if preserve_core(
+        &word.core, shouting
+      ) {
+        word.core.clone()
+      } else {
+        self.convert(
+          &word.core,
+          starts_clause,
+          is_last
+        )
+      };
+      output.push(format!(
+        "{}{}{}",
+        word.prefix, core, word.suffix
+      ));
+      starts_clause = ends_clause(word);
+      idx += 1;
+    }
+    output.join(" ")
+  }
+
+  /// Wraps protected words, acronyms
+  /// and formulae in braces so BibTeX
+  /// styles keep their case.
+  pub fn protect_for_bibtex(
+    &self,
+    input: &str
+  ) -> String {
+    let words = split_words(input);
+    let shouting = is_shouting(&words);
+    let mut output =
+      Vec::with_capacity(words.len());
+    let mut idx = 0usize;
+    while idx < words.len() {
+      if let Some((span, phrase)) = self
+        .match_protected(&words, idx)
+      {
+        let slice =
+          &words[idx..idx + span];
+        output.push(format!(
+          "{}{{{}}}{}",
+          slice[0].prefix,
+          phrase,
+          slice[span - 1].suffix
+        ));
+        idx += span;
+        continue;
+      }
+      let word = &words[idx];
+      if !word.core.is_empty()
+        && preserve_core(
+          &word.core, shouting
+        )
+        && word
+          .core
+          .chars()
+          .any(char::is_uppercase)
+      {
+        output.push(format!(
+          "{}{{{}}}{}",
+          word.prefix,
+          word.core,
+          word.suffix
+        ));
+      } else {
+        output.push(format!(
+          "{}{}{}",
+          word.prefix,
+          word.core,
+          word.suffix
+        ));
+      }
+      idx += 1;
+    }
+    output.join(" ")
+  }
+
+  fn match_protected(
+    &self,
+    words: &[Word],
+    start: usize
+  ) -> Option<(usize, String)> {
+    let max = self
+      .protected
+      .max_words
+      .min(words.len() - start);
+    for span in (1..=max).rev() {
+      let slice =
+        &words[start..start + span];
+      // Inner punctuation breaks a
+      // phrase: "York, New" is not "New
+      // York".
+      if slice[..span - 1].iter().any(
+        |word| !word.suffix.is_empty()
+      ) || slice[1..].iter().any(
+        |word| !word.prefix.is_empty()
+      ) {
+        continue;
+      }
+      let key = slice
+        .iter()
+        .map(|word| word.core.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+      if let Some(found) =
+        self.protected.get(&key)
+      {
+        return Some((
+          span,
+          found.to_string()
+        ));
+      }
+    }
+    None
+  }
+
+  fn convert(
+    &self,
+    core: &str,
+    starts_clause: bool,
+    is_last: bool
+  ) -> String {
+    match self.case {
+      | TitleCase::AsIs => {
+        core.to_string()
+      }
+      | TitleCase::Sentence => {
+        if starts_clause {
+          capitalize(core)
+        } else {
+          core.to_lowercase()
+        }
+      }
+      | TitleCase::Title => {
+        let lower = core.to_lowercase();
+        if !starts_clause
+          && !is_last
+          && MINOR_WORDS
+            .contains(&lower.as_str())
+        {
+          lower
+        } else {
+          core
+            .split('-')
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join("-")
+        }
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Word {
+  prefix: String,
+  core:   String,
+  suffix: String
+}
+
+fn split_words(
+  input: &str
+) -> Vec<Word> {
+  input
+    .split_whitespace()
+    .map(|raw| {
+      let start = raw
+        .find(char::is_alphanumeric)
+        .unwrap_or(raw.len());
+      let end = raw
+        .rfind(char::is_alphanumeric)
+        .map(|pos| {
+          pos
+            + raw[pos..]
+              .chars()
+              .next()
+              .map(char::len_utf8)
+              .unwrap_or(0)
+        })
+        .unwrap_or(start)
+        .max(start);
+      Word {
+        prefix: raw[..start]
+          .to_string(),
+        core:   raw[start..end]
+          .to_string(),
+        suffix: raw[end..].to_string()
+      }
+    })
+    .collect()
+}
+
+fn join_phrase(
+  words: &[Word],
+  phrase: String
+) -> String {
+  let prefix = words
+    .first()
+    .map(|word| word.prefix.as_str())
+    .unwrap_or("");
+  let suffix = words
+    .last()
+    .map(|word| word.suffix.as_str())
+    .unwrap_or("");
+  format!("{prefix}{phrase}{suffix}")
+}
+
+fn ends_clause(word: &Word) -> bool {
+  word.suffix.chars().any(|c| {
+    matches!(c, ':' | '?' | '!' | '.')
+  }) && !is_initial(&word.core)
+}
+
+fn is_initial(core: &str) -> bool {
+  core.chars().count() == 1
+}
+
+/// OCR output and some catalogs print
+/// whole titles in capitals; acronyms
+/// cannot be told apart by case there.
+fn is_shouting(words: &[Word]) -> bool {
+  let cased = words
+    .iter()
+    .filter(|word| {
+      word
+        .core
+        .chars()
+        .any(char::is_alphabetic)
+    })
+    .count();
+  if cased < 2 {
+    return false;
+  }
+  let mut upper = 0usize;
+  let mut lower = 0usize;
+  for ch in words
+    .iter()
+    .flat_map(|word| word.core.chars())
+  {
+    if ch.is_uppercase() {
+      upper += 1;
+    } else if ch.is_lowercase() {
+      lower += 1;
+    }
+  }
+  upper > 0 && lower * 5 < upper
+}
+
+fn preserve_core(
+  core: &str,
+  shouting: bool
+) -> bool {
+  if core.is_empty() || core == "I" {
+    return true;
+  }
+  if is_formula(core) {
+    return true;
+  }
+  if is_roman_numeral(core) {
+    return true;
+  }
+  if shouting {
+    return false;
+  }
+  is_acronym(core)
+    || has_inner_capital(core)
+}
+
+fn is_acronym(core: &str) -> bool {
+  let letters = core
+    .trim_end_matches("'s")
+    .chars()
+    .filter(|c| c.is_alphabetic())
+    .collect::<Vec<_>>();
+  letters.len() >= 2
+    && letters
+      .iter()
+      .all(|c| c.is_uppercase())
+}
+
+fn has_inner_capital(
+  core: &str
+) -> bool {
+  core.split('-').any(|part| {
+    part
+      .chars()
+      .skip(1)
+      .any(char::is_uppercase)
+      && part
+        .chars()
+        .any(char::is_lowercase)
+  })
+}
+
+/// Matches element-symbol sequences
+/// with counts such as "H2O", "CO2" or
+/// "C6H12O6".
+fn is_formula(core: &str) -> bool {
+  let chars =
+    core.chars().collect::<Vec<_>>();
+  if !chars.first().is_some_and(|c| {
+    c.is_ascii_uppercase()
+  }) || !chars
+    .iter()
+    .any(|c| c.is_ascii_digit())
+  {
+    return false;
+  }
+  let mut idx = 0usize;
+  while idx < chars.len() {
+    if !chars[idx].is_ascii_uppercase()
+    {
+      return false;
+    }
+    idx += 1;
+    if idx < chars.len()
+      && chars[idx].is_ascii_lowercase()
+    {
+      idx += 1;
+    }
+    while idx < chars.len()
+      && chars[idx].is_ascii_digit()
+    {
+      idx += 1;
+    }
+  }
+  true
+}
+
+fn is_roman_numeral(
+  core: &str
+) -> bool {
+  core.len() >= 2
+    && core.chars().all(|c| {
+      matches!(c, 'I' | 'V' | 'X')
+    })
+    && roman_numeral_value(core)
+      .is_some()
+}
+
+fn capitalize(word: &str) -> String {
+  let mut chars = word.chars();
+  match chars.next() {
+    | Some(first) => {
+      first
+        .to_uppercase()
+        .chain(
+          chars.flat_map(
+            char::to_lowercase
+          )
+        )
+        .collect()
+    }
+    | None => String::new()
+  }
+}
+
+const MINOR_WORDS: &[&str] = &[
+  "a", "an", "and", "as", "at", "but",
+  "by", "for", "from", "in", "into",
+  "nor", "of", "on", "or", "per",
+  "the", "to", "via", "vs", "with"
+];
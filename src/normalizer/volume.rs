@@ -0,0 +1,358 @@
+use super::roman_numeral_value;
+
+/// Which field a volume or issue
+/// keyword introduces.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum Marker {
+  Volume,
+  Issue,
+  Supplement
+}
+
+/// Volume, issue and supplement
+/// keywords with the language they are
+/// used in.
+pub static KEYWORDS: &[(
+  &str,
+  Marker,
+  &str
+)] = &[
+  ("volume", Marker::Volume, "en"),
+  ("vol.", Marker::Volume, "en"),
+  ("vol", Marker::Volume, "en"),
+  ("vols.", Marker::Volume, "en"),
+  ("v.", Marker::Volume, "en"),
+  ("band", Marker::Volume, "de"),
+  ("bd.", Marker::Volume, "de"),
+  ("jahrgang", Marker::Volume, "de"),
+  ("jg.", Marker::Volume, "de"),
+  ("tome", Marker::Volume, "fr"),
+  ("t.", Marker::Volume, "fr"),
+  ("tomo", Marker::Volume, "es"),
+  ("número", Marker::Issue, "es"),
+  ("núm.", Marker::Issue, "es"),
+  ("n.º", Marker::Issue, "es"),
+  ("nº", Marker::Issue, "es"),
+  ("number", Marker::Issue, "en"),
+  ("no.", Marker::Issue, "en"),
+  ("nos.", Marker::Issue, "en"),
+  ("issue", Marker::Issue, "en"),
+  ("iss.", Marker::Issue, "en"),
+  ("heft", Marker::Issue, "de"),
+  ("h.", Marker::Issue, "de"),
+  ("nr.", Marker::Issue, "de"),
+  ("numéro", Marker::Issue, "fr"),
+  ("n°", Marker::Issue, "fr"),
+  ("fasc.", Marker::Issue, "fr"),
+  ("fascicule", Marker::Issue, "fr"),
+  (
+    "supplement",
+    Marker::Supplement,
+    "en"
+  ),
+  ("suppl.", Marker::Supplement, "en"),
+  ("suppl", Marker::Supplement, "en"),
+  ("supp.", Marker::Supplement, "en"),
+  ("beiheft", Marker::Supplement, "de"),
+  (
+    "suplemento",
+    Marker::Supplement,
+    "es"
+  )
+];
+
+/// Marker and language for a keyword
+/// token such as "Bd." or "núm.".
+pub fn keyword(
+  token: &str
+) -> Option<(Marker, &'static str)> {
+  let lower = token.to_lowercase();
+  KEYWORDS.iter().find_map(
+    |(word, marker, language)| {
+      (*word == lower)
+        .then_some((*marker, *language))
+    }
+  )
+}
+
+/// An issue split into its numbers and
+/// supplement; "3/4" and "7–8" are
+/// combined issues, "Suppl 2" a
+/// supplement.
+#[derive(
+  Debug, Clone, Default, PartialEq, Eq,
+)]
+pub struct Issue {
+  pub numbers:    Vec<String>,
+  pub supplement: Option<String>
+}
+
+impl Issue {
+  pub fn is_combined(&self) -> bool {
+    self.numbers.len() > 1
+  }
+
+  pub fn is_supplement(&self) -> bool {
+    self.supplement.is_some()
+  }
+
+  /// Canonical form: "3-4", "Suppl. 2",
+  /// "4 Suppl." or the plain number.
+  pub fn label(&self) -> String {
+    let numbers =
+      self.numbers.join("-");
+    match self.supplement.as_deref() {
+      | Some("")
+        if numbers.is_empty() =>
+      {
+        "Suppl.".to_string()
+      }
+      | Some("") => {
+        format!("{numbers} Suppl.")
+      }
+      | Some(supplement)
+        if numbers.is_empty() =>
+      {
+        format!("Suppl. {supplement}")
+      }
+      | Some(supplement) => {
+        format!(
+          "{numbers} Suppl. \
+           {supplement}"
+        )
+      }
+      | None => numbers
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+  arabic: bool
+}
+
+impl Normalizer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Rewrite roman numeral volumes and
+  /// issues ("XII") as arabic numbers.
+  pub fn with_arabic_numerals(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.arabic = enabled;
+    self
+  }
+
+  pub fn arabic_numerals(
+    &self
+  ) -> bool {
+    self.arabic
+  }
+
+  pub fn normalize_volume(
+    &self,
+    input: &str
+  ) -> String {
+    let value = strip_keywords(
+      input,
+      Marker::Volume
+    );
+    if value.contains(", Part ") {
+      return value;
+    }
+    split_range(&value)
+      .map(|parts| {
+        parts
+          .iter()
+          .map(|part| self.number(part))
+          .collect::<Vec<_>>()
+          .join("-")
+      })
+      .unwrap_or_else(|| {
+        self.number(&value)
+      })
+  }
+
+  pub fn normalize_issue(
+    &self,
+    input: &str
+  ) -> String {
+    let trimmed = input.trim();
+    if trimmed.contains(", Part ") {
+      return trimmed.to_string();
+    }
+    let issue =
+      self.parse_issue(trimmed);
+    if issue.is_supplement()
+      || issue.is_combined()
+    {
+      issue.label()
+    } else if trimmed
+      .split_whitespace()
+      .count()
+      == 1
+    {
+      self.number(trimmed)
+    } else {
+      trimmed.to_string()
+    }
+  }
+
+  pub fn parse_issue(
+    &self,
+    input: &str
+  ) -> Issue {
+    let mut issue = Issue::default();
+    let tokens = input
+      .split_whitespace()
+      .flat_map(split_glued_supplement)
+      .collect::<Vec<_>>();
+    let mut in_supplement = false;
+    for token in tokens {
+      let bare = token.trim_matches(
+        |c: char| {
+          matches!(
+            c,
+            ',' | ';' | ':' | '(' | ')'
+          )
+        }
+      );
+      if bare.is_empty() {
+        continue;
+      }
+      match keyword(bare) {
+        | Some((
+          Marker::Supplement,
+          _
+        )) => {
+          in_supplement = true;
+          issue
+            .supplement
+            .get_or_insert_default();
+          continue;
+        }
+        | Some(_) => continue,
+        | None => {}
+      }
+      let numbers = split_range(bare)
+        .unwrap_or_else(|| {
+          vec![bare.to_string()]
+        })
+        .into_iter()
+        .map(|part| self.number(&part))
+        .collect::<Vec<_>>();
+      if in_supplement {
+        issue.supplement =
+          Some(numbers.join("-"));
+      } else {
+        issue.numbers.extend(numbers);
+      }
+    }
+    issue
+  }
+
+  fn number(
+    &self,
+    value: &str
+  ) -> String {
+    let trimmed = value
+      .trim()
+      .trim_end_matches(['.', ',']);
+    if self.arabic
+      && is_roman(trimmed)
+      && let Some(number) =
+        roman_numeral_value(trimmed)
+    {
+      return number.to_string();
+    }
+    trimmed.to_string()
+  }
+}
+
+/// Whether `value` is a roman numeral
+/// that reads as a volume ("XII",
+/// "iv").
+pub fn is_roman(value: &str) -> bool {
+  !value.is_empty()
+    && (value
+      .chars()
+      .all(|c| c.is_ascii_uppercase())
+      || value.chars().all(|c| {
+        c.is_ascii_lowercase()
+      }))
+    && roman_numeral_value(value)
+      .is_some()
+}
+
+/// Splits combined numbers such as
+/// "3/4", "7–8" or "7-8".
+pub fn split_range(
+  value: &str
+) -> Option<Vec<String>> {
+  let parts = value
+    .split(['/', '-', '\u{2013}'])
+    .map(str::trim)
+    .collect::<Vec<_>>();
+  (parts.len() == 2
+    && parts.iter().all(|part| {
+      !part.is_empty()
+        && (part
+          .chars()
+          .all(|c| c.is_ascii_digit())
+          || is_roman(part))
+    }))
+  .then(|| {
+    parts
+      .into_iter()
+      .map(str::to_string)
+      .collect()
+  })
+}
+
+fn strip_keywords(
+  input: &str,
+  marker: Marker
+) -> String {
+  input
+    .split_whitespace()
+    .filter(|token| {
+      keyword(token).is_none_or(
+        |(found, _)| found != marker
+      )
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+    .trim_matches(|c: char| {
+      matches!(c, ',' | ';' | ':')
+    })
+    .trim()
+    .to_string()
+}
+
+/// "Suppl2" style tokens carry the
+/// supplement number glued on.
+fn split_glued_supplement(
+  token: &str
+) -> Vec<String> {
+  let lower = token.to_lowercase();
+  for prefix in ["suppl.", "suppl"] {
+    if let Some(rest) =
+      lower.strip_prefix(prefix)
+      && !rest.is_empty()
+      && rest
+        .chars()
+        .all(|c| c.is_ascii_digit())
+    {
+      return vec![
+        prefix.to_string(),
+        rest.to_string(),
+      ];
+    }
+  }
+  vec![token.to_string()]
+}
@@ -1,6 +1,7 @@
 use crate::dictionary::{
   Dictionary,
-  DictionaryAdapter
+  DictionaryAdapter,
+  LayeredDictionary
 };
 use crate::format::ParseFormat;
 use crate::language::{
@@ -8,9 +9,11 @@ use crate::language::{
   detect_scripts
 };
 use crate::normalizer::NormalizationConfig;
+use crate::normalizer::location::Normalizer as LocationNormalizer;
 use crate::parser::extract::{
+  ReferenceKeywords,
   authors_for_reference,
-  collect_year_tokens,
+  collect_year_tokens_with_terms,
   detect_circa,
   extract_citation_number,
   extract_collection_number,
@@ -18,29 +21,40 @@ use crate::parser::extract::{
   extract_container_title,
   extract_doi,
   extract_edition,
-  extract_editor_list,
+  extract_editor_list_with_terms,
   extract_genre,
   extract_identifiers,
   extract_isbn,
   extract_issn,
   extract_issue,
-  extract_journal_with_dictionary,
+  extract_journal_with_terms,
   extract_location,
   extract_note,
-  extract_pages,
+  extract_pages_with_terms,
   extract_publisher,
   extract_title,
-  extract_translator,
+  extract_translator_with_terms,
   extract_url,
-  extract_volume,
-  resolve_type_with_dictionary,
+  extract_volume_with_terms,
+  resolve_type_with_terms,
   split_references,
   tag_token
 };
 use crate::parser::field_tokens::FieldTokens;
+use crate::parser::input::{
+  InputNormalizer,
+  NormalizedInput
+};
+use crate::parser::join::{
+  JoinedReference,
+  LineJoiner
+};
+use crate::parser::spans::field_spans;
 use crate::parser::types::{
   FieldValue,
+  Place,
   Reference,
+  ReferenceSpans,
   TaggedToken
 };
 
@@ -55,8 +69,10 @@ const PREPARED_LINES: [&str; 2] = [
 
 #[derive(Debug)]
 pub struct Parser {
-  dictionary:    Dictionary,
-  normalization: NormalizationConfig
+  dictionary:    LayeredDictionary,
+  normalization: NormalizationConfig,
+  input:         InputNormalizer,
+  joiner:        Option<LineJoiner>
 }
 
 impl Default for Parser {
@@ -77,19 +93,29 @@ impl Parser {
         Dictionary::create(
           DictionaryAdapter::Memory
         )
-        .open(),
+        .open()
+        .into(),
       normalization:
-        NormalizationConfig::default()
+        NormalizationConfig::default(),
+      input:
+        InputNormalizer::new(),
+      joiner:        None
     }
   }
 
+  /// Accepts a [`Dictionary`] or a
+  /// [`LayeredDictionary`] with
+  /// overlays and a deny list.
   pub fn with_dictionary(
-    dictionary: Dictionary
+    dictionary: impl Into<LayeredDictionary>
   ) -> Self {
     Self {
-      dictionary,
+      dictionary:    dictionary.into(),
       normalization:
-        NormalizationConfig::default()
+        NormalizationConfig::default(),
+      input:
+        InputNormalizer::new(),
+      joiner:        None
     }
   }
 
@@ -100,18 +126,75 @@ impl Parser {
       dictionary: Dictionary::create(
         DictionaryAdapter::Memory
       )
-      .open(),
-      normalization
+      .open()
+      .into(),
+      normalization,
+      input: InputNormalizer::new(),
+      joiner: None
     }
   }
 
   pub fn with_dictionary_and_normalization(
-    dictionary: Dictionary,
+    dictionary: impl Into<LayeredDictionary>,
     normalization: NormalizationConfig
   ) -> Self {
     Self {
-      dictionary,
-      normalization
+      dictionary: dictionary.into(),
+      normalization,
+      input: InputNormalizer::new(),
+      joiner: None
+    }
+  }
+
+  /// Replaces the Unicode and OCR
+  /// cleanup applied before
+  /// tokenization.
+  pub fn with_input_normalizer(
+    mut self,
+    input: InputNormalizer
+  ) -> Self {
+    self.input = input;
+    self
+  }
+
+  /// Splits input into references with
+  /// `joiner`, so references wrapped
+  /// over several lines parse whole.
+  /// Without one, each line is a
+  /// reference.
+  pub fn with_line_joiner(
+    mut self,
+    joiner: LineJoiner
+  ) -> Self {
+    self.joiner = Some(joiner);
+    self
+  }
+
+  /// Cleaned input as seen by the
+  /// extractors, with offsets back into
+  /// `input`.
+  pub fn normalize_input(
+    &self,
+    input: &str
+  ) -> NormalizedInput {
+    self.input.normalize(input)
+  }
+
+  /// References of `input`, one per
+  /// line or as re-joined by the line
+  /// joiner.
+  fn references(
+    &self,
+    input: &str
+  ) -> Vec<String> {
+    match &self.joiner {
+      | Some(joiner) => {
+        joiner.join_with_dictionary(
+          input,
+          Some(&self.dictionary)
+        )
+      }
+      | None => split_references(input)
     }
   }
 
@@ -136,11 +219,11 @@ impl Parser {
       ]);
     }
 
-    let sequences = input
-      .lines()
-      .filter(|line| {
-        !line.trim().is_empty()
-      })
+    let normalized =
+      self.input.normalize(input);
+    let sequences = self
+      .references(normalized.as_str())
+      .iter()
       .map(|line| {
         line
           .split_whitespace()
@@ -162,8 +245,11 @@ impl Parser {
     &self,
     input: &str
   ) -> Vec<Vec<TaggedToken>> {
+    let normalized =
+      self.input.normalize(input);
+    let input = normalized.as_str();
     let references =
-      split_references(input);
+      self.references(input);
     let contexts: Vec<FieldTokens> = references
       .iter()
       .map(|reference| {
@@ -207,14 +293,40 @@ impl Parser {
   ) -> Vec<Reference> {
     refs
       .iter()
-      .map(|reference| {
+      .flat_map(|source| {
+        match &self.joiner {
+          | Some(joiner) => {
+            joiner.join_with_offsets(
+              source,
+              Some(&self.dictionary),
+            )
+          }
+          | None => {
+            vec![JoinedReference::new(
+              source,
+            )]
+          }
+        }
+      })
+      .map(|joined| {
+        let source = joined.text().to_string();
         let mut mapped = Reference::new();
         mapped.insert(
           "__source",
           FieldValue::List(vec![
-            (*reference).to_string()
+            source.clone()
           ]),
         );
+        let normalized =
+          self.input.normalize(&source);
+        let reference = normalized.as_str();
+        // Keyword and journal terms of the
+        // reference come from one batch
+        // lookup.
+        let terms = ReferenceKeywords::new(
+          reference,
+          &self.dictionary,
+        );
         let authors =
           authors_for_reference(reference);
         if !authors.is_empty() {
@@ -240,9 +352,9 @@ impl Parser {
         mapped.insert(
           "type",
           FieldValue::Single(
-            resolve_type_with_dictionary(
+            resolve_type_with_terms(
               reference,
-              &self.dictionary,
+              &terms,
             ),
           ),
         );
@@ -252,10 +364,7 @@ impl Parser {
           FieldValue::List(vec![location.clone()]),
         );
         if !location.is_empty() {
-          mapped.insert(
-            "publisher-place",
-            FieldValue::List(vec![location.clone()]),
-          );
+          self.insert_places(&mut mapped, &location);
         }
         mapped.insert(
           "publisher",
@@ -291,9 +400,9 @@ impl Parser {
         }
 
         if let Some(journal) =
-          extract_journal_with_dictionary(
+          extract_journal_with_terms(
             reference,
-            Some(&self.dictionary),
+            Some(&terms),
           )
         {
           let journal_value = journal.clone();
@@ -312,7 +421,10 @@ impl Parser {
           }
         }
 
-        let editors = extract_editor_list(reference);
+        let editors = extract_editor_list_with_terms(
+            reference,
+            &terms,
+          );
         if !editors.is_empty() {
           mapped.insert(
             "editor",
@@ -320,7 +432,10 @@ impl Parser {
           );
         }
         if let Some(translator) =
-          extract_translator(reference)
+          extract_translator_with_terms(
+            reference,
+            &terms,
+          )
         {
           mapped.insert(
             "translator",
@@ -377,7 +492,10 @@ impl Parser {
           );
         }
 
-        if let Some(volume) = extract_volume(reference) {
+        if let Some(volume) = extract_volume_with_terms(
+          reference,
+          &terms,
+        ) {
           mapped.insert(
             "volume",
             FieldValue::List(vec![volume]),
@@ -405,7 +523,10 @@ impl Parser {
         }
 
         let mut year_values =
-          collect_year_tokens(reference);
+          collect_year_tokens_with_terms(
+            reference,
+            &terms,
+          );
         if year_values.is_empty() {
           year_values.push(String::new());
         }
@@ -421,7 +542,10 @@ impl Parser {
         }
         mapped.insert(
           "pages",
-          FieldValue::List(vec![extract_pages(reference)]),
+          FieldValue::List(vec![extract_pages_with_terms(
+            reference,
+            &terms,
+          )]),
         );
         mapped.insert(
           "language",
@@ -431,11 +555,65 @@ impl Parser {
           "scripts",
           FieldValue::List(detect_scripts(reference)),
         );
-        self.apply_normalization(mapped)
+        let reference =
+          self.apply_normalization(mapped);
+        // Spans are read off the final
+        // values, in input offsets.
+        let mut spans = ReferenceSpans {
+          end: source.len(),
+          fields: field_spans(
+            reference.fields(),
+            &normalized,
+          ),
+          ..ReferenceSpans::default()
+        };
+        spans.map_ranges(|range| {
+          joined.source_range(range)
+        });
+        reference.with_spans(spans)
       })
       .collect()
   }
 
+  fn insert_places(
+    &self,
+    mapped: &mut Reference,
+    location: &str
+  ) {
+    let places =
+      LocationNormalizer::new()
+        .parse(
+          location,
+          Some(&self.dictionary)
+        )
+        .places;
+    let labels = places
+      .iter()
+      .map(Place::label)
+      .filter(|label| !label.is_empty())
+      .collect::<Vec<_>>();
+    mapped.insert(
+      "publisher-place",
+      FieldValue::List(
+        if labels.is_empty() {
+          vec![location.to_string()]
+        } else {
+          labels
+        }
+      )
+    );
+    if self
+      .normalization
+      .structured_locations()
+      && !places.is_empty()
+    {
+      mapped.insert(
+        "structured-location",
+        FieldValue::Places(places)
+      );
+    }
+  }
+
   fn apply_normalization(
     &self,
     reference: Reference
@@ -1,14 +1,28 @@
 use std::collections::BTreeSet;
 
 use crate::dictionary::{
-  Dictionary,
-  DictionaryCode
+  DictionaryCode,
+  keywords
+};
+use crate::normalizer::volume::{
+  self,
+  Marker
 };
 use crate::parser::types::Author;
 
+mod dictionary_keywords;
+mod numbering;
 mod tagging;
 mod tokenize;
 
+use dictionary_keywords::BuiltinKeywords;
+pub(crate) use dictionary_keywords::{
+  KeywordTerms,
+  ReferenceKeywords,
+  collect_year_tokens_with_terms,
+  extract_editor_list_with_terms,
+  extract_translator_with_terms
+};
 pub(crate) use tagging::tag_token;
 pub(crate) use tokenize::{
   normalize_compare_value,
@@ -955,14 +969,9 @@ fn is_page_marker(token: &str) -> bool {
   {
     return true;
   }
-  matches!(
-    lower.as_str(),
-    "p"
-      | "p."
-      | "pp"
-      | "pp."
-      | "page"
-      | "pages"
+  keywords::is_keyword(
+    keywords::PAGE_LOCATORS,
+    &lower
   )
 }
 
@@ -1114,25 +1123,7 @@ fn extract_day_token(
 fn month_number(
   token: &str
 ) -> Option<u32> {
-  let lower = token.to_lowercase();
-  let abbrev =
-    lower.trim_end_matches(['.', ',']);
-  match abbrev {
-    | "jan" | "january" => Some(1),
-    | "feb" | "february" => Some(2),
-    | "mar" | "march" => Some(3),
-    | "apr" | "april" => Some(4),
-    | "may" => Some(5),
-    | "jun" | "june" => Some(6),
-    | "jul" | "july" => Some(7),
-    | "aug" | "august" => Some(8),
-    | "sep" | "sept" | "september"
-    | "septembre" => Some(9),
-    | "oct" | "october" => Some(10),
-    | "nov" | "november" => Some(11),
-    | "dec" | "december" => Some(12),
-    | _ => None
-  }
+  keywords::month_number(token)
 }
 
 fn normalize_date_part(
@@ -3027,7 +3018,7 @@ fn looks_like_short_journal(
 
 fn segment_journal_score(
   segment: &str,
-  dictionary: Option<&Dictionary>
+  terms: Option<&dyn KeywordTerms>
 ) -> usize {
   let mut score = 0usize;
   if segment_is_journal_like(segment) {
@@ -3054,18 +3045,17 @@ fn segment_journal_score(
   {
     score += 2;
   }
-  if let Some(dictionary) = dictionary {
+  if let Some(terms) = terms {
     let matches = segment
       .split(|c: char| {
         !c.is_alphanumeric()
       })
       .filter(|token| !token.is_empty())
       .filter(|token| {
-        dictionary
-          .lookup(token)
-          .contains(
-            &DictionaryCode::Journal
-          )
+        terms.is_term(
+          DictionaryCode::Journal,
+          token
+        )
       })
       .count();
     score += matches * 2;
@@ -3110,7 +3100,8 @@ fn strip_numeric_suffix(
   segment.trim().to_string()
 }
 fn resolve_type(
-  reference: &str
+  reference: &str,
+  terms: &dyn KeywordTerms
 ) -> String {
   let lower = reference.to_lowercase();
   if lower.contains("chapter")
@@ -3119,8 +3110,17 @@ fn resolve_type(
   {
     return "chapter".into();
   }
-  if lower.contains("thesis")
-    || lower.contains("dissertation")
+  if reference
+    .split(|c: char| {
+      !c.is_alphanumeric()
+    })
+    .any(|word| {
+      !word.is_empty()
+        && terms.is_term(
+          DictionaryCode::Genre,
+          word
+        )
+    })
   {
     return "thesis".into();
   }
@@ -3163,9 +3163,9 @@ fn resolve_type(
   "book".into()
 }
 
-pub(crate) fn resolve_type_with_dictionary(
+pub(crate) fn resolve_type_with_terms(
   reference: &str,
-  dictionary: &Dictionary
+  terms: &dyn KeywordTerms
 ) -> String {
   let lower = reference.to_lowercase();
   if lower.contains("chapter")
@@ -3174,22 +3174,19 @@ pub(crate) fn resolve_type_with_dictionary(
   {
     return "chapter".into();
   }
-  for token in
-    reference.split(|c: char| {
+  if reference
+    .split(|c: char| {
       !c.is_alphanumeric()
     })
-  {
-    if token.is_empty() {
-      continue;
-    }
-    if dictionary
-      .lookup(token)
-      .contains(
-        &DictionaryCode::Journal
+    .filter(|token| !token.is_empty())
+    .any(|token| {
+      terms.is_term(
+        DictionaryCode::Journal,
+        token
       )
-    {
-      return "article".into();
-    }
+    })
+  {
+    return "article".into();
   }
   if split_reference_segments(reference)
     .iter()
@@ -3199,7 +3196,7 @@ pub(crate) fn resolve_type_with_dictionary(
   {
     return "paper-conference".into();
   }
-  resolve_type(reference)
+  resolve_type(reference, terms)
 }
 
 pub(crate) fn extract_location(
@@ -3235,11 +3232,8 @@ fn extract_location_publisher(
   let before = reference[..pos].trim();
   let after =
     reference[pos + 1..].trim();
-  let location_segment = before
-    .rsplit('.')
-    .next()
-    .unwrap_or(before)
-    .trim();
+  let location_segment =
+    trailing_location_segment(before);
   if !is_location_segment(
     location_segment
   ) {
@@ -3258,9 +3252,47 @@ fn extract_location_publisher(
   (location, publisher)
 }
 
+/// Text after the last sentence period,
+/// keeping abbreviations such as
+/// "Mass." or "N.Y." that end a
+/// location.
+fn trailing_location_segment(
+  before: &str
+) -> &str {
+  let trimmed = before.trim_end();
+  for (idx, ch) in
+    trimmed.char_indices().rev()
+  {
+    if ch != '.' {
+      continue;
+    }
+    let rest = &trimmed[idx + 1..];
+    if rest.is_empty()
+      || !rest.starts_with(
+        char::is_whitespace
+      )
+    {
+      continue;
+    }
+    return rest.trim();
+  }
+  trimmed
+}
+
 fn is_location_segment(
   segment: &str
 ) -> bool {
+  if segment.contains('&') {
+    let parts = segment
+      .split('&')
+      .map(str::trim)
+      .collect::<Vec<_>>();
+    return parts.len() > 1
+      && parts.iter().all(|part| {
+        !part.contains('&')
+          && is_location_segment(part)
+      });
+  }
   let words = segment
     .split_whitespace()
     .collect::<Vec<_>>();
@@ -3302,6 +3334,18 @@ fn is_location_segment(
 
 pub(crate) fn extract_pages(
   reference: &str
+) -> String {
+  extract_pages_with_terms(
+    reference,
+    &BuiltinKeywords
+  )
+}
+
+/// [`extract_pages`] with page locators
+/// answered by `terms`.
+pub(crate) fn extract_pages_with_terms(
+  reference: &str,
+  terms: &dyn KeywordTerms
 ) -> String {
   let tokens = reference
     .split_whitespace()
@@ -3386,9 +3430,9 @@ pub(crate) fn extract_pages(
       }
       continue;
     }
-    if matches!(
-      lower.as_str(),
-      "p" | "pp" | "p." | "pp."
+    if terms.is_term(
+      DictionaryCode::PageLocator,
+      &lower
     ) && let Some(next) =
       tokens.get(idx + 1)
     {
@@ -3799,14 +3843,17 @@ fn capture_number_after(
 pub(crate) fn extract_journal(
   reference: &str
 ) -> Option<String> {
-  extract_journal_with_dictionary(
+  extract_journal_with_terms(
     reference, None
   )
 }
 
-pub(crate) fn extract_journal_with_dictionary(
+/// [`extract_journal`] with journal
+/// words from `terms` raising the
+/// score of a segment.
+pub(crate) fn extract_journal_with_terms(
   reference: &str,
-  dictionary: Option<&Dictionary>
+  terms: Option<&dyn KeywordTerms>
 ) -> Option<String> {
   let title = extract_title(reference);
   let title_norm =
@@ -3859,7 +3906,7 @@ pub(crate) fn extract_journal_with_dictionary(
     }
     let mut score =
       segment_journal_score(
-        trimmed, dictionary
+        trimmed, terms
       );
     let candidate =
       extract_journal_from_segment(
@@ -4305,14 +4352,9 @@ fn strip_leading_date(
 pub(crate) fn extract_editor(
   reference: &str
 ) -> Option<String> {
-  let keywords = [
-    "edited by",
-    "edited",
-    "editor",
-    "eds"
-  ];
-
-  for keyword in keywords {
+  for keyword in
+    keywords::EDITOR_PHRASES
+  {
     if let Some(segment) =
       segment_after_keyword(
         reference, keyword
@@ -4325,9 +4367,9 @@ pub(crate) fn extract_editor(
             || c == ')'
         })
         .to_lowercase();
-      if matches!(
-        normalized.as_str(),
-        "ed" | "ed." | "eds" | "eds."
+      if keywords::is_keyword(
+        keywords::EDITOR_ABBREVIATIONS,
+        &normalized
       ) {
         continue;
       }
@@ -4511,13 +4553,9 @@ fn strip_parenthetical_date(
 pub(crate) fn extract_translator(
   reference: &str
 ) -> Option<String> {
-  let keywords = [
-    "translated by",
-    "translator",
-    "trans."
-  ];
-
-  for keyword in keywords {
+  for keyword in
+    keywords::TRANSLATOR_PHRASES
+  {
     if let Some(segment) =
       segment_after_keyword(
         reference, keyword
@@ -4782,39 +4820,139 @@ fn segment_after_keyword(
     .map(clean_segment)
 }
 
+/// Keywords read by the generic issue
+/// scan.
+const ISSUE_LOCATORS: [&str; 3] =
+  ["number", "no.", "issue"];
+
+/// Whether the generic scan reads a
+/// plain number right after a keyword
+/// ending at one of `ends`; the marked
+/// path in [`numbering::marked_value`]
+/// is for references where it does
+/// not.
+fn has_plain_number_after(
+  reference: &str,
+  ends: &[usize]
+) -> bool {
+  ends.iter().any(|end| {
+    numbering::plain_number_after(
+      reference, *end
+    )
+    .is_some()
+  })
+}
+
+/// Offsets just past the volume
+/// locators of `reference`, in reading
+/// order; "vol.12" ends after "vol.".
+/// Single-letter locators only count in
+/// lower case, as "V." is an initial.
+fn volume_locator_ends(
+  reference: &str,
+  terms: &dyn KeywordTerms
+) -> Vec<usize> {
+  reference
+    .split_whitespace()
+    .filter_map(|token| {
+      let word = token
+        .trim_start_matches(['(', '[']);
+      let start = token.as_ptr()
+        as usize
+        - reference.as_ptr() as usize
+        + token.len()
+        - word.len();
+      let word = word
+        .split(|c: char| {
+          c.is_ascii_digit()
+        })
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches([
+          ',', ';', ':'
+        ]);
+      let letters =
+        word.trim_end_matches('.');
+      let initial =
+        letters.chars().count() == 1
+          && letters
+            .chars()
+            .any(char::is_uppercase);
+      (!letters.is_empty()
+        && !initial
+        && terms.is_term(
+          DictionaryCode::VolumeLocator,
+          word
+        ))
+      .then_some(start + word.len())
+    })
+    .collect()
+}
+
 pub(crate) fn extract_volume(
   reference: &str
+) -> Option<String> {
+  extract_volume_with_terms(
+    reference,
+    &BuiltinKeywords
+  )
+}
+
+/// [`extract_volume`] with volume
+/// locators answered by `terms`.
+pub(crate) fn extract_volume_with_terms(
+  reference: &str,
+  terms: &dyn KeywordTerms
 ) -> Option<String> {
   let cleaned =
     strip_leading_citation_number(
       reference
     );
-  let lower = cleaned.to_lowercase();
-  for keyword in [
-    "volume", "vol.", "vol", "v.",
-    "vols"
-  ] {
-    if let Some(pos) =
-      lower.find(keyword)
+  let ends = volume_locator_ends(
+    &cleaned, terms
+  );
+  if !has_plain_number_after(
+    &cleaned, &ends
+  ) && let Some((volume, end)) =
+    numbering::marked_value(
+      &cleaned,
+      Marker::Volume
+    )
+  {
+    return Some(with_part_suffix(
+      volume,
+      &cleaned[end..]
+    ));
+  }
+  for start in ends {
+    let remainder = cleaned
+      .get(start..)
+      .unwrap_or("");
+    // The number follows the locator;
+    // "Vol. XII, no. 3" has none.
+    if !remainder
+      .trim_start_matches(|c: char| {
+        !c.is_alphanumeric()
+      })
+      .starts_with(|c: char| {
+        c.is_ascii_digit()
+      })
     {
-      let start = pos + keyword.len();
-      let remainder = cleaned
-        .get(start..)
-        .unwrap_or("");
-      if let Some(volume) =
-        capture_number_after(
-          &cleaned, start
-        )
+      continue;
+    }
+    if let Some(volume) =
+      capture_number_after(
+        &cleaned, start
+      )
+    {
+      if let Some(part) =
+        extract_part_suffix(remainder)
       {
-        if let Some(part) =
-          extract_part_suffix(remainder)
-        {
-          return Some(format!(
-            "{volume}, Part {part}"
-          ));
-        }
-        return Some(volume);
+        return Some(format!(
+          "{volume}, Part {part}"
+        ));
       }
+      return Some(volume);
     }
   }
   for segment in
@@ -4864,9 +5002,35 @@ pub(crate) fn extract_issue(
   reference: &str
 ) -> Option<String> {
   let lower = reference.to_lowercase();
-  for keyword in
-    ["number", "no.", "issue"]
+  let ends = ISSUE_LOCATORS
+    .iter()
+    .filter_map(|keyword| {
+      lower
+        .find(keyword)
+        .map(|pos| pos + keyword.len())
+    })
+    .collect::<Vec<_>>();
+  if !has_plain_number_after(
+    reference, &ends
+  ) && let Some((issue, end)) =
+    numbering::marked_value(
+      reference,
+      Marker::Issue
+    )
   {
+    return Some(with_part_suffix(
+      issue,
+      &reference[end..]
+    ));
+  }
+  if let Some(issue) =
+    numbering::supplement_value(
+      reference
+    )
+  {
+    return Some(issue);
+  }
+  for keyword in ISSUE_LOCATORS {
     if let Some(pos) =
       lower.find(keyword)
     {
@@ -5044,7 +5208,19 @@ fn parse_volume_issue_pair(
       inside.to_lowercase();
     let inside_digits =
       number_token(inside);
-    if !inside_lower.contains("vol")
+    if numbering::supplement_value(
+      inside
+    )
+    .is_some()
+      || volume::split_range(
+        inside.trim()
+      )
+      .is_some()
+    {
+      issue =
+        Some(inside.trim().to_string());
+    } else if !inside_lower
+      .contains("vol")
       && !inside_lower.contains("part")
       && !inside_lower.contains("no.")
       && let Some(value) = inside_digits
@@ -5141,6 +5317,18 @@ fn year_token_index(
   })
 }
 
+fn with_part_suffix(
+  value: String,
+  remainder: &str
+) -> String {
+  match extract_part_suffix(remainder) {
+    | Some(part) => {
+      format!("{value}, Part {part}")
+    }
+    | None => value
+  }
+}
+
 fn extract_part_suffix(
   segment: &str
 ) -> Option<String> {
@@ -0,0 +1,329 @@
+//! Keyword-driven fields read through
+//! the parser dictionary. Built-in
+//! month, role, genre and locator terms
+//! reach the parser through the
+//! dictionary's keyword layer, so
+//! imported terms ("outubro", "red.",
+//! "s.") extend them and deny lists
+//! remove them. Multi-word phrases
+//! ("edited by") span several keys and
+//! stay patterns of the extractors.
+
+use std::collections::{
+  BTreeSet,
+  HashMap
+};
+
+use super::{
+  collect_year_tokens,
+  extract_day_token,
+  extract_editor_list,
+  extract_translator,
+  split_editor_names
+};
+use crate::dictionary::{
+  DictionaryCode,
+  DictionaryLookup,
+  DictionaryWeights,
+  keywords
+};
+
+/// Answers whether a word is a keyword
+/// term of a code.
+pub(crate) trait KeywordTerms {
+  fn is_term(
+    &self,
+    code: DictionaryCode,
+    word: &str
+  ) -> bool;
+
+  /// Month number of a month term.
+  fn month(
+    &self,
+    word: &str
+  ) -> Option<u32>;
+}
+
+/// The built-in tables alone, for
+/// extraction without a dictionary.
+pub(crate) struct BuiltinKeywords;
+
+impl KeywordTerms for BuiltinKeywords {
+  fn is_term(
+    &self,
+    code: DictionaryCode,
+    word: &str
+  ) -> bool {
+    keywords::is_builtin(code, word)
+  }
+
+  fn month(
+    &self,
+    word: &str
+  ) -> Option<u32> {
+    keywords::month_number(word)
+  }
+}
+
+/// Dictionary answers for the words of
+/// one reference, looked up in one
+/// batch. Words are the whitespace
+/// tokens without surrounding
+/// punctuation and their alphanumeric
+/// runs; others are not terms.
+pub(crate) struct ReferenceKeywords {
+  weights:
+    HashMap<String, DictionaryWeights>
+}
+
+impl ReferenceKeywords {
+  pub(crate) fn new(
+    reference: &str,
+    dictionary: &dyn DictionaryLookup
+  ) -> Self {
+    let words = reference
+      .split_whitespace()
+      .flat_map(|token| {
+        let word = word_key(token);
+        let whole = word
+          .chars()
+          .all(char::is_alphanumeric)
+          .then(|| word.clone());
+        let runs = word
+          .split(|c: char| {
+            !c.is_alphanumeric()
+          })
+          .map(str::to_string)
+          .collect::<Vec<_>>();
+        whole.into_iter().chain(runs)
+      })
+      .filter(|word| !word.is_empty())
+      .collect::<BTreeSet<_>>()
+      .into_iter()
+      .collect::<Vec<_>>();
+    let weights = dictionary
+      .lookup_many(
+        &words
+          .iter()
+          .map(String::as_str)
+          .collect::<Vec<_>>()
+      );
+    Self {
+      weights: words
+        .into_iter()
+        .zip(weights)
+        .collect()
+    }
+  }
+}
+
+impl KeywordTerms
+  for ReferenceKeywords
+{
+  fn is_term(
+    &self,
+    code: DictionaryCode,
+    word: &str
+  ) -> bool {
+    self
+      .weights
+      .get(&word_key(word))
+      .is_some_and(|weights| {
+        weights.weight(code) > 0.0
+      })
+  }
+
+  fn month(
+    &self,
+    word: &str
+  ) -> Option<u32> {
+    self
+      .weights
+      .get(&word_key(word))
+      .and_then(
+        DictionaryWeights::month
+      )
+  }
+}
+
+fn word_key(word: &str) -> String {
+  word
+    .trim_matches(|c: char| {
+      c.is_ascii_punctuation()
+    })
+    .to_lowercase()
+}
+
+pub(crate) fn extract_editor_list_with_terms(
+  reference: &str,
+  terms: &dyn KeywordTerms
+) -> Vec<String> {
+  let editors =
+    extract_editor_list(reference);
+  if !editors.is_empty() {
+    return editors;
+  }
+  role_names(
+    reference,
+    terms,
+    DictionaryCode::Editor
+  )
+  .map(|names| {
+    split_editor_names(&names)
+  })
+  .unwrap_or_default()
+}
+
+pub(crate) fn extract_translator_with_terms(
+  reference: &str,
+  terms: &dyn KeywordTerms
+) -> Option<String> {
+  extract_translator(reference).or_else(
+    || {
+      role_names(
+        reference,
+        terms,
+        DictionaryCode::Translator
+      )
+    }
+  )
+}
+
+/// Date parts with the month the
+/// dictionary gives: an imported month
+/// term adds one, a denied built-in
+/// name drops it.
+pub(crate) fn collect_year_tokens_with_terms(
+  reference: &str,
+  terms: &dyn KeywordTerms
+) -> Vec<String> {
+  let mut parts =
+    collect_year_tokens(reference);
+  if parts.is_empty() {
+    return parts;
+  }
+  let words = reference
+    .split_whitespace()
+    .map(|token| {
+      token.trim_matches(|c: char| {
+        c.is_ascii_punctuation()
+      })
+    })
+    .filter(|token| !token.is_empty())
+    .collect::<Vec<_>>();
+  let named =
+    words.iter().find_map(|word| {
+      keywords::month_number(word)
+    });
+  let found = words
+    .iter()
+    .enumerate()
+    .find_map(|(idx, word)| {
+      terms
+        .month(word)
+        .map(|month| (idx, month))
+    });
+  let read_month = parts
+    .get(1)
+    .and_then(|part| part.parse().ok());
+  match found {
+    | None => {
+      // Only a month read from a
+      // built-in name is dropped;
+      // numeric dates keep theirs.
+      if named.is_some()
+        && read_month == named
+      {
+        parts.truncate(1);
+      }
+    }
+    | Some((_, month))
+      if named.is_some() =>
+    {
+      if let Some(part) =
+        parts.get_mut(1)
+      {
+        *part = format!("{month:02}");
+      }
+    }
+    | Some((idx, month))
+      if parts.len() == 1
+        && parts[0].len() == 4 =>
+    {
+      parts.push(format!("{month:02}"));
+      let day = words
+        .get(idx + 1)
+        .and_then(|word| {
+          extract_day_token(word)
+        })
+        .or_else(|| {
+          idx
+            .checked_sub(1)
+            .and_then(|pos| {
+              words.get(pos)
+            })
+            .and_then(|word| {
+              extract_day_token(word)
+            })
+        });
+      parts.extend(day);
+    }
+    | Some(_) => {}
+  }
+  parts
+}
+
+/// First word of `words` that is a
+/// term of `code`.
+fn keyword_index(
+  words: &[&str],
+  terms: &dyn KeywordTerms,
+  code: DictionaryCode
+) -> Option<usize> {
+  words.iter().position(|word| {
+    terms.is_term(code, word)
+  })
+}
+
+/// Names introduced by a role keyword
+/// ("red. J. Kowalski") or, for a
+/// parenthesised keyword, the names
+/// before it ("Müller, K. (Hrsg.)").
+fn role_names(
+  reference: &str,
+  terms: &dyn KeywordTerms,
+  code: DictionaryCode
+) -> Option<String> {
+  let words = reference
+    .split_whitespace()
+    .collect::<Vec<_>>();
+  let idx =
+    keyword_index(&words, terms, code)?;
+  let names = if words[idx]
+    .starts_with('(')
+  {
+    let start = words[..idx]
+      .iter()
+      .rposition(|word| {
+        word.ends_with([':', ';'])
+          || word
+            .eq_ignore_ascii_case("in")
+      })
+      .map_or(0, |pos| pos + 1);
+    words[start..idx].join(" ")
+  } else {
+    let rest =
+      words[idx + 1..].join(" ");
+    rest
+      .split([',', ';', ':', '('])
+      .next()
+      .unwrap_or_default()
+      .to_string()
+  };
+  let names = names
+    .trim_matches(|c: char| {
+      c.is_whitespace() || c == ','
+    })
+    .to_string();
+  (!names.is_empty()).then_some(names)
+}
@@ -0,0 +1,184 @@
+use crate::normalizer::volume::{
+  Marker,
+  is_roman,
+  keyword,
+  split_range
+};
+
+/// Value after a volume or issue
+/// keyword when the generic digit scan
+/// would miss or mangle it: roman
+/// numerals ("Vol. XII"), combined
+/// numbers ("no. 7–8") and localized
+/// keywords ("Bd. 5", "núm. 2").
+/// Returns the value and the offset
+/// after it.
+pub(super) fn marked_value(
+  reference: &str,
+  marker: Marker
+) -> Option<(String, usize)> {
+  let tokens = reference
+    .split_whitespace()
+    .collect::<Vec<_>>();
+  tokens.windows(2).find_map(|pair| {
+    let bare = pair[0]
+      .trim_start_matches(['(', '['])
+      .trim_end_matches([
+        ',', ';', ':'
+      ]);
+    let (found, language) =
+      keyword(bare)?;
+    // "T. L." and "H. D." are author
+    // initials, not "t. 5" or "H. 2".
+    if found != marker
+      || (bare.chars().count() == 2
+        && is_initial(pair[1]))
+    {
+      return None;
+    }
+    let value = pair[1]
+      .trim_matches(|c: char| {
+        matches!(
+          c,
+          ',' | ';' | ':' | '(' | ')'
+        )
+      })
+      .trim_end_matches('.');
+    let accepted = (is_roman(value)
+      && (2..=6).contains(
+        &value.chars().count()
+      ))
+      || split_range(value).is_some()
+      || (language != "en"
+        && !value.is_empty()
+        && value.len() <= 3
+        && value
+          .chars()
+          .all(|c| c.is_ascii_digit()));
+    // Offset just past the value token,
+    // for callers looking for a "Part"
+    // suffix.
+    let end = pair[1].as_ptr() as usize
+      - reference.as_ptr() as usize
+      + pair[1].len();
+    accepted
+      .then(|| (value.to_string(), end))
+  })
+}
+
+/// A token such as "D." or "L.,".
+fn is_initial(token: &str) -> bool {
+  let mut chars = token
+    .trim_end_matches([',', ';', ':'])
+    .chars();
+  matches!(
+    (chars.next(), chars.next(), chars.next()),
+    (Some(letter), Some('.'), None)
+      if letter.is_uppercase()
+  )
+}
+
+/// Plain number right after a keyword
+/// ending at `start`, as in "vol. 12,".
+/// `None` when the next token is not
+/// all digits, such as "XII" or "7–8".
+pub(super) fn plain_number_after(
+  reference: &str,
+  start: usize
+) -> Option<String> {
+  let token = reference
+    .get(start..)?
+    .split_whitespace()
+    .next()?
+    .trim_matches(|c: char| {
+      matches!(
+        c,
+        ','
+          | ';'
+          | ':'
+          | '('
+          | ')'
+          | '.'
+      )
+    });
+  (!token.is_empty()
+    && token
+      .chars()
+      .all(|c| c.is_ascii_digit()))
+  .then(|| token.to_string())
+}
+
+/// Supplement marker with its optional
+/// number, as in "41(Suppl 2)" or "12
+/// Suppl. 1".
+pub(super) fn supplement_value(
+  reference: &str
+) -> Option<String> {
+  let tokens = reference
+    .split_whitespace()
+    .collect::<Vec<_>>();
+  for (idx, token) in
+    tokens.iter().enumerate()
+  {
+    let bare =
+      token.trim_matches(|c: char| {
+        matches!(
+          c,
+          ',' | ';' | ':' | '(' | ')'
+        )
+      });
+    let lower = bare.to_lowercase();
+    if let Some(number) = lower
+      .strip_prefix("suppl")
+      .filter(|rest| {
+        !rest.is_empty()
+          && rest
+            .chars()
+            .all(|c| c.is_ascii_digit())
+      })
+    {
+      return Some(format!(
+        "Suppl {number}"
+      ));
+    }
+    if !matches!(
+      keyword(bare),
+      Some((Marker::Supplement, _))
+    ) {
+      continue;
+    }
+    let number = tokens
+      .get(idx + 1)
+      .map(|next| {
+        next.trim_matches(|c: char| {
+          !c.is_ascii_digit()
+        })
+      })
+      .filter(|next| {
+        !next.is_empty()
+          && next.len() <= 3
+          && next
+            .chars()
+            .all(|c| c.is_ascii_digit())
+      });
+    // A bare "Supplement" only counts
+    // in parentheses; titles use
+    // the word too.
+    match number {
+      | Some(number) => {
+        return Some(format!(
+          "Suppl {number}"
+        ));
+      }
+      | None
+        if token.starts_with('(') =>
+      {
+        return Some(
+          "Suppl".to_string()
+        );
+      }
+      | None => {}
+    }
+  }
+  None
+}
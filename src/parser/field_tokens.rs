@@ -22,10 +22,17 @@ use super::extract::{
   tokens_from_segment
 };
 use crate::dictionary::{
-  Dictionary,
-  DictionaryCode
+  DictionaryCode,
+  DictionaryLookup
 };
 
+/// Dictionary codes weighing less than
+/// this share of a term's strongest
+/// code are ignored, so a scored
+/// "Jordan" that is mostly a name does
+/// not also mark a place.
+const DOMINANT_WEIGHT_RATIO: f32 = 0.5;
+
 #[derive(Debug, Clone, Default)]
 pub(super) struct FieldTokens {
   pub(super) author: BTreeSet<String>,
@@ -138,7 +145,7 @@ impl FieldTokens {
 
   pub(super) fn from_reference_with_dictionary(
     reference: &str,
-    dictionary: &Dictionary
+    dictionary: &dyn DictionaryLookup
   ) -> Self {
     let mut tokens =
       Self::from_reference(reference);
@@ -151,24 +158,39 @@ impl FieldTokens {
   pub(super) fn apply_dictionary(
     &mut self,
     reference: &str,
-    dictionary: &Dictionary
+    dictionary: &dyn DictionaryLookup
   ) {
-    for term in
-      reference.split(|c: char| {
+    let (terms, normalized): (
+      Vec<_>,
+      Vec<_>
+    ) = reference
+      .split(|c: char| {
         !c.is_alphanumeric()
       })
+      .map(str::trim)
+      .filter(|term| !term.is_empty())
+      .map(|term| {
+        (term, normalize_token(term))
+      })
+      .filter(|(_, normalized)| {
+        !normalized.is_empty()
+      })
+      .unzip();
+    // One batch lookup per reference
+    // keeps remote stores to a single
+    // round trip.
+    let weights =
+      dictionary.lookup_many(&terms);
+    for (normalized, weights) in
+      normalized
+        .into_iter()
+        .zip(weights)
     {
-      let term = term.trim();
-      if term.is_empty() {
-        continue;
-      }
-      let normalized =
-        normalize_token(term);
-      if normalized.is_empty() {
-        continue;
-      }
-      for code in
-        dictionary.lookup(term)
+      for code in weights
+        .restricted(
+          &DictionaryCode::FIELDS
+        )
+        .dominant(DOMINANT_WEIGHT_RATIO)
       {
         match code {
           | DictionaryCode::Name => {
@@ -187,6 +209,7 @@ impl FieldTokens {
             self.journal
               .insert(normalized.clone());
           }
+          | _ => {}
         }
       }
     }
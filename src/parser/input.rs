@@ -0,0 +1,432 @@
+use std::ops::Range;
+
+use clap::ValueEnum;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::canonical_combining_class;
+
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  ValueEnum,
+)]
+pub enum UnicodeForm {
+  None,
+  #[default]
+  Nfc,
+  Nfkc
+}
+
+/// Cleans OCR and typesetting artifacts
+/// before tokenization: Unicode
+/// normalization, ligature expansion,
+/// dash and quote unification, space
+/// variants and invisible or control
+/// characters.
+#[derive(Debug, Clone, Copy)]
+pub struct InputNormalizer {
+  form:      UnicodeForm,
+  ligatures: bool,
+  dashes:    bool,
+  quotes:    bool,
+  spaces:    bool,
+  controls:  bool
+}
+
+impl Default for InputNormalizer {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl InputNormalizer {
+  /// Every cleanup step enabled with
+  /// NFC composition.
+  pub fn new() -> Self {
+    Self {
+      form:      UnicodeForm::Nfc,
+      ligatures: true,
+      dashes:    true,
+      quotes:    true,
+      spaces:    true,
+      controls:  true
+    }
+  }
+
+  /// Passes input through untouched.
+  pub fn disabled() -> Self {
+    Self {
+      form:      UnicodeForm::None,
+      ligatures: false,
+      dashes:    false,
+      quotes:    false,
+      spaces:    false,
+      controls:  false
+    }
+  }
+
+  pub fn with_form(
+    mut self,
+    form: UnicodeForm
+  ) -> Self {
+    self.form = form;
+    self
+  }
+
+  /// Expand typographic ligatures such
+  /// as "ﬁ" and "ﬂ".
+  pub fn with_ligatures(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.ligatures = enabled;
+    self
+  }
+
+  /// Map hyphen, en/em dash and minus
+  /// variants to `-`.
+  pub fn with_dashes(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.dashes = enabled;
+    self
+  }
+
+  /// Map curly and low quotes to their
+  /// ASCII counterparts. A right single
+  /// quote becomes `'` only when it
+  /// closes a left one; apostrophes, as
+  /// in "L’écriture", stay.
+  pub fn with_quotes(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.quotes = enabled;
+    self
+  }
+
+  /// Map non-breaking and other fixed
+  /// width spaces to a plain space.
+  pub fn with_spaces(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.spaces = enabled;
+    self
+  }
+
+  /// Drop control characters, soft
+  /// hyphens and zero-width marks.
+  pub fn with_control_stripping(
+    mut self,
+    enabled: bool
+  ) -> Self {
+    self.controls = enabled;
+    self
+  }
+
+  pub fn form(&self) -> UnicodeForm {
+    self.form
+  }
+
+  pub fn normalize(
+    &self,
+    input: &str
+  ) -> NormalizedInput {
+    let mut output = NormalizedInput {
+      text:  String::with_capacity(
+        input.len()
+      ),
+      spans: Vec::with_capacity(
+        input.len()
+      )
+    };
+    let closing = closing_quotes(input);
+    for span in starter_chunks(input) {
+      let chunk = &input[span.clone()];
+      let formed = match self.form {
+        | UnicodeForm::None => {
+          chunk.to_string()
+        }
+        | UnicodeForm::Nfc => {
+          chunk.nfc().collect()
+        }
+        | UnicodeForm::Nfkc => {
+          chunk.nfkc().collect()
+        }
+      };
+      for ch in formed.chars() {
+        self.push_char(
+          ch,
+          &span,
+          &closing,
+          &mut output
+        );
+      }
+    }
+    output
+  }
+
+  fn push_char(
+    &self,
+    ch: char,
+    span: &Range<usize>,
+    closing: &[usize],
+    output: &mut NormalizedInput
+  ) {
+    if self.controls && is_invisible(ch)
+    {
+      return;
+    }
+    if self.spaces && is_space(ch) {
+      output.push(' ', span);
+      return;
+    }
+    if self.ligatures
+      && let Some(expanded) =
+        expand_ligature(ch)
+    {
+      for part in expanded.chars() {
+        output.push(part, span);
+      }
+      return;
+    }
+    if self.dashes && is_dash(ch) {
+      output.push('-', span);
+      return;
+    }
+    if self.quotes
+      && (ch != '\u{2019}'
+        || closing
+          .binary_search(&span.start)
+          .is_ok())
+      && let Some(quote) =
+        unify_quote(ch)
+    {
+      output.push(quote, span);
+      return;
+    }
+    output.push(ch, span);
+  }
+}
+
+/// Normalized text with the span of
+/// original input each byte came from.
+#[derive(
+  Debug, Clone, Default, PartialEq, Eq,
+)]
+pub struct NormalizedInput {
+  text:  String,
+  spans: Vec<Range<usize>>
+}
+
+impl NormalizedInput {
+  pub fn as_str(&self) -> &str {
+    &self.text
+  }
+
+  pub fn into_string(self) -> String {
+    self.text
+  }
+
+  /// Byte offset in the original input
+  /// where the character at `offset` in
+  /// the normalized text starts. The
+  /// end of the text maps past the last
+  /// original character.
+  pub fn original_offset(
+    &self,
+    offset: usize
+  ) -> usize {
+    self
+      .spans
+      .get(offset)
+      .map(|span| span.start)
+      .unwrap_or_else(|| {
+        self
+          .spans
+          .last()
+          .map(|span| span.end)
+          .unwrap_or(0)
+      })
+  }
+
+  /// Original byte range covering a
+  /// range of the normalized text.
+  pub fn original_range(
+    &self,
+    range: Range<usize>
+  ) -> Range<usize> {
+    if range.is_empty() {
+      let offset = self
+        .original_offset(range.start);
+      return offset..offset;
+    }
+    let start =
+      self.original_offset(range.start);
+    let end = self
+      .spans
+      .get(range.end - 1)
+      .map(|span| span.end)
+      .unwrap_or_else(|| {
+        self.original_offset(range.end)
+      });
+    start..end.max(start)
+  }
+
+  fn push(
+    &mut self,
+    ch: char,
+    span: &Range<usize>
+  ) {
+    self.text.push(ch);
+    for _ in 0..ch.len_utf8() {
+      self.spans.push(span.clone());
+    }
+  }
+}
+
+/// Splits input before every starter so
+/// each chunk holds a base character
+/// and its combining marks;
+/// normalization never reorders across
+/// chunks.
+fn starter_chunks(
+  input: &str
+) -> Vec<Range<usize>> {
+  let mut chunks = Vec::new();
+  let mut start = 0usize;
+  for (idx, ch) in input.char_indices()
+  {
+    if idx > start
+      && canonical_combining_class(ch)
+        == 0
+    {
+      chunks.push(start..idx);
+      start = idx;
+    }
+  }
+  if start < input.len() {
+    chunks.push(start..input.len());
+  }
+  chunks
+}
+
+fn is_invisible(ch: char) -> bool {
+  match ch {
+    // Line structure and the form feed
+    // page separator stay intact.
+    | '\n' | '\r' | '\t'
+    | '\u{000C}' => false,
+    | '\u{00AD}' | '\u{200B}'
+    | '\u{200E}' | '\u{200F}'
+    | '\u{2060}' | '\u{FEFF}' => true,
+    | _ => ch.is_control()
+  }
+}
+
+fn is_space(ch: char) -> bool {
+  match ch {
+    | '\u{2000}'..='\u{200A}' => true,
+    | _ => {
+      matches!(
+        ch,
+        '\u{00A0}'
+          | '\u{202F}'
+          | '\u{205F}'
+          | '\u{3000}'
+      )
+    }
+  }
+}
+
+fn is_dash(ch: char) -> bool {
+  match ch {
+    | '\u{2010}'..='\u{2015}' => true,
+    | _ => {
+      matches!(
+        ch,
+        '\u{2212}'
+          | '\u{FE58}'
+          | '\u{FE63}'
+          | '\u{FF0D}'
+      )
+    }
+  }
+}
+
+/// Offsets of the right single quotes
+/// that close a left one. One between
+/// letters is an apostrophe, as is one
+/// with no quote open.
+fn closing_quotes(
+  input: &str
+) -> Vec<usize> {
+  let mut closing = Vec::new();
+  let mut open = 0usize;
+  let mut previous = None;
+  let mut chars =
+    input.char_indices().peekable();
+  while let Some((idx, ch)) =
+    chars.next()
+  {
+    match ch {
+      | '\u{2018}' | '\u{201A}'
+      | '\u{201B}' => open += 1,
+      | '\u{2019}' => {
+        let inside_word = previous
+          .is_some_and(
+            char::is_alphanumeric
+          )
+          && chars.peek().is_some_and(
+            |(_, next)| {
+              next.is_alphabetic()
+            }
+          );
+        if open > 0 && !inside_word {
+          open -= 1;
+          closing.push(idx);
+        }
+      }
+      | _ => {}
+    }
+    previous = Some(ch);
+  }
+  closing
+}
+
+fn unify_quote(
+  ch: char
+) -> Option<char> {
+  match ch {
+    | '\u{201C}' | '\u{201D}'
+    | '\u{201E}' | '\u{201F}' => {
+      Some('"')
+    }
+    | '\u{2018}' | '\u{2019}'
+    | '\u{201A}' | '\u{201B}' => {
+      Some('\'')
+    }
+    | _ => None
+  }
+}
+
+fn expand_ligature(
+  ch: char
+) -> Option<&'static str> {
+  match ch {
+    | '\u{FB00}' => Some("ff"),
+    | '\u{FB01}' => Some("fi"),
+    | '\u{FB02}' => Some("fl"),
+    | '\u{FB03}' => Some("ffi"),
+    | '\u{FB04}' => Some("ffl"),
+    | '\u{FB05}' | '\u{FB06}' => {
+      Some("st")
+    }
+    | _ => None
+  }
+}
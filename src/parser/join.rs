@@ -0,0 +1,540 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::dictionary::DictionaryLookup;
+
+/// Prefixes that keep their hyphen at a
+/// line break ("self-\nesteem").
+const COMPOUND_PREFIXES: [&str; 8] = [
+  "self", "non", "cross", "well", "ex",
+  "half", "anti", "post"
+];
+
+/// Words that leave a reference open
+/// when they end a line.
+const OPEN_ENDINGS: [&str; 8] = [
+  "and", "und", "et", "in", "of",
+  "the", "&", "by"
+];
+
+/// Re-joins references wrapped over
+/// several lines and mends words
+/// hyphenated at line ends.
+///
+/// A line continues the reference above
+/// it when it follows a line-end hyphen
+/// or is indented under a hanging first
+/// line. Otherwise a line starting with
+/// a label ("[3]", "3.") opens a new
+/// reference; one starting in lower
+/// case, after a comma or connective,
+/// or while the reference has no year
+/// yet continues it; and a line
+/// starting with an author or year
+/// after a finished entry opens a new
+/// one.
+///
+/// A line-end hyphen is dropped when
+/// the joined word is known: written
+/// solid elsewhere in the input, in the
+/// lexicon or in the parser dictionary.
+/// It stays for compounds seen with a
+/// hyphen, common compound prefixes,
+/// capitalised or numeric second parts
+/// ("Smith-\nJones", "1990-\n95");
+/// other syllable breaks are closed up.
+#[derive(Debug, Clone, Default)]
+pub struct LineJoiner {
+  lexicon: HashSet<String>
+}
+
+/// A reference re-joined from input
+/// lines, mapping its text back to the
+/// input.
+#[derive(
+  Debug, Clone, PartialEq, Eq,
+)]
+pub struct JoinedReference {
+  text:   String,
+  /// Start in `text`, input offset and
+  /// length of each joined line.
+  pieces: Vec<(usize, usize, usize)>
+}
+
+impl JoinedReference {
+  /// `text` taken whole, starting the
+  /// input.
+  pub fn new(text: &str) -> Self {
+    Self {
+      text:   text.to_string(),
+      pieces: vec![(0, 0, text.len())]
+    }
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// Input offset of a byte offset of
+  /// [`JoinedReference::text`]. The
+  /// space between joined lines maps
+  /// to the end of the line before it.
+  pub fn source_offset(
+    &self,
+    offset: usize
+  ) -> usize {
+    let idx =
+      self.pieces.partition_point(
+        |(start, ..)| *start <= offset
+      );
+    match idx.checked_sub(1) {
+      | Some(idx) => {
+        let (start, source, len) =
+          self.pieces[idx];
+        source
+          + (offset - start).min(len)
+      }
+      | None => offset
+    }
+  }
+
+  /// Input range of a range of
+  /// [`JoinedReference::text`].
+  pub fn source_range(
+    &self,
+    range: Range<usize>
+  ) -> Range<usize> {
+    let start =
+      self.source_offset(range.start);
+    if range.is_empty() {
+      return start..start;
+    }
+    let end = self
+      .source_offset(range.end - 1)
+      + 1;
+    start..end.max(start)
+  }
+
+  fn push_line(
+    &mut self,
+    line: &str,
+    offset: usize
+  ) {
+    self.pieces.push((
+      self.text.len(),
+      offset,
+      line.len()
+    ));
+    self.text.push_str(line);
+  }
+}
+
+struct Open {
+  joined:    JoinedReference,
+  indent:    usize,
+  continued: Option<usize>,
+  has_year:  bool
+}
+
+impl LineJoiner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds the words of `text` to the
+  /// lexicon consulted for hyphens.
+  pub fn with_lexicon(
+    mut self,
+    text: &str
+  ) -> Self {
+    self.lexicon.extend(lexicon(text));
+    self
+  }
+
+  /// One entry per reference.
+  pub fn join(
+    &self,
+    input: &str
+  ) -> Vec<String> {
+    self
+      .join_with_dictionary(input, None)
+  }
+
+  /// Like [`LineJoiner::join`], also
+  /// treating dictionary terms as known
+  /// words.
+  pub fn join_with_dictionary(
+    &self,
+    input: &str,
+    dictionary: Option<
+      &dyn DictionaryLookup
+    >
+  ) -> Vec<String> {
+    self
+      .join_with_offsets(
+        input, dictionary
+      )
+      .into_iter()
+      .map(|joined| joined.text)
+      .collect()
+  }
+
+  /// Like
+  /// [`LineJoiner::join_with_dictionary`],
+  /// keeping where each reference's
+  /// text came from in `input`.
+  pub fn join_with_offsets(
+    &self,
+    input: &str,
+    dictionary: Option<
+      &dyn DictionaryLookup
+    >
+  ) -> Vec<JoinedReference> {
+    let mut known = lexicon(input);
+    known.extend(
+      self.lexicon.iter().cloned()
+    );
+    let mut references = Vec::new();
+    let mut open: Option<Open> = None;
+    let mut start = 0;
+    for raw in
+      input.split_inclusive('\n')
+    {
+      let offset = start + raw.len()
+        - raw.trim_start().len();
+      start += raw.len();
+      let raw = raw
+        .trim_end_matches(['\n', '\r']);
+      let line = raw.trim();
+      if line.is_empty() {
+        references.extend(
+          open
+            .take()
+            .map(|open| open.joined)
+        );
+        continue;
+      }
+      let indent = raw.len()
+        - raw.trim_start().len();
+      match open.as_mut() {
+        | Some(current)
+          if continues(
+            current, line, indent
+          ) =>
+        {
+          append(
+            &mut current.joined,
+            line,
+            offset,
+            &known,
+            dictionary
+          );
+          current
+            .continued
+            .get_or_insert(indent);
+          current.has_year |=
+            has_year(line);
+        }
+        | _ => {
+          references.extend(
+            open
+              .take()
+              .map(|open| open.joined)
+          );
+          let mut joined =
+            JoinedReference {
+              text:   String::new(),
+              pieces: Vec::new()
+            };
+          joined
+            .push_line(line, offset);
+          open = Some(Open {
+            joined,
+            indent,
+            continued: None,
+            has_year: has_year(line)
+          });
+        }
+      }
+    }
+    references.extend(
+      open.map(|open| open.joined)
+    );
+    references
+  }
+}
+
+/// Whether `line` belongs to the open
+/// reference.
+fn continues(
+  current: &Open,
+  line: &str,
+  indent: usize
+) -> bool {
+  if ends_with_hyphen(
+    &current.joined.text
+  ) || indent > current.indent
+  {
+    return true;
+  }
+  if starts_with_label(line) {
+    return false;
+  }
+  // Back at the margin after indented
+  // lines: a new hanging entry.
+  if current.continued.is_some_and(
+    |continued| {
+      continued > current.indent
+    }
+  ) {
+    return false;
+  }
+  if line
+    .chars()
+    .next()
+    .is_some_and(char::is_lowercase)
+  {
+    return true;
+  }
+  let last = current
+    .joined
+    .text
+    .split_whitespace()
+    .last()
+    .unwrap_or_default();
+  if last
+    .ends_with([',', ';', ':', '('])
+    || OPEN_ENDINGS.iter().any(|word| {
+      last.eq_ignore_ascii_case(word)
+    })
+    || !current.has_year
+  {
+    return true;
+  }
+  !(ends_entry(last)
+    && starts_entry(line))
+}
+
+fn append(
+  joined: &mut JoinedReference,
+  line: &str,
+  offset: usize,
+  known: &HashSet<String>,
+  dictionary: Option<
+    &dyn DictionaryLookup
+  >
+) {
+  if !ends_with_hyphen(&joined.text) {
+    joined.text.push(' ');
+    joined.push_line(line, offset);
+    return;
+  }
+  let head = joined
+    .text
+    .split_whitespace()
+    .last()
+    .unwrap_or_default()
+    .trim_end_matches('-')
+    .to_string();
+  let tail = line
+    .split_whitespace()
+    .next()
+    .unwrap_or_default()
+    .trim_end_matches(|c: char| {
+      !c.is_alphanumeric()
+    });
+  if drops_hyphen(
+    &head, tail, known, dictionary
+  ) {
+    joined.text.pop();
+  }
+  joined.push_line(line, offset);
+}
+
+fn drops_hyphen(
+  head: &str,
+  tail: &str,
+  known: &HashSet<String>,
+  dictionary: Option<
+    &dyn DictionaryLookup
+  >
+) -> bool {
+  if !tail
+    .chars()
+    .next()
+    .is_some_and(char::is_lowercase)
+  {
+    return false;
+  }
+  let head_word = head
+    .rsplit(|c: char| {
+      !c.is_alphanumeric()
+    })
+    .next()
+    .unwrap_or_default()
+    .to_lowercase();
+  let joined =
+    format!("{head_word}{tail}");
+  if known.contains(&format!(
+    "{head_word}-{tail}"
+  )) {
+    return false;
+  }
+  if known.contains(&joined)
+    || dictionary.is_some_and(
+      |dictionary| {
+        !dictionary
+          .lookup(&joined)
+          .is_empty()
+      }
+    )
+  {
+    return true;
+  }
+  !COMPOUND_PREFIXES
+    .contains(&head_word.as_str())
+}
+
+/// Lowercase words and hyphenated
+/// compounds of `text`, skipping words
+/// broken at a line end.
+fn lexicon(
+  text: &str
+) -> HashSet<String> {
+  text
+    .lines()
+    .flat_map(|line| {
+      let words = line
+        .split_whitespace()
+        .collect::<Vec<_>>();
+      let last =
+        words.len().saturating_sub(1);
+      words
+        .into_iter()
+        .enumerate()
+        .filter(move |(idx, word)| {
+          *idx != last
+            || !word.ends_with('-')
+        })
+        .map(|(_, word)| word)
+    })
+    .map(|word| {
+      word
+        .trim_matches(|c: char| {
+          !c.is_alphanumeric()
+        })
+        .to_lowercase()
+    })
+    .filter(|word| {
+      word.chars().count() > 2
+        && word
+          .chars()
+          .any(char::is_alphabetic)
+    })
+    .collect()
+}
+
+fn ends_with_hyphen(
+  text: &str
+) -> bool {
+  let mut chars = text.chars().rev();
+  chars.next() == Some('-')
+    && chars.next().is_some_and(
+      char::is_alphanumeric
+    )
+}
+
+fn ends_entry(word: &str) -> bool {
+  word.ends_with([
+    '.', ')', ']', '?', '!'
+  ]) || word
+    .chars()
+    .last()
+    .is_some_and(|c| c.is_ascii_digit())
+}
+
+/// "[12]", "12.", "(12)", "12)".
+pub(crate) fn starts_with_label(
+  line: &str
+) -> bool {
+  let first = line
+    .split_whitespace()
+    .next()
+    .unwrap_or_default();
+  let digits =
+    first.trim_matches(|c: char| {
+      matches!(
+        c,
+        '[' | ']' | '(' | ')' | '.'
+      )
+    });
+  first.len() > digits.len()
+    && !digits.is_empty()
+    && digits.len() <= 3
+    && digits
+      .chars()
+      .all(|c| c.is_ascii_digit())
+    && (first.starts_with(['[', '('])
+      || first.ends_with(['.', ')']))
+}
+
+/// Starts like a reference: a surname
+/// followed by a comma or initial, or a
+/// year among the first words.
+pub(crate) fn starts_entry(
+  line: &str
+) -> bool {
+  let words = line
+    .split_whitespace()
+    .take(8)
+    .collect::<Vec<_>>();
+  let author = words
+    .first()
+    .is_some_and(|first| {
+      first
+        .chars()
+        .next()
+        .is_some_and(char::is_uppercase)
+        && (first.ends_with(',')
+          || words.get(1).is_some_and(
+            |next| is_initial(next)
+          ))
+    });
+  author
+    || words
+      .iter()
+      .any(|word| has_year(word))
+}
+
+fn is_initial(word: &str) -> bool {
+  let mut chars = word.chars();
+  chars
+    .next()
+    .is_some_and(char::is_uppercase)
+    && matches!(
+      chars.as_str(),
+      "." | ".," | ","
+    )
+}
+
+/// A four-digit year from 1500 to 2099.
+pub(crate) fn has_year(
+  text: &str
+) -> bool {
+  text
+    .split(|c: char| {
+      !c.is_ascii_digit()
+    })
+    .any(|digits| {
+      digits.len() == 4
+        && matches!(
+          &digits[..2],
+          "15"
+            | "16"
+            | "17"
+            | "18"
+            | "19"
+            | "20"
+        )
+    })
+}
@@ -1,6 +1,9 @@
 mod core;
 mod extract;
 mod field_tokens;
+mod input;
+mod join;
+mod spans;
 mod types;
 
 pub use core::{
@@ -12,9 +15,26 @@ pub use extract::{
   sequence_signature,
   tagged_sequence_signature
 };
+pub use input::{
+  InputNormalizer,
+  NormalizedInput,
+  UnicodeForm
+};
+pub use join::{
+  JoinedReference,
+  LineJoiner
+};
+pub(crate) use join::{
+  has_year,
+  starts_entry,
+  starts_with_label
+};
 pub use types::{
   Author,
   FieldValue,
+  Place,
   Reference,
+  ReferenceSpans,
+  Span,
   TaggedToken
 };
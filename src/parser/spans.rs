@@ -0,0 +1,232 @@
+//! Finds the source tokens behind
+//! parsed field values.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::parser::input::NormalizedInput;
+use crate::parser::types::{
+  FieldValue,
+  Span
+};
+
+/// Fields derived from the whole
+/// reference rather than read from its
+/// tokens.
+const DERIVED_FIELDS: [&str; 5] = [
+  "type",
+  "language",
+  "scripts",
+  "date-circa",
+  "structured-location"
+];
+
+/// Shortest word matched by prefix, so
+/// an abbreviated "Econ" finds
+/// "Economics".
+const MIN_PREFIX: usize = 3;
+
+struct Token {
+  key:   String,
+  range: Range<usize>
+}
+
+/// Spans in the original input of the
+/// tokens behind each value of
+/// `fields`. A value's words are
+/// matched to the longest run of
+/// consecutive tokens starting with
+/// its first word; words left over,
+/// such as a given name before the
+/// family name, take the nearest
+/// matching token.
+pub(super) fn field_spans(
+  fields: &BTreeMap<String, FieldValue>,
+  input: &NormalizedInput
+) -> BTreeMap<String, Vec<Vec<Span>>> {
+  let tokens = tokens(input.as_str());
+  fields
+    .iter()
+    .filter(|(key, _)| {
+      !key.starts_with("__")
+        && !DERIVED_FIELDS
+          .contains(&key.as_str())
+    })
+    .filter_map(|(key, value)| {
+      let spans = values(value)
+        .iter()
+        .map(|value| {
+          value_tokens(&tokens, value)
+            .into_iter()
+            .map(|idx| {
+              input
+                .original_range(
+                  tokens[idx]
+                    .range
+                    .clone()
+                )
+                .into()
+            })
+            .collect::<Vec<Span>>()
+        })
+        .collect::<Vec<_>>();
+      spans
+        .iter()
+        .any(|spans| !spans.is_empty())
+        .then(|| (key.clone(), spans))
+    })
+    .collect()
+}
+
+fn values(
+  value: &FieldValue
+) -> Vec<String> {
+  match value {
+    | FieldValue::Single(value) => {
+      vec![value.clone()]
+    }
+    | FieldValue::List(values) => {
+      values.clone()
+    }
+    | FieldValue::Authors(authors) => {
+      authors
+        .iter()
+        .map(|author| {
+          format!(
+            "{} {}",
+            author.family, author.given
+          )
+        })
+        .collect()
+    }
+    | FieldValue::Places(places) => {
+      places
+        .iter()
+        .map(|place| place.label())
+        .collect()
+    }
+  }
+}
+
+/// Whitespace-separated tokens with
+/// their lowercase letters and digits.
+fn tokens(text: &str) -> Vec<Token> {
+  let mut tokens = Vec::new();
+  let mut start = None;
+  for (idx, c) in text
+    .char_indices()
+    .chain(std::iter::once((
+      text.len(),
+      ' '
+    )))
+  {
+    match (c.is_whitespace(), start) {
+      | (true, Some(begin)) => {
+        tokens.push(Token {
+          key:   key(&text[begin..idx]),
+          range: begin..idx
+        });
+        start = None;
+      }
+      | (false, None) => {
+        start = Some(idx);
+      }
+      | _ => {}
+    }
+  }
+  tokens
+}
+
+fn key(word: &str) -> String {
+  word
+    .chars()
+    .filter(|c| c.is_alphanumeric())
+    .flat_map(char::to_lowercase)
+    .collect()
+}
+
+fn matches(
+  token: &str,
+  word: &str
+) -> bool {
+  token == word
+    || (word.chars().count()
+      >= MIN_PREFIX
+      && token.starts_with(word))
+    || (token.chars().count()
+      >= MIN_PREFIX
+      && word.starts_with(token))
+}
+
+/// Indices of the tokens behind
+/// `value`, in source order.
+fn value_tokens(
+  tokens: &[Token],
+  value: &str
+) -> Vec<usize> {
+  let words = value
+    .split_whitespace()
+    .map(key)
+    .filter(|word| !word.is_empty())
+    .collect::<Vec<_>>();
+  let Some(first) = words.first()
+  else {
+    return Vec::new();
+  };
+  let mut best = Vec::new();
+  for start in 0..tokens.len() {
+    if !matches(
+      &tokens[start].key,
+      first
+    ) {
+      continue;
+    }
+    let mut run = vec![start];
+    let mut next = start + 1;
+    for word in &words[1..] {
+      // Punctuation between words has
+      // no letters to match.
+      while tokens
+        .get(next)
+        .is_some_and(|token| {
+          token.key.is_empty()
+        })
+      {
+        next += 1;
+      }
+      if !tokens.get(next).is_some_and(
+        |token| {
+          matches(&token.key, word)
+        }
+      ) {
+        break;
+      }
+      run.push(next);
+      next += 1;
+    }
+    if run.len() > best.len() {
+      best = run;
+    }
+  }
+  let anchor = best.first().copied();
+  for word in
+    words.iter().skip(best.len())
+  {
+    let nearest = tokens
+      .iter()
+      .enumerate()
+      .filter(|(idx, token)| {
+        !best.contains(idx)
+          && matches(&token.key, word)
+      })
+      .min_by_key(|(idx, _)| {
+        anchor.map_or(*idx, |anchor| {
+          idx.abs_diff(anchor)
+        })
+      })
+      .map(|(idx, _)| idx);
+    best.extend(nearest);
+  }
+  best.sort_unstable();
+  best
+}
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::ops::Range;
 
 use serde::Serialize;
 
@@ -16,23 +17,162 @@ pub struct Author {
   pub given:  String
 }
 
+/// A publication place split into city,
+/// region and country.
+#[derive(
+  Debug,
+  Clone,
+  Default,
+  Serialize,
+  PartialEq,
+  Eq,
+)]
+pub struct Place {
+  #[serde(
+    skip_serializing_if = "String::is_empty"
+  )]
+  pub city:    String,
+  #[serde(
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub region:  Option<String>,
+  #[serde(
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub country: Option<String>
+}
+
+impl Place {
+  pub fn city(
+    city: impl Into<String>
+  ) -> Self {
+    Self {
+      city: city.into(),
+      ..Self::default()
+    }
+  }
+
+  /// Display form used for
+  /// `publisher-place`; the country is
+  /// implied when a region is known.
+  pub fn label(&self) -> String {
+    let mut parts = Vec::new();
+    if !self.city.is_empty() {
+      parts.push(self.city.as_str());
+    }
+    if let Some(region) = &self.region {
+      parts.push(region.as_str());
+    } else if let Some(country) =
+      &self.country
+    {
+      parts.push(country.as_str());
+    }
+    parts.join(", ")
+  }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum FieldValue {
   Single(String),
   List(Vec<String>),
-  Authors(Vec<Author>)
+  Authors(Vec<Author>),
+  Places(Vec<Place>)
+}
+
+/// Byte range of source text.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Serialize,
+  PartialEq,
+  Eq,
+)]
+pub struct Span {
+  pub start: usize,
+  pub end:   usize
+}
+
+impl From<Range<usize>> for Span {
+  fn from(range: Range<usize>) -> Self {
+    Self {
+      start: range.start,
+      end:   range.end
+    }
+  }
+}
+
+/// Where a reference and its field
+/// values came from. Offsets are bytes
+/// of the parsed input, or of the
+/// document text for found references,
+/// which also know the page index and
+/// line index they start on.
+#[derive(
+  Debug,
+  Clone,
+  Default,
+  Serialize,
+  PartialEq,
+  Eq,
+)]
+pub struct ReferenceSpans {
+  #[serde(
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub page:   Option<usize>,
+  #[serde(
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub line:   Option<usize>,
+  pub start:  usize,
+  pub end:    usize,
+  /// Source token spans of each value
+  /// of a field, in value order.
+  pub fields:
+    BTreeMap<String, Vec<Vec<Span>>>
+}
+
+impl ReferenceSpans {
+  /// Moves every range through `map`,
+  /// as when the parsed text was cut
+  /// from a larger source.
+  pub fn map_ranges(
+    &mut self,
+    map: impl Fn(
+      Range<usize>
+    ) -> Range<usize>
+  ) {
+    let range =
+      map(self.start..self.end);
+    (self.start, self.end) =
+      (range.start, range.end);
+    for span in self
+      .fields
+      .values_mut()
+      .flatten()
+      .flatten()
+    {
+      *span = map(span.start..span.end)
+        .into();
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(transparent)]
 pub struct Reference(
-  pub BTreeMap<String, FieldValue>
+  pub BTreeMap<String, FieldValue>,
+  #[serde(skip)] ReferenceSpans
 );
 
 impl Reference {
   pub fn new() -> Self {
-    Self(BTreeMap::new())
+    Self(
+      BTreeMap::new(),
+      ReferenceSpans::default()
+    )
   }
 
   pub fn insert(
@@ -52,7 +192,29 @@ impl Reference {
   pub fn from_map(
     map: BTreeMap<String, FieldValue>
   ) -> Self {
-    Self(map)
+    Self(map, ReferenceSpans::default())
+  }
+
+  /// Source spans, filled in by
+  /// [`Parser::parse`](super::Parser::parse).
+  pub fn spans(
+    &self
+  ) -> &ReferenceSpans {
+    &self.1
+  }
+
+  pub fn spans_mut(
+    &mut self
+  ) -> &mut ReferenceSpans {
+    &mut self.1
+  }
+
+  pub fn with_spans(
+    mut self,
+    spans: ReferenceSpans
+  ) -> Self {
+    self.1 = spans;
+    self
   }
 }
 
@@ -0,0 +1,480 @@
+//! Test doubles for external services,
+//! compiled with the `test-support`
+//! feature.
+
+use std::collections::HashMap;
+use std::io::{
+  self,
+  BufRead,
+  BufReader,
+  Write
+};
+use std::net::{
+  SocketAddr,
+  TcpListener,
+  TcpStream
+};
+use std::sync::atomic::{
+  AtomicBool,
+  Ordering
+};
+use std::sync::{
+  Arc,
+  Mutex,
+  MutexGuard
+};
+use std::thread;
+
+type Store =
+  Arc<Mutex<HashMap<String, String>>>;
+
+/// In-process Redis stand-in speaking
+/// RESP over a loopback port. It keeps
+/// string keys in a `HashMap` and
+/// answers GET, SET, SETNX, MGET, MSET,
+/// DEL and KEYS, plus MULTI/EXEC for
+/// pipelines; other commands get an
+/// error reply.
+///
+/// The server stops when dropped.
+#[derive(Debug)]
+pub struct RespServer {
+  addr:     SocketAddr,
+  store:    Store,
+  shutdown: Arc<AtomicBool>
+}
+
+impl RespServer {
+  /// Binds an ephemeral loopback port
+  /// and serves each client on its own
+  /// thread.
+  pub fn start() -> io::Result<Self> {
+    let listener =
+      TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let store = Store::default();
+    let shutdown =
+      Arc::new(AtomicBool::new(false));
+    let accept_store = store.clone();
+    let accept_shutdown =
+      shutdown.clone();
+    thread::spawn(move || {
+      for stream in listener.incoming()
+      {
+        if accept_shutdown
+          .load(Ordering::SeqCst)
+        {
+          break;
+        }
+        let Ok(stream) = stream else {
+          continue;
+        };
+        let store =
+          accept_store.clone();
+        thread::spawn(move || {
+          let _ = serve(stream, &store);
+        });
+      }
+    });
+    Ok(Self {
+      addr,
+      store,
+      shutdown
+    })
+  }
+
+  /// `redis://` URL for
+  /// `DictionaryConfig::with_redis_url`.
+  pub fn url(&self) -> String {
+    format!("redis://{}/", self.addr)
+  }
+
+  /// Sorted keys currently stored.
+  pub fn keys(&self) -> Vec<String> {
+    let mut keys = lock(&self.store)
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+    keys.sort();
+    keys
+  }
+
+  /// Stored value of `key`.
+  pub fn get(
+    &self,
+    key: &str
+  ) -> Option<String> {
+    lock(&self.store).get(key).cloned()
+  }
+}
+
+impl Drop for RespServer {
+  fn drop(&mut self) {
+    self
+      .shutdown
+      .store(true, Ordering::SeqCst);
+    // Wake the accept loop so it sees
+    // the flag.
+    let _ =
+      TcpStream::connect(self.addr);
+  }
+}
+
+enum Reply {
+  Ok,
+  Status(&'static str),
+  Error(String),
+  Integer(i64),
+  Bulk(Option<String>),
+  Array(Vec<Reply>)
+}
+
+impl Reply {
+  fn write_to(
+    &self,
+    out: &mut Vec<u8>
+  ) {
+    match self {
+      | Reply::Ok => {
+        out.extend_from_slice(
+          b"+OK\r\n"
+        );
+      }
+      | Reply::Status(status) => {
+        out.extend_from_slice(
+          format!("+{status}\r\n")
+            .as_bytes()
+        );
+      }
+      | Reply::Error(message) => {
+        out.extend_from_slice(
+          format!("-ERR {message}\r\n")
+            .as_bytes()
+        );
+      }
+      | Reply::Integer(value) => {
+        out.extend_from_slice(
+          format!(":{value}\r\n")
+            .as_bytes()
+        );
+      }
+      | Reply::Bulk(None) => {
+        out.extend_from_slice(
+          b"$-1\r\n"
+        );
+      }
+      | Reply::Bulk(Some(value)) => {
+        out.extend_from_slice(
+          format!(
+            "${}\r\n",
+            value.len()
+          )
+          .as_bytes()
+        );
+        out.extend_from_slice(
+          value.as_bytes()
+        );
+        out.extend_from_slice(b"\r\n");
+      }
+      | Reply::Array(items) => {
+        out.extend_from_slice(
+          format!(
+            "*{}\r\n",
+            items.len()
+          )
+          .as_bytes()
+        );
+        for item in items {
+          item.write_to(out);
+        }
+      }
+    }
+  }
+}
+
+fn serve(
+  stream: TcpStream,
+  store: &Store
+) -> io::Result<()> {
+  let mut reader =
+    BufReader::new(stream.try_clone()?);
+  let mut writer = stream;
+  let mut queued: Option<
+    Vec<Vec<String>>
+  > = None;
+  while let Some(args) =
+    read_command(&mut reader)?
+  {
+    let name = args
+      .first()
+      .map(|name| name.to_uppercase())
+      .unwrap_or_default();
+    let reply = match (
+      name.as_str(),
+      queued.as_mut()
+    ) {
+      | ("MULTI", None) => {
+        queued = Some(Vec::new());
+        Reply::Ok
+      }
+      | ("EXEC", Some(_)) => {
+        let commands = queued
+          .take()
+          .unwrap_or_default();
+        Reply::Array(
+          commands
+            .iter()
+            .map(|args| {
+              execute(args, store)
+            })
+            .collect()
+        )
+      }
+      | ("DISCARD", Some(_)) => {
+        queued = None;
+        Reply::Ok
+      }
+      | (_, Some(commands)) => {
+        commands.push(args);
+        Reply::Status("QUEUED")
+      }
+      | _ => execute(&args, store)
+    };
+    let mut out = Vec::new();
+    reply.write_to(&mut out);
+    writer.write_all(&out)?;
+  }
+  Ok(())
+}
+
+fn execute(
+  args: &[String],
+  store: &Store
+) -> Reply {
+  let Some((name, args)) =
+    args.split_first()
+  else {
+    return Reply::Error(
+      "empty command".into()
+    );
+  };
+  let mut store = lock(store);
+  match (
+    name.to_uppercase().as_str(),
+    args
+  ) {
+    | ("PING", []) => {
+      Reply::Status("PONG")
+    }
+    | ("CLIENT" | "SELECT", _) => {
+      Reply::Ok
+    }
+    | ("GET", [key]) => {
+      Reply::Bulk(
+        store.get(key).cloned()
+      )
+    }
+    | ("SET", [key, value]) => {
+      store.insert(
+        key.clone(),
+        value.clone()
+      );
+      Reply::Ok
+    }
+    | ("SETNX", [key, value]) => {
+      if store.contains_key(key) {
+        Reply::Integer(0)
+      } else {
+        store.insert(
+          key.clone(),
+          value.clone()
+        );
+        Reply::Integer(1)
+      }
+    }
+    | ("MGET", keys)
+      if !keys.is_empty() =>
+    {
+      Reply::Array(
+        keys
+          .iter()
+          .map(|key| {
+            Reply::Bulk(
+              store.get(key).cloned()
+            )
+          })
+          .collect()
+      )
+    }
+    | ("MSET", pairs)
+      if !pairs.is_empty()
+        && pairs.len() % 2 == 0 =>
+    {
+      for pair in pairs.chunks(2) {
+        store.insert(
+          pair[0].clone(),
+          pair[1].clone()
+        );
+      }
+      Reply::Ok
+    }
+    | ("DEL", keys)
+      if !keys.is_empty() =>
+    {
+      let removed = keys
+        .iter()
+        .filter(|key| {
+          store.remove(*key).is_some()
+        })
+        .count();
+      Reply::Integer(removed as i64)
+    }
+    | ("KEYS", [pattern]) => {
+      let mut keys = store
+        .keys()
+        .filter(|key| {
+          glob_match(pattern, key)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+      keys.sort();
+      Reply::Array(
+        keys
+          .into_iter()
+          .map(|key| {
+            Reply::Bulk(Some(key))
+          })
+          .collect()
+      )
+    }
+    | (name, _) => {
+      Reply::Error(format!(
+        "unsupported command '{name}'"
+      ))
+    }
+  }
+}
+
+/// Reads one RESP array of bulk
+/// strings; `None` at end of stream.
+fn read_command(
+  reader: &mut impl BufRead
+) -> io::Result<Option<Vec<String>>> {
+  let Some(header) = read_line(reader)?
+  else {
+    return Ok(None);
+  };
+  let Some(count) = header
+    .strip_prefix('*')
+    .and_then(|count| {
+      count.parse::<usize>().ok()
+    })
+  else {
+    // Inline commands are plain words.
+    return Ok(Some(
+      header
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+    ));
+  };
+  let mut args =
+    Vec::with_capacity(count);
+  for _ in 0..count {
+    let len = read_line(reader)?
+      .as_deref()
+      .and_then(|line| {
+        line.strip_prefix('$')
+      })
+      .and_then(|len| {
+        len.parse::<usize>().ok()
+      })
+      .ok_or_else(|| {
+        io::Error::new(
+          io::ErrorKind::InvalidData,
+          "expected bulk string"
+        )
+      })?;
+    let mut data = vec![0; len + 2];
+    reader.read_exact(&mut data)?;
+    data.truncate(len);
+    args.push(
+      String::from_utf8(data).map_err(
+        |err| {
+          io::Error::new(
+            io::ErrorKind::InvalidData,
+            err
+          )
+        }
+      )?
+    );
+  }
+  Ok(Some(args))
+}
+
+fn read_line(
+  reader: &mut impl BufRead
+) -> io::Result<Option<String>> {
+  let mut line = String::new();
+  if reader.read_line(&mut line)? == 0 {
+    return Ok(None);
+  }
+  Ok(Some(
+    line
+      .trim_end_matches(['\r', '\n'])
+      .to_string()
+  ))
+}
+
+/// Redis glob patterns with `*` and
+/// `?`.
+fn glob_match(
+  pattern: &str,
+  key: &str
+) -> bool {
+  let pattern =
+    pattern.chars().collect::<Vec<_>>();
+  let key =
+    key.chars().collect::<Vec<_>>();
+  let (mut p, mut k) = (0, 0);
+  let mut star = None;
+  while k < key.len() {
+    match pattern.get(p) {
+      | Some('*') => {
+        star = Some((p, k));
+        p += 1;
+      }
+      | Some('?') => {
+        p += 1;
+        k += 1;
+      }
+      | Some(c) if *c == key[k] => {
+        p += 1;
+        k += 1;
+      }
+      | _ => {
+        let Some((star_p, star_k)) =
+          star
+        else {
+          return false;
+        };
+        p = star_p + 1;
+        k = star_k + 1;
+        star =
+          Some((star_p, star_k + 1));
+      }
+    }
+  }
+  pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn lock(
+  store: &Store
+) -> MutexGuard<
+  '_,
+  HashMap<String, String>
+> {
+  store.lock().unwrap_or_else(
+    |poisoned| poisoned.into_inner()
+  )
+}
@@ -4,8 +4,29 @@ use cite_otter::dictionary::{
   Dictionary,
   DictionaryAdapter,
   DictionaryCode,
-  DictionaryConfig
+  DictionaryConfig,
+  KeyScheme,
+  TermNormalizer
 };
+use cite_otter::test_support::RespServer;
+
+/// A live server from
+/// `CITE_OTTER_REDIS_URL` or
+/// `REDIS_URL`, else the in-process
+/// stand-in.
+fn redis_url()
+-> (String, Option<RespServer>) {
+  match env::var("CITE_OTTER_REDIS_URL")
+    .or_else(|_| env::var("REDIS_URL"))
+  {
+    | Ok(url) => (url, None),
+    | Err(_) => {
+      let server = RespServer::start()
+        .expect("resp server starts");
+      (server.url(), Some(server))
+    }
+  }
+}
 
 #[test]
 fn lmdb_backend_lookup_reads_seeded_data()
@@ -60,19 +81,436 @@ fn lmdb_backend_imports_terms() {
   );
 }
 
+#[test]
+fn lmdb_backend_migrates_keys() {
+  let temp_dir = tempfile::tempdir()
+    .expect("lmdb tempdir");
+  let mut dictionary =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Lmdb
+      )
+      .with_lmdb_path(temp_dir.path())
+    )
+    .expect("lmdb dictionary opens");
+  assert_eq!(
+    dictionary.key_scheme(),
+    KeyScheme::Unicode
+  );
+
+  dictionary
+    .import_terms(
+      DictionaryCode::Place,
+      vec!["Wakanda".to_string()]
+    )
+    .expect("lmdb import");
+  let removed = dictionary
+    .migrate_keys(
+      TermNormalizer::new()
+        .with_diacritic_folding(true)
+    )
+    .expect("lmdb migration");
+  assert!(removed > 0);
+  assert!(
+    dictionary
+      .lookup("Wakanda")
+      .is_empty(),
+    "migration clears imported terms"
+  );
+  assert_eq!(
+    dictionary.lookup("Italy"),
+    vec![DictionaryCode::Place],
+    "migration reseeds place names"
+  );
+  drop(dictionary);
+
+  let reopened =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Lmdb
+      )
+      .with_lmdb_path(temp_dir.path())
+    )
+    .expect("lmdb dictionary reopens");
+  assert!(
+    reopened
+      .term_normalizer()
+      .folds_diacritics(),
+    "the stored scheme wins on reopen"
+  );
+}
+
+#[test]
+fn sqlite_backend_lookup_reads_seeded_data()
+ {
+  let temp_dir = tempfile::tempdir()
+    .expect("sqlite tempdir");
+  let dictionary =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Sqlite
+      )
+      .with_sqlite_path(
+        temp_dir
+          .path()
+          .join("places.sqlite3")
+      )
+    )
+    .expect("sqlite dictionary opens");
+
+  assert_eq!(
+    dictionary.lookup("Italy"),
+    vec![DictionaryCode::Place],
+    "sqlite adapter should resolve \
+     place names"
+  );
+}
+
+#[test]
+fn sqlite_backend_imports_terms() {
+  let temp_dir = tempfile::tempdir()
+    .expect("sqlite tempdir");
+  let path =
+    temp_dir.path().join("dict.db");
+  let config = DictionaryConfig::new(
+    DictionaryAdapter::Sqlite
+  )
+  .with_sqlite_path(&path);
+  let mut dictionary =
+    Dictionary::try_create(
+      config.clone()
+    )
+    .expect("sqlite dictionary opens");
+
+  let inserted = dictionary
+    .import_entries(vec![
+      (
+        "Wakanda".to_string(),
+        DictionaryCode::Place.bit()
+      ),
+      (
+        "Wakanda".to_string(),
+        DictionaryCode::Journal.bit()
+      ),
+    ])
+    .expect("sqlite import");
+  assert_eq!(inserted, 1);
+  let repeated = dictionary
+    .import_terms(
+      DictionaryCode::Place,
+      vec!["Wakanda".to_string()]
+    )
+    .expect("sqlite reimport");
+  assert_eq!(
+    repeated, 0,
+    "unchanged entries are not \
+     rewritten"
+  );
+  drop(dictionary);
+
+  let reopened =
+    Dictionary::try_create(config)
+      .expect(
+        "sqlite dictionary reopens"
+      );
+  assert_eq!(
+    reopened.lookup("Wakanda"),
+    vec![
+      DictionaryCode::Place,
+      DictionaryCode::Journal
+    ],
+    "sqlite adapter should persist \
+     merged codes"
+  );
+}
+
+#[test]
+fn fst_backend_reads_compiled_terms() {
+  let temp_dir = tempfile::tempdir()
+    .expect("fst tempdir");
+  let path =
+    temp_dir.path().join("places.fst");
+  Dictionary::compile(
+    &path,
+    TermNormalizer::new(),
+    vec![
+      (
+        "Kraków".to_string(),
+        DictionaryCode::Place.bit()
+      ),
+      (
+        "Krakatoa".to_string(),
+        DictionaryCode::Place.bit()
+      ),
+      (
+        "Nature".to_string(),
+        DictionaryCode::Journal.bit()
+      ),
+    ]
+  )
+  .expect("fst compiles");
+  let mut dictionary =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Fst
+      )
+      .with_fst_path(&path)
+    )
+    .expect("fst dictionary opens");
+
+  assert_eq!(
+    dictionary.lookup("Italy"),
+    vec![DictionaryCode::Place],
+    "compiled dictionaries are seeded"
+  );
+  assert_eq!(
+    dictionary.lookup("KRAKÓW"),
+    vec![DictionaryCode::Place]
+  );
+
+  let prefixed = dictionary
+    .lookup_prefix("Kra")
+    .expect("prefix query")
+    .into_iter()
+    .map(|found| found.term)
+    .collect::<Vec<_>>();
+  assert_eq!(prefixed, vec![
+    "krakatoa", "kraków"
+  ]);
+
+  let fuzzy = dictionary
+    .lookup_fuzzy("natrue", 2)
+    .expect("fuzzy query");
+  assert_eq!(fuzzy.len(), 1);
+  assert_eq!(fuzzy[0].term, "nature");
+  assert_eq!(fuzzy[0].codes, vec![
+    DictionaryCode::Journal
+  ]);
+
+  assert!(
+    dictionary
+      .import_terms(
+        DictionaryCode::Place,
+        vec!["Wakanda".to_string()]
+      )
+      .is_err(),
+    "fst dictionaries are read-only"
+  );
+}
+
+#[test]
+fn persistent_backends_keep_weights() {
+  let temp_dir = tempfile::tempdir()
+    .expect("weights tempdir");
+  let entries = vec![
+    (
+      "Jordan".to_string(),
+      DictionaryCode::Name,
+      0.75
+    ),
+    (
+      "Jordan".to_string(),
+      DictionaryCode::Place,
+      0.25
+    ),
+  ];
+  let fst_path =
+    temp_dir.path().join("places.fst");
+  Dictionary::compile_weighted(
+    &fst_path,
+    TermNormalizer::new(),
+    entries.clone()
+  )
+  .expect("fst compiles");
+  let configs = vec![
+    DictionaryConfig::new(
+      DictionaryAdapter::Lmdb
+    )
+    .with_lmdb_path(
+      temp_dir.path().join("lmdb")
+    ),
+    DictionaryConfig::new(
+      DictionaryAdapter::Sqlite
+    )
+    .with_sqlite_path(
+      temp_dir.path().join("dict.db")
+    ),
+  ];
+  for config in configs {
+    let mut dictionary =
+      Dictionary::try_create(
+        config.clone()
+      )
+      .expect("dictionary opens");
+    dictionary
+      .import_weighted(entries.clone())
+      .expect("weighted import");
+    drop(dictionary);
+    let reopened =
+      Dictionary::try_create(config)
+        .expect("dictionary reopens");
+    let weights =
+      reopened.lookup_weights("Jordan");
+    assert_eq!(
+      weights
+        .weight(DictionaryCode::Name),
+      0.75,
+      "{:?} keeps weights",
+      reopened.adapter()
+    );
+    assert_eq!(
+      weights
+        .weight(DictionaryCode::Place),
+      0.25
+    );
+  }
+
+  let compiled =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Fst
+      )
+      .with_fst_path(&fst_path)
+    )
+    .expect("fst dictionary opens");
+  let weights =
+    compiled.lookup_weights("Jordan");
+  assert_eq!(
+    weights
+      .weight(DictionaryCode::Name),
+    0.75
+  );
+  assert_eq!(
+    compiled
+      .lookup_weights("Italy")
+      .weight(DictionaryCode::Place),
+    1.0
+  );
+  let prefixed = compiled
+    .lookup_prefix("jor")
+    .expect("prefix query");
+  assert_eq!(
+    prefixed[0]
+      .weights
+      .weight(DictionaryCode::Place),
+    0.25
+  );
+}
+
+#[test]
+fn persistent_backends_keep_month_numbers()
+ {
+  let temp_dir = tempfile::tempdir()
+    .expect("months tempdir");
+  let (redis_url, _server) =
+    redis_url();
+  // "Mar" is a Portuguese noun as well
+  // as a month; its number must not
+  // turn into a weight.
+  let entries = vec![
+    (
+      "outubro".to_string(),
+      DictionaryCode::Month,
+      10.0
+    ),
+    (
+      "Mar".to_string(),
+      DictionaryCode::Name,
+      4.0
+    ),
+    (
+      "Mar".to_string(),
+      DictionaryCode::Month,
+      3.0
+    ),
+  ];
+  let fst_path =
+    temp_dir.path().join("months.fst");
+  Dictionary::compile_weighted(
+    &fst_path,
+    TermNormalizer::new(),
+    entries.clone()
+  )
+  .expect("fst compiles");
+  let configs = vec![
+    DictionaryConfig::new(
+      DictionaryAdapter::Lmdb
+    )
+    .with_lmdb_path(
+      temp_dir.path().join("lmdb")
+    ),
+    DictionaryConfig::new(
+      DictionaryAdapter::Sqlite
+    )
+    .with_sqlite_path(
+      temp_dir.path().join("dict.db")
+    ),
+    DictionaryConfig::new(
+      DictionaryAdapter::Redis
+    )
+    .with_redis_url(redis_url)
+    .with_namespace(
+      "cite-otter-months".to_string()
+    ),
+  ];
+  let mut dictionaries = Vec::new();
+  for config in configs {
+    let mut dictionary =
+      Dictionary::try_create(
+        config.clone()
+      )
+      .expect("dictionary opens");
+    dictionary
+      .import_weighted(entries.clone())
+      .expect("month import");
+    drop(dictionary);
+    dictionaries.push(
+      Dictionary::try_create(config)
+        .expect("dictionary reopens")
+    );
+  }
+  dictionaries.push(
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Fst
+      )
+      .with_fst_path(&fst_path)
+    )
+    .expect("fst dictionary opens")
+  );
+  for dictionary in &dictionaries {
+    let adapter = dictionary.adapter();
+    assert_eq!(
+      dictionary
+        .lookup_weights("Outubro")
+        .month(),
+      Some(10),
+      "{adapter:?} keeps the month"
+    );
+    let weights =
+      dictionary.lookup_weights("mar");
+    assert_eq!(
+      weights.month(),
+      Some(3),
+      "{adapter:?} keeps the month"
+    );
+    assert_eq!(
+      weights
+        .weight(DictionaryCode::Month),
+      1.0
+    );
+    assert_eq!(
+      weights.dominant(0.5),
+      vec![DictionaryCode::Name]
+    );
+  }
+}
+
 #[test]
 fn redis_backend_lookup_reads_seeded_data()
  {
-  let redis_url =
-    env::var("CITE_OTTER_REDIS_URL")
-      .or_else(|_| {
-        env::var("REDIS_URL")
-      })
-      .ok();
-  let Some(redis_url) = redis_url
-  else {
-    return;
-  };
+  let (redis_url, _server) =
+    redis_url();
 
   let config = DictionaryConfig::new(
     DictionaryAdapter::Redis
@@ -97,16 +535,8 @@ fn redis_backend_lookup_reads_seeded_data()
 
 #[test]
 fn redis_backend_imports_terms() {
-  let redis_url =
-    env::var("CITE_OTTER_REDIS_URL")
-      .or_else(|_| {
-        env::var("REDIS_URL")
-      })
-      .ok();
-  let Some(redis_url) = redis_url
-  else {
-    return;
-  };
+  let (redis_url, _server) =
+    redis_url();
 
   let mut dictionary =
     Dictionary::try_create(
@@ -137,6 +567,81 @@ fn redis_backend_imports_terms() {
   );
 }
 
+#[test]
+fn redis_stand_in_keeps_seeded_places()
+{
+  let server = RespServer::start()
+    .expect("resp server starts");
+  let mut dictionary =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Redis
+      )
+      .with_redis_url(server.url())
+      .with_namespace(
+        "stand-in".to_string()
+      )
+    )
+    .expect("redis dictionary opens");
+
+  let keys = server.keys();
+  assert!(keys.iter().all(|key| {
+    key.starts_with("stand-in:")
+  }));
+  assert!(
+    server
+      .get("stand-in:italy")
+      .is_some(),
+    "seed_places should write place \
+     names: {keys:?}"
+  );
+
+  dictionary
+    .import_terms(
+      DictionaryCode::Journal,
+      vec![
+        "Nature".to_string(),
+        "Italy".to_string(),
+      ]
+    )
+    .expect("redis import");
+  let weights =
+    dictionary.lookup_many(&[
+      "Italy", "Nature", "zzz"
+    ]);
+  assert_eq!(weights[0].codes(), vec![
+    DictionaryCode::Place,
+    DictionaryCode::Journal
+  ]);
+  assert_eq!(weights[1].codes(), vec![
+    DictionaryCode::Journal
+  ]);
+  assert!(
+    weights[2].codes().is_empty()
+  );
+
+  let entries = dictionary
+    .entries()
+    .expect("redis entries");
+  assert!(entries.iter().any(
+    |entry| { entry.term == "nature" }
+  ));
+
+  let cleared = dictionary
+    .migrate_keys(TermNormalizer::new())
+    .expect("redis migrate");
+  assert!(cleared > 0);
+  assert_eq!(
+    dictionary.lookup("Nature"),
+    Vec::new()
+  );
+  assert_eq!(
+    dictionary.lookup("Italy"),
+    vec![DictionaryCode::Place],
+    "migration reseeds place names"
+  );
+}
+
 #[cfg(feature = "gdbm")]
 #[test]
 fn gdbm_backend_lookup_reads_seeded_data()
@@ -195,3 +700,118 @@ fn gdbm_backend_imports_terms() {
      names"
   );
 }
+
+#[test]
+fn backends_list_entries_for_stats_and_diff()
+ {
+  let temp_dir = tempfile::tempdir()
+    .expect("dictionary tempdir");
+  let fst_path =
+    temp_dir.path().join("places.fst");
+  Dictionary::compile(
+    &fst_path,
+    TermNormalizer::new(),
+    vec![(
+      "Jordan".to_string(),
+      DictionaryCode::Name.bit()
+    )]
+  )
+  .expect("fst compiles");
+  let mut lmdb =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Lmdb
+      )
+      .with_lmdb_path(
+        temp_dir.path().join("lmdb")
+      )
+    )
+    .expect("lmdb dictionary opens");
+  lmdb
+    .import_entries(vec![(
+      "Jordan".to_string(),
+      DictionaryCode::Name.bit()
+        | DictionaryCode::Place.bit()
+    )])
+    .expect("lmdb import");
+  let mut sqlite =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Sqlite
+      )
+      .with_sqlite_path(
+        temp_dir.path().join("dict.db")
+      )
+    )
+    .expect("sqlite dictionary opens");
+  sqlite
+    .import_terms(
+      DictionaryCode::Journal,
+      vec!["Nature".to_string()]
+    )
+    .expect("sqlite import");
+  let fst = Dictionary::try_create(
+    DictionaryConfig::new(
+      DictionaryAdapter::Fst
+    )
+    .with_fst_path(&fst_path)
+  )
+  .expect("fst dictionary opens");
+
+  let terms =
+    |dictionary: &Dictionary| {
+      dictionary
+        .entries()
+        .expect("entries")
+        .into_iter()
+        .map(|entry| entry.term)
+        .collect::<Vec<_>>()
+    };
+  assert_eq!(terms(&lmdb), vec![
+    "italy",
+    "jordan",
+    "philippines"
+  ]);
+  assert_eq!(terms(&sqlite), vec![
+    "italy",
+    "nature",
+    "philippines"
+  ]);
+  assert_eq!(terms(&fst), vec![
+    "italy",
+    "jordan",
+    "philippines"
+  ]);
+
+  let stats =
+    lmdb.stats().expect("stats");
+  assert_eq!(stats.terms, 3);
+  assert_eq!(
+    stats.count(DictionaryCode::Place),
+    3
+  );
+  assert_eq!(stats.overlaps, vec![(
+    DictionaryCode::Name,
+    DictionaryCode::Place,
+    1
+  )]);
+
+  let diff =
+    lmdb.diff(&sqlite).expect("diff");
+  assert_eq!(
+    diff.only_left[0].term,
+    "jordan"
+  );
+  assert_eq!(
+    diff.only_right[0].term,
+    "nature"
+  );
+  assert!(diff.changed.is_empty());
+  let diff =
+    lmdb.diff(&fst).expect("diff");
+  assert_eq!(diff.changed.len(), 1);
+  assert_eq!(
+    diff.changed[0].1.codes,
+    vec![DictionaryCode::Name]
+  );
+}
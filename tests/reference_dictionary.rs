@@ -1,7 +1,11 @@
 use cite_otter::dictionary::{
   Dictionary,
   DictionaryAdapter,
-  DictionaryCode
+  DictionaryCode,
+  DictionaryConfig,
+  DictionaryLearner,
+  LayeredDictionary,
+  TermNormalizer
 };
 
 #[test]
@@ -59,3 +63,483 @@ fn dictionary_imports_merge_codes() {
      Publisher"
   );
 }
+
+#[test]
+fn dictionary_keys_keep_unicode_terms()
+{
+  let mut dict = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+
+  dict
+    .import_terms(
+      DictionaryCode::Place,
+      vec![
+        "Zürich".to_string(),
+        "Kraków".to_string(),
+        "Москва".to_string(),
+        "東京".to_string(),
+      ]
+    )
+    .expect("dictionary import");
+
+  for term in [
+    "ZÜRICH",
+    "kraków",
+    "москва",
+    "東京"
+  ] {
+    assert_eq!(
+      dict.lookup(term),
+      vec![DictionaryCode::Place],
+      "expected {term} to map to Place"
+    );
+  }
+  assert!(
+    dict.lookup("rich").is_empty(),
+    "Zürich must not split into ASCII \
+     fragments"
+  );
+  assert!(
+    dict.lookup("Krakow").is_empty(),
+    "diacritics are kept by default"
+  );
+}
+
+#[test]
+fn dictionary_folds_diacritics_on_request()
+ {
+  let mut dict =
+    Dictionary::try_create(
+      DictionaryConfig::new(
+        DictionaryAdapter::Memory
+      )
+      .with_diacritic_folding(true)
+    )
+    .expect("memory dictionary opens");
+
+  dict
+    .import_terms(
+      DictionaryCode::Place,
+      vec!["Kraków".to_string()]
+    )
+    .expect("dictionary import");
+
+  assert_eq!(
+    dict.lookup("Krakow"),
+    vec![DictionaryCode::Place]
+  );
+}
+
+#[test]
+fn term_normalizer_segments_scripts() {
+  let terms = TermNormalizer::new();
+
+  assert_eq!(
+    terms.terms("東京Tokyo"),
+    vec!["東京", "tokyo"]
+  );
+  assert_eq!(
+    terms.terms("Müller-Lüdenscheid"),
+    vec!["müller", "lüdenscheid"]
+  );
+  assert_eq!(
+    TermNormalizer::legacy()
+      .terms("Zürich"),
+    vec!["z", "rich"]
+  );
+}
+
+#[test]
+fn dictionary_exposes_entry_weights() {
+  let mut dict = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+
+  dict
+    .import_weighted(vec![
+      (
+        "Jordan".to_string(),
+        DictionaryCode::Name,
+        0.75
+      ),
+      (
+        "Jordan".to_string(),
+        DictionaryCode::Place,
+        0.25
+      ),
+      (
+        "Jordan".to_string(),
+        DictionaryCode::Name,
+        0.5
+      ),
+    ])
+    .expect("weighted import");
+
+  let weights =
+    dict.lookup_weights("Jordan");
+  assert_eq!(weights.codes(), vec![
+    DictionaryCode::Name,
+    DictionaryCode::Place
+  ]);
+  assert_eq!(
+    weights
+      .weight(DictionaryCode::Name),
+    0.75,
+    "repeated imports keep the larger \
+     weight"
+  );
+  assert_eq!(
+    weights.probability(
+      DictionaryCode::Place
+    ),
+    0.25
+  );
+  assert_eq!(
+    weights.dominant(0.5),
+    vec![DictionaryCode::Name]
+  );
+  assert_eq!(
+    dict.lookup("Jordan"),
+    vec![
+      DictionaryCode::Name,
+      DictionaryCode::Place
+    ],
+    "plain lookups still list every \
+     code"
+  );
+  assert_eq!(
+    dict
+      .lookup_weights("Italy")
+      .weight(DictionaryCode::Place),
+    1.0,
+    "unscored entries weigh 1.0"
+  );
+}
+
+#[test]
+fn dictionary_answers_keyword_codes() {
+  assert_eq!(
+    DictionaryCode::from_tag(
+      "volume-locator"
+    ),
+    Some(DictionaryCode::VolumeLocator)
+  );
+  assert_eq!(
+    DictionaryCode::Month.bit(),
+    16
+  );
+
+  let mut dict = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  assert_eq!(
+    dict.lookup("Hrsg."),
+    vec![DictionaryCode::Editor]
+  );
+  assert_eq!(
+    dict
+      .lookup_weights("octobre")
+      .month(),
+    Some(10)
+  );
+  assert!(
+    dict.lookup("outubro").is_empty()
+  );
+
+  dict
+    .import_weighted(vec![
+      (
+        "outubro".to_string(),
+        DictionaryCode::Month,
+        10.0
+      ),
+      (
+        "red.".to_string(),
+        DictionaryCode::Editor,
+        1.0
+      ),
+    ])
+    .expect("keyword import");
+
+  assert_eq!(
+    dict
+      .lookup_weights("Outubro")
+      .month(),
+    Some(10)
+  );
+  assert_eq!(dict.lookup("red"), vec![
+    DictionaryCode::Editor
+  ]);
+}
+
+#[test]
+fn dictionary_requires_month_numbers() {
+  let mut dict = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  assert!(
+    dict
+      .import_terms(
+        DictionaryCode::Month,
+        vec!["outubro".to_string()]
+      )
+      .is_err(),
+    "month terms need their number"
+  );
+  assert!(
+    dict
+      .import_weighted(vec![(
+        "outubro".to_string(),
+        DictionaryCode::Month,
+        13.0
+      )])
+      .is_err()
+  );
+  assert!(
+    dict.lookup("outubro").is_empty()
+  );
+
+  // A later import replaces the number
+  // instead of keeping the larger one.
+  for month in [12.0, 10.0] {
+    dict
+      .import_weighted(vec![(
+        "outubro".to_string(),
+        DictionaryCode::Month,
+        month
+      )])
+      .expect("month import");
+  }
+  let weights =
+    dict.lookup_weights("outubro");
+  assert_eq!(weights.month(), Some(10));
+  assert_eq!(
+    weights.probability(
+      DictionaryCode::Month
+    ),
+    1.0
+  );
+}
+
+#[test]
+fn dictionary_learner_counts_tagged_spans()
+ {
+  let dataset = "<dataset>\
+    <sequence><author>Harris, P. and \
+    J.D. Shorthouse.</author> \
+    <journal>The Canadian \
+    Entomologist</journal></sequence>\
+    <sequence><author>Shorthouse, \
+    J.D.</author> <location>Ottawa\
+    </location> <journal>Canadian \
+    Journal of Zoology</journal>\
+    </sequence></dataset>";
+  let mut learner =
+    DictionaryLearner::new(
+      TermNormalizer::new()
+    )
+    .with_stop_words(vec![
+      "Journal".to_string(),
+    ]);
+
+  assert_eq!(
+    learner
+      .add_dataset(dataset)
+      .expect("dataset parses"),
+    2
+  );
+  assert_eq!(learner.entries(), vec![
+    (
+      "canadian".to_string(),
+      DictionaryCode::Journal,
+      2.0
+    ),
+    (
+      "shorthouse".to_string(),
+      DictionaryCode::Name,
+      2.0
+    ),
+  ]);
+
+  let mut dict = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  dict
+    .import_weighted(
+      learner
+        .with_min_count(1)
+        .entries()
+    )
+    .expect("learned import");
+  assert_eq!(
+    dict.lookup("Ottawa"),
+    vec![DictionaryCode::Place]
+  );
+  assert!(
+    dict.lookup("of").is_empty(),
+    "stop words are skipped"
+  );
+}
+
+#[test]
+fn layered_dictionary_overlays_and_denies()
+ {
+  let mut overlay = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  overlay
+    .import_weighted(vec![(
+      "Jordan".to_string(),
+      DictionaryCode::Name,
+      0.9
+    )])
+    .expect("overlay import");
+  let mut base = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  base
+    .import_terms(
+      DictionaryCode::Place,
+      vec!["Jordan".to_string()]
+    )
+    .expect("base import");
+
+  let layered =
+    LayeredDictionary::new(base)
+      .with_layer(overlay);
+  assert_eq!(
+    layered.lookup("Jordan"),
+    vec![
+      DictionaryCode::Name,
+      DictionaryCode::Place
+    ]
+  );
+
+  let layered = layered
+    .with_denied("Jordan", &[
+      DictionaryCode::Place
+    ])
+    .with_denied("Italy", &[]);
+  let weights =
+    layered.lookup_weights("Jordan");
+  assert_eq!(weights.codes(), vec![
+    DictionaryCode::Name
+  ]);
+  assert_eq!(
+    weights
+      .weight(DictionaryCode::Name),
+    0.9
+  );
+  assert!(
+    layered.lookup("Italy").is_empty()
+  );
+  assert_eq!(
+    layered.lookup("Philippines"),
+    vec![DictionaryCode::Place]
+  );
+}
+
+#[test]
+fn layered_dictionary_denies_whole_terms()
+ {
+  let mut base = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  base
+    .import_terms(
+      DictionaryCode::Place,
+      vec![
+        "New York".to_string(),
+        "York".to_string(),
+      ]
+    )
+    .expect("base import");
+
+  let layered =
+    LayeredDictionary::new(base)
+      .with_denied("New York", &[
+        DictionaryCode::Place
+      ]);
+  assert!(
+    layered
+      .lookup("New York")
+      .is_empty()
+  );
+  assert!(
+    layered.lookup_many(&["new york"])
+      [0]
+      .is_empty()
+  );
+  assert_eq!(
+    layered.lookup("york"),
+    vec![DictionaryCode::Place]
+  );
+  assert_eq!(
+    layered.lookup_many(&["York"])[0]
+      .codes(),
+    vec![DictionaryCode::Place]
+  );
+}
+
+#[test]
+fn lookup_many_matches_single_lookups()
+{
+  let mut base = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  base
+    .import_terms(
+      DictionaryCode::Place,
+      vec!["Jordan".to_string()]
+    )
+    .expect("base import");
+  let mut overlay = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  overlay
+    .import_weighted(vec![(
+      "Nature".to_string(),
+      DictionaryCode::Journal,
+      0.7
+    )])
+    .expect("overlay import");
+  let terms = [
+    "Jordan", "Nature", "Jordan", "zzz"
+  ];
+
+  let many = base.lookup_many(&terms);
+  assert_eq!(many.len(), terms.len());
+  for (term, weights) in
+    terms.iter().zip(&many)
+  {
+    assert_eq!(
+      weights.codes(),
+      base.lookup(term)
+    );
+  }
+
+  let layered =
+    LayeredDictionary::new(base)
+      .with_layer(overlay)
+      .with_denied("Jordan", &[]);
+  for (term, weights) in terms
+    .iter()
+    .zip(layered.lookup_many(&terms))
+  {
+    assert_eq!(
+      weights,
+      layered.lookup_weights(term)
+    );
+  }
+}
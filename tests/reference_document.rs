@@ -1,6 +1,9 @@
 mod support;
 
-use cite_otter::document::Document;
+use cite_otter::document::{
+  Document,
+  InputFormat
+};
 use support::fixture_path;
 
 #[test]
@@ -8,7 +11,8 @@ fn document_counts_pages_from_fixture()
 {
   let doc = Document::open(
     fixture_path("phd.txt")
-  );
+  )
+  .expect("fixture should open");
   assert_eq!(
     doc.pages().len(),
     84,
@@ -16,3 +20,465 @@ fn document_counts_pages_from_fixture()
      for the published PhD fixture"
   );
 }
+
+#[test]
+fn document_reads_pdf_pages_in_column_order()
+ {
+  let doc = Document::open(
+    fixture_path("two-column.pdf")
+  )
+  .expect("fixture should open");
+  let pages = doc.pages();
+  assert_eq!(pages.len(), 2);
+  assert_eq!(
+    pages[0].text(),
+    "A Two Column Article\n\nColumn \
+     text starts on the left\nand \
+     keeps going down the \
+     page\nbefore the right column \
+     begins.\nThe right column \
+     continues\nthe article with more \
+     text\nabout reference \
+     extraction.\n\n1"
+  );
+  assert!(pages[1].text().contains(
+    "Smith, J. (2020). A study of \
+     things.\nJournal of Studies"
+  ));
+  assert!(
+    doc.text().contains('\u{000C}')
+  );
+}
+
+#[test]
+fn pdf_pages_carry_line_layout() {
+  let doc = Document::open(
+    fixture_path("two-column.pdf")
+  )
+  .expect("fixture should open");
+  let pages = doc.pages();
+  let lines = pages[0].lines();
+  let title = &lines[0];
+  assert_eq!(
+    title.text(),
+    "A Two Column Article"
+  );
+  assert_eq!(title.bold(), Some(true));
+  assert_eq!(
+    title.font_size(),
+    Some(16.0)
+  );
+  assert_eq!(title.index(), 0);
+
+  let body = &lines[1];
+  assert_eq!(body.index(), 2);
+  assert_eq!(body.bold(), Some(false));
+  assert_eq!(body.x(), 72.0);
+  assert_eq!(body.y(), 92.0);
+  assert_eq!(body.indent(), 0.0);
+
+  let right = lines
+    .iter()
+    .find(|line| {
+      line.text()
+        == "The right column continues"
+    })
+    .expect("right column line");
+  assert_eq!(right.x(), 320.0);
+  assert_eq!(right.indent(), 0.0);
+  assert_eq!(
+    pages[0].body_font_size(),
+    Some(10.0)
+  );
+}
+
+#[test]
+fn text_pages_number_their_lines() {
+  let doc = Document::from_text(
+    "Smith, J. 2020.\n  A \
+     study.\n\nDoe, A. 2019."
+  );
+  let pages = doc.pages();
+  let lines = pages[0].lines();
+  assert_eq!(lines.len(), 3);
+  assert_eq!(
+    lines[1].text(),
+    "A study."
+  );
+  assert_eq!(lines[1].indent(), 2.0);
+  assert!(lines[1].is_indented());
+  assert_eq!(lines[2].index(), 3);
+  assert_eq!(lines[2].y(), 3.0);
+  assert_eq!(
+    lines[2].font_size(),
+    None
+  );
+  assert_eq!(lines[2].bold(), None);
+}
+
+#[test]
+fn document_clean_strips_page_furniture()
+ {
+  let source = [
+    "Journal of Studies, Vol \
+     3\n\nBody text one.\n\nSmith, J. \
+     (2020). A study\n\n1",
+    "Journal of Studies, Vol 3\n\nof \
+     things. Nature, 12.\n\n- 2 -",
+    "Journal of Studies, Vol \
+     3\n\nDoe, A. (2019).\n\nPage 3 \
+     of 3"
+  ]
+  .join("\u{000C}");
+  let source = source.as_str();
+  let clean =
+    Document::from_text(source).clean();
+  assert_eq!(
+    clean.text(),
+    "Body text one.\n\nSmith, J. \
+     (2020). A study\nof things. \
+     Nature, 12.\nDoe, A. (2019)."
+  );
+  assert_eq!(clean.removed().len(), 6);
+
+  let offset = clean
+    .text()
+    .find("of things")
+    .expect("joined page text");
+  assert_eq!(
+    clean.source_offset(offset),
+    source.find("of things").unwrap()
+  );
+  let nature = clean
+    .text()
+    .find("Nature, 12.")
+    .expect("kept line");
+  let range = clean
+    .source_range(nature..nature + 11);
+  assert_eq!(
+    &source[range.clone()],
+    "Nature, 12."
+  );
+  let (page, line) =
+    Document::from_text(source)
+      .line_at(range.start)
+      .map(|(page, line)| {
+        (page, line.index())
+      })
+      .expect("line of the offset");
+  assert_eq!((page, line), (1, 2));
+
+  let single = Document::from_text(
+    "Header\n\nSmith 2020\n\n1"
+  )
+  .clean();
+  assert_eq!(
+    single.text(),
+    "Header\n\nSmith 2020\n\n1"
+  );
+}
+
+#[test]
+fn document_clean_keeps_words_made_of_roman_letters()
+ {
+  let source = [
+    "Body one.\n\nxiv",
+    "Body two.\n\ncivic",
+    "ill\n\nBody three."
+  ]
+  .join("\u{000C}");
+  let clean =
+    Document::from_text(&source)
+      .clean();
+  let text = clean.text();
+
+  assert!(
+    !text.contains("xiv"),
+    "{text}"
+  );
+  assert!(
+    text.contains("civic"),
+    "{text}"
+  );
+  assert!(
+    text.contains("ill"),
+    "{text}"
+  );
+  assert_eq!(clean.removed().len(), 1);
+}
+
+#[test]
+fn document_detects_utf16_with_zero_low_bytes()
+ {
+  // "一" is U+4E00 and "Ā" U+0100, with
+  // a zero low byte each.
+  let text = "Wang, L. (2019). \
+              一个研究. Beijing: \
+              Ā科学, 12–34.";
+  for little in [true, false] {
+    let data = text
+      .encode_utf16()
+      .flat_map(|unit| {
+        if little {
+          unit.to_le_bytes()
+        } else {
+          unit.to_be_bytes()
+        }
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      Document::decode(&data)
+        .expect("UTF-16 should decode"),
+      text,
+      "little endian: {little}"
+    );
+  }
+}
+
+#[test]
+fn document_open_transcodes_legacy_encodings()
+ {
+  let dir = tempfile::tempdir()
+    .expect("temp dir should exist");
+  let text =
+    "Müller, J. (2020). Über Dinge.";
+  let utf16 = |little: bool| {
+    text
+      .encode_utf16()
+      .flat_map(|unit| {
+        if little {
+          unit.to_le_bytes()
+        } else {
+          unit.to_be_bytes()
+        }
+      })
+      .collect::<Vec<_>>()
+  };
+  let latin1 = text
+    .chars()
+    .map(|c| c as u8)
+    .collect::<Vec<_>>();
+  let inputs = [
+    (
+      "utf8-bom.txt",
+      [
+        &[0xef, 0xbb, 0xbf][..],
+        text.as_bytes()
+      ]
+      .concat()
+    ),
+    (
+      "utf16le-bom.txt",
+      [&[0xff, 0xfe][..], &utf16(true)]
+        .concat()
+    ),
+    ("utf16be.txt", utf16(false)),
+    ("latin1.txt", latin1)
+  ];
+  for (name, data) in inputs {
+    let path = dir.path().join(name);
+    std::fs::write(&path, data).expect(
+      "input should be written"
+    );
+    let document =
+      Document::open(&path)
+        .expect("input should decode");
+    assert_eq!(
+      document.text(),
+      text,
+      "{name}"
+    );
+  }
+
+  // 0x93 and 0x94 are curly quotes in
+  // Windows-1252; 0x81 is undefined
+  // there, so that text is Latin-1.
+  assert_eq!(
+    Document::decode(
+      b"\x93Things\x94 \xe9t\xe9"
+    )
+    .expect("Windows-1252 text"),
+    "\u{201C}Things\u{201D} été"
+  );
+  assert_eq!(
+    Document::decode(b"\x81 \xe9")
+      .expect("Latin-1 text"),
+    "\u{81} é"
+  );
+
+  assert!(
+    Document::open(
+      dir.path().join("missing.txt")
+    )
+    .is_err()
+  );
+  assert!(
+    Document::decode(b"\x00\x01\x02")
+      .is_err()
+  );
+}
+
+#[test]
+fn document_reads_html_blocks_and_lists()
+ {
+  let html = "<!DOCTYPE html>\
+    <html><head><title>Page</title>\
+    <style>p { margin: 0 }</style>\
+    </head><body>\
+    <nav><a href=\"/\">Home</a></nav>\
+    <h1>Markets</h1>\
+    <p>Prior work (Smith &amp; Doe,\
+    \n  2020) disagrees.<br>A second \
+    &lt;line&gt;.</p>\
+    <!-- <p>hidden</p> -->\
+    <h2>References</h2><ol>\
+    <li>Smith, J. (2020). <i>Markets\
+    </i>, 3&ndash;20.</li>\
+    <li>Roe, R. (2019). Books.</li>\
+    </ol><script>var p = \"<p>\";\
+    </script></body></html>";
+
+  assert_eq!(
+    Document::from_html(html).text(),
+    "Markets\n\nPrior work (Smith & \
+     Doe, 2020) disagrees.\nA second \
+     <line>.\n\nReferences\n\nSmith, \
+     J. (2020). Markets, \
+     3–20.\n\nRoe, R. (2019). Books."
+  );
+}
+
+#[test]
+fn document_keeps_text_after_self_closing_skipped_elements()
+ {
+  let html =
+    "<html><body><p>Intro<svg \
+     width=\"4\"/> text.</p><script \
+     src=\"app.js\"/><iframe \
+     src=\"a.html\" \
+     /><h2>References</h2><p>Roe, R. \
+     (2019). Books.</p><script>var p \
+     = 1;</script></body></html>";
+
+  assert_eq!(
+    Document::from_html(html).text(),
+    "Intro text.\n\nReferences\n\n\
+     Roe, R. (2019). Books."
+  );
+}
+
+#[test]
+fn document_reads_jats_reference_lists()
+{
+  let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE article PUBLIC "-//NLM//DTD JATS (Z39.96) Journal Publishing DTD v1.2 20190208//EN" "JATS-journalpublishing1.dtd">
+<article>
+  <front><article-meta>
+    <title-group><article-title>Markets</article-title></title-group>
+    <abstract><p>We study
+      markets.</p></abstract>
+  </article-meta></front>
+  <body><sec><title>Introduction</title>
+    <p>Prior work <xref rid="r1">[1]</xref> disagrees.</p>
+  </sec></body>
+  <back><ref-list><title>References</title>
+    <ref id="r1"><label>1</label><mixed-citation><person-group><name><surname>Smith</surname>, <given-names>J.</given-names></name></person-group> (<year>2020</year>). <article-title>Markets and memory</article-title>. <source>Journal of History</source>, <volume>12</volume>, <fpage>3</fpage>–<lpage>20</lpage>.</mixed-citation></ref>
+    <ref id="r2"><label>2</label><element-citation publication-type="book"><person-group><name><surname>Roe</surname><given-names>R.</given-names></name></person-group><year>2019</year><source>Another book</source><publisher-loc>Berlin</publisher-loc><publisher-name>Springer</publisher-name></element-citation></ref>
+  </ref-list></back>
+</article>"#;
+  let document =
+    Document::from_jats(xml)
+      .expect("JATS should parse");
+  let text = document.text();
+
+  let body = [
+    "Markets",
+    "We study markets.",
+    "Introduction",
+    "Prior work [1] disagrees.",
+    "References",
+    ""
+  ]
+  .join("\n\n");
+  assert!(text.starts_with(&body));
+  let references = document
+    .references()
+    .iter()
+    .map(|range| &text[range.clone()])
+    .collect::<Vec<_>>();
+  assert_eq!(references, vec![
+    "Smith, J. (2020). Markets and \
+     memory. Journal of History, 12, \
+     3–20.",
+    "Roe, R. (2019). Another book. \
+     Berlin: Springer."
+  ]);
+
+  assert!(
+    Document::from_jats("<article>")
+      .is_err()
+  );
+  assert_eq!(
+    InputFormat::from_path(
+      "article.nxml"
+    ),
+    Some(InputFormat::Jats)
+  );
+  assert_eq!(
+    InputFormat::from_path("page.HTM"),
+    Some(InputFormat::Html)
+  );
+  assert_eq!(
+    InputFormat::from_path("notes"),
+    None
+  );
+}
+
+#[test]
+fn document_keeps_tagged_jats_citation_fields()
+ {
+  let xml = r#"<article><back><ref-list>
+    <ref id="r1"><element-citation publication-type="journal"><person-group person-group-type="author"><name><surname>Smith</surname><given-names>J.</given-names></name><name><surname>Doe</surname><given-names>A.</given-names></name></person-group><year>2020a</year><article-title>Markets and memory</article-title><source>Journal of History</source><volume>12</volume><issue>3</issue><fpage>45</fpage><lpage>67</lpage><pub-id pub-id-type="doi">10.1000/xyz</pub-id><pub-id pub-id-type="pmid">123</pub-id></element-citation></ref>
+    <ref id="r2"><mixed-citation publication-type="book"><person-group person-group-type="editor"><name><surname>Roe</surname><given-names>R.</given-names></name></person-group> (Ed.). (<year>2019</year>). <source>Another book</source>. <publisher-loc>Berlin</publisher-loc>: <publisher-name>Springer</publisher-name>.</mixed-citation></ref>
+    <ref id="r3"><mixed-citation>Poe, P. (2018). Plain text. Paris: Seuil.</mixed-citation></ref>
+  </ref-list></back></article>"#;
+  let document =
+    Document::from_jats(xml)
+      .expect("JATS should parse");
+  let tagged = document
+    .tagged_references()
+    .iter()
+    .map(|reference| {
+      serde_json::to_value(reference)
+        .expect("serializable")
+    })
+    .collect::<Vec<_>>();
+
+  assert_eq!(tagged, vec![
+    serde_json::json!({
+      "author": [
+        {"family": "Smith", "given": "J."},
+        {"family": "Doe", "given": "A."}
+      ],
+      "container-title": ["Journal of History"],
+      "date": ["2020"],
+      "doi": ["doi:10.1000/xyz"],
+      "identifier": ["pmid:123"],
+      "issue": ["3"],
+      "journal": ["Journal of History"],
+      "pages": ["45-67"],
+      "title": ["Markets and memory"],
+      "type": "article-journal",
+      "volume": ["12"]
+    }),
+    serde_json::json!({
+      "date": ["2019"],
+      "editor": ["Roe, R."],
+      "location": ["Berlin"],
+      "publisher": ["Springer"],
+      "title": ["Another book"],
+      "type": "book"
+    }),
+    serde_json::Value::Null
+  ]);
+}
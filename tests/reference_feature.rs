@@ -21,3 +21,55 @@ fn number_feature_detects_years() {
     );
   }
 }
+
+#[test]
+fn layout_feature_reads_line_cues() {
+  use cite_otter::document::{
+    Line,
+    Page
+  };
+  use cite_otter::feature::layout;
+
+  let page = Page::from_lines(vec![
+    Line::new(0, "References")
+      .with_font_size(12.0)
+      .with_bold(true),
+    Line::new(2, "Smith, J. (2020).")
+      .with_font_size(10.0),
+    Line::new(3, "A study of things.")
+      .with_indent(12.0)
+      .with_font_size(10.0),
+    Line::new(4, "Doe, A. (2019).")
+      .with_font_size(10.0),
+  ]);
+  assert_eq!(
+    page.text(),
+    "References\n\nSmith, J. \
+     (2020).\nA study of \
+     things.\nDoe, A. (2019)."
+  );
+
+  let feature = layout::Feature::new();
+  assert_eq!(
+    feature.observe(&page, 0),
+    vec![
+      layout::Observation::LargerFont,
+      layout::Observation::Bold
+    ]
+  );
+  assert_eq!(
+    feature.observe(&page, 1),
+    vec![
+    layout::Observation::HangingIndent
+  ]
+  );
+  assert_eq!(
+    feature.observe(&page, 2),
+    vec![layout::Observation::Indented]
+  );
+  assert!(
+    feature
+      .observe(&page, 3)
+      .is_empty()
+  );
+}
@@ -1,8 +1,18 @@
 mod support;
 
 use std::fs;
+use std::process::Command;
 
-use cite_otter::finder::Finder;
+use cite_otter::document::Document;
+use cite_otter::finder::{
+  CitationKey,
+  CitationKind,
+  Finder,
+  ReferenceSection,
+  SectionPosition
+};
+use cite_otter::format::ParseFormat;
+use cite_otter::parser::Parser;
 use support::fixture_path;
 
 #[test]
@@ -22,3 +32,356 @@ fn finder_detects_references_in_a_document()
      one reference sequence"
   );
 }
+
+#[test]
+fn finder_restricts_segments_to_the_bibliography()
+ {
+  let path = fixture_path("phd.txt");
+  let document = Document::open(path)
+    .expect("fixture should open");
+  let sections =
+    Finder::sections(&document);
+
+  // The table of contents entry
+  // "Bibliography 68" is not a heading.
+  assert_eq!(sections.len(), 1);
+  let section = &sections[0];
+  assert_eq!(
+    section.heading.as_deref(),
+    Some("Bibliography")
+  );
+  assert_eq!(
+    (
+      section.start.page,
+      section.end.page
+    ),
+    (78, 82)
+  );
+
+  let clean = Finder::section_text(
+    &document, &sections
+  );
+  let segments =
+    Finder::segments(clean.text());
+  assert_eq!(segments.len(), 43);
+  assert!(
+    segments[0]
+      .starts_with("Ackerberg")
+  );
+  assert!(
+    !clean
+      .text()
+      .contains("Bibliography")
+  );
+}
+
+#[test]
+fn finder_ends_sections_at_the_next_heading()
+ {
+  let text = [
+    "1 Einleitung",
+    "",
+    "Seit 1998 wächst der Markt, wie \
+     frühere Studien zeigen.",
+    "",
+    "7 Literatur",
+    "",
+    "Müller, H. (2001): Märkte. \
+     Berlin: Springer.",
+    "",
+    "Schmidt, A. (1999): Preise und \
+     Mengen. München: Beck.",
+    "",
+    "Anhang",
+    "",
+    "Tabelle 1 zeigt die Daten von \
+     2004."
+  ]
+  .join("\n");
+  let document =
+    Document::from_text(&text);
+  let sections =
+    Finder::sections(&document);
+
+  assert_eq!(sections, vec![
+    ReferenceSection {
+      heading: Some(
+        "7 Literatur".into()
+      ),
+      start:   SectionPosition {
+        page: 0,
+        line: 4
+      },
+      end:     SectionPosition {
+        page: 0,
+        line: 8
+      }
+    }
+  ]);
+}
+
+#[test]
+fn finder_falls_back_to_dense_reference_runs()
+ {
+  let text = [
+    "A short note on pricing.",
+    "",
+    "[1] Smith, J. (2001). Prices. \
+     Journal of Markets, 3, 1-20.",
+    "[2] Doe, A. (2003). Quantities. \
+     Journal of Markets, 5, 21-40.",
+    "[3] Roe, B. (2005). Margins. \
+     Economic Review, 7, 41-60.",
+    "[4] Poe, C. (2007). Costs. \
+     Economic Review, 9, 61-80.",
+    "[5] Lee, D. (2009). Entry. \
+     Economic Review, 11, 81-99.",
+    "[6] Kim, E. (2011). Exit. \
+     Economic Review, 13, 1-19."
+  ]
+  .join("\n");
+  let document =
+    Document::from_text(&text);
+  let sections =
+    Finder::sections(&document);
+
+  assert_eq!(sections.len(), 1);
+  assert_eq!(sections[0].heading, None);
+  assert_eq!(
+    (
+      sections[0].start.line,
+      sections[0].end.line
+    ),
+    (2, 7)
+  );
+}
+
+#[test]
+fn finder_finds_pdf_reference_headings()
+{
+  let document = Document::open(
+    fixture_path("two-column.pdf")
+  )
+  .expect("fixture should open");
+  let sections =
+    Finder::sections(&document);
+
+  assert_eq!(sections.len(), 1);
+  assert_eq!(
+    sections[0].heading.as_deref(),
+    Some("References")
+  );
+  assert_eq!(sections[0].start.page, 1);
+}
+
+#[test]
+fn finder_resolves_footnote_citations()
+{
+  let text = [
+    "The history of things is \
+     debated.1 Later work disagreed.2",
+    "",
+    "1. John Smith, A History of \
+     Things (London: Penguin,",
+    "   1990), 45; ibid., 47.",
+    "2. See Jane Doe, \"Markets and \
+     Memory,\" Journal of Modern",
+    "   History 12 (1995): 3-20; \
+     Smith, History, 52.",
+    "3. Doe, op. cit., 7; Ibid."
+  ]
+  .join("\n");
+  let citations =
+    Finder::footnotes(&text);
+
+  let summary = citations
+    .iter()
+    .map(|citation| {
+      (
+        citation.note.as_str(),
+        citation.kind,
+        citation.locator.as_deref()
+      )
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(summary, vec![
+    (
+      "1",
+      CitationKind::Full,
+      Some("45")
+    ),
+    (
+      "1",
+      CitationKind::Ibid,
+      Some("47")
+    ),
+    ("2", CitationKind::Full, None),
+    (
+      "2",
+      CitationKind::Short,
+      Some("52")
+    ),
+    (
+      "3",
+      CitationKind::OpCit,
+      Some("7")
+    ),
+    (
+      "3",
+      CitationKind::Ibid,
+      Some("7")
+    )
+  ]);
+
+  let smith = &citations[0];
+  assert_eq!(
+    smith.source_text(&text),
+    "John Smith, A History of Things \
+     (London: Penguin,    1990)"
+  );
+  assert_eq!(
+    &text[smith.span.clone()],
+    "John Smith, A History of Things \
+     (London: Penguin,\n   1990), 45"
+  );
+  assert_eq!(
+    citations[1].source,
+    smith.source
+  );
+  assert_eq!(
+    citations[3].source,
+    smith.source
+  );
+  assert_eq!(
+    citations[2].text,
+    "Jane Doe, \"Markets and \
+     Memory,\" Journal of Modern    \
+     History 12 (1995): 3-20"
+  );
+  assert_eq!(
+    citations[4].source,
+    citations[2].source
+  );
+  assert_eq!(
+    citations[5].source,
+    citations[2].source
+  );
+}
+
+#[test]
+fn finder_links_in_text_citations() {
+  let text = [
+    "Prior work disagrees (Smith & \
+     Doe, 2020b; see Roe et al.,",
+    "2019). Smith and Doe (2020a) \
+     argue otherwise. Numbered",
+    "studies [1] and [2–3] agree. A \
+     figure (see Figure 2) does not."
+  ]
+  .join("\n");
+  let mut citations =
+    Finder::citations(&text);
+
+  let cited = citations
+    .iter()
+    .map(|citation| {
+      citation.text.as_str()
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(cited, vec![
+    "(Smith & Doe, 2020b; see Roe et \
+     al., 2019)",
+    "Smith and Doe (2020a)",
+    "[1]",
+    "[2–3]"
+  ]);
+  assert_eq!(
+    citations[0].keys[1],
+    CitationKey::AuthorYear {
+      authors: vec!["Roe".into()],
+      et_al:   true,
+      year:    "2019".into()
+    }
+  );
+  assert_eq!(citations[3].keys, vec![
+    CitationKey::Number(2),
+    CitationKey::Number(3)
+  ]);
+  assert_eq!(
+    &text[citations[1].span.clone()],
+    "Smith and Doe (2020a)"
+  );
+  assert_eq!(
+    citations[1].context,
+    "Smith and Doe (2020a) argue \
+     otherwise."
+  );
+
+  let references = Parser::new().parse(
+    &[
+      "Smith, J., & Doe, A. (2020). \
+       Markets and memory. Journal of \
+       History, 12, 3-20.",
+      "Smith, J., & Doe, A. (2020). \
+       Memory and markets. Journal of \
+       History, 13, 5-9.",
+      "Roe, R., Poe, P., & Moe, M. \
+       (2019). Another book. Berlin: \
+       Springer."
+    ],
+    ParseFormat::Json
+  );
+  Finder::link_citations(
+    &mut citations,
+    &references
+  );
+
+  let resolved = citations
+    .iter()
+    .map(|citation| {
+      citation.resolved.clone()
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(resolved, vec![
+    vec![vec![1], vec![2]],
+    vec![vec![0]],
+    vec![vec![0]],
+    vec![vec![1], vec![2]]
+  ]);
+}
+
+#[test]
+fn find_link_citations_prints_one_json_document()
+ {
+  let output = Command::new(env!(
+    "CARGO_BIN_EXE_cite-otter"
+  ))
+  .arg("find")
+  .arg("--link-citations")
+  .arg(fixture_path("phd.txt"))
+  .output()
+  .expect("cite-otter should run");
+  assert!(output.status.success());
+
+  // The sequence count precedes the
+  // JSON document.
+  let stdout =
+    String::from_utf8(output.stdout)
+      .expect("stdout is UTF-8");
+  let (count, json) = stdout
+    .split_once('\n')
+    .expect("count and JSON lines");
+  assert!(count.starts_with("found "));
+  let value: serde_json::Value =
+    serde_json::from_str(json).expect(
+      "stdout is one JSON value"
+    );
+  let references = value["references"]
+    .as_array()
+    .expect("references array");
+  let citations = value["citations"]
+    .as_array()
+    .expect("citations array");
+  assert!(!references.is_empty());
+  assert!(!citations.is_empty());
+}
@@ -6,6 +6,10 @@ use cite_otter::format::{
 };
 use cite_otter::normalizer::{
   abbreviations::AbbreviationMap,
+  title::{
+    ProtectedWords,
+    TitleCase
+  },
   NormalizationConfig
 };
 use cite_otter::parser::{
@@ -552,6 +556,46 @@ fn csl_includes_scripts_array() {
   );
 }
 
+#[test]
+fn bibtex_braces_protected_title_words()
+{
+  let config =
+    NormalizationConfig::default()
+      .with_title_case(
+        TitleCase::Sentence
+      )
+      .with_protected_words(
+        ProtectedWords::load_from_str(
+          "Antitrust"
+        )
+      );
+  let formatter =
+    Format::with_normalization(config);
+
+  let mut reference = Reference::new();
+  reference.insert(
+    "title",
+    FieldValue::List(vec![
+      "ESSAYS ON MERGERS AND ANTITRUST"
+        .into(),
+    ])
+  );
+  reference.insert(
+    "type",
+    FieldValue::Single("book".into())
+  );
+  let bibtex =
+    formatter.to_bibtex(&[reference]);
+  assert!(
+    bibtex.contains(
+      "title = {Essays on mergers and \
+       {Antitrust}}"
+    ),
+    "BibTeX should recase titles and \
+     brace protected words: {bibtex}"
+  );
+}
+
 #[test]
 fn formatter_expands_journal_abbrev() {
   let contents = fs::read_to_string(
@@ -761,3 +805,45 @@ fn format_core_outputs_match_snapshots()
     &expected_bibtex
   );
 }
+
+#[test]
+fn json_with_spans_adds_source_offsets()
+{
+  let parser = Parser::new();
+  let references = parser.parse(
+    &["Doe, A. (2019). Things. \
+       Berlin: Springer."],
+    ParseFormat::Json
+  );
+  let format = Format::new();
+  let plain: serde_json::Value =
+    serde_json::from_str(
+      &format.to_json(&references)
+    )
+    .expect("json output");
+  assert!(
+    plain[0].get("spans").is_none()
+  );
+
+  let spanned: serde_json::Value =
+    serde_json::from_str(
+      &format.to_json_with_spans(
+        &references
+      )
+    )
+    .expect("json output");
+  let spans = &spanned[0]["spans"];
+  assert_eq!(spans["start"], 0);
+  assert_eq!(spans["end"], 41);
+  assert_eq!(
+    spans["fields"]["date"][0][0],
+    serde_json::json!({
+      "start": 8,
+      "end": 15
+    })
+  );
+  assert_eq!(
+    spanned[0]["date"],
+    plain[0]["date"]
+  );
+}
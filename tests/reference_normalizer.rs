@@ -1,11 +1,31 @@
 use std::fs;
 
+use cite_otter::dictionary::{
+  Dictionary,
+  DictionaryAdapter,
+  DictionaryCode
+};
 use cite_otter::normalizer::abbreviations::AbbreviationMap;
 use cite_otter::normalizer::container::Normalizer as ContainerNormalizer;
 use cite_otter::normalizer::journal::Normalizer as JournalNormalizer;
 use cite_otter::normalizer::location::Normalizer as LocationNormalizer;
 use cite_otter::normalizer::names::Normalizer;
+use cite_otter::normalizer::publisher::{
+  Normalizer as PublisherNormalizer,
+  PublisherTable
+};
+use cite_otter::normalizer::title::{
+  Normalizer as TitleNormalizer,
+  ProtectedWords,
+  TitleCase
+};
+use cite_otter::normalizer::volume::{
+  Marker,
+  Normalizer as VolumeNormalizer,
+  keyword
+};
 use cite_otter::normalizer::NormalizationConfig;
+use cite_otter::parser::Place;
 use serde_json::{
   Map,
   Value
@@ -61,6 +81,165 @@ fn location_normalizer_splits_location_and_publisher()
   );
 }
 
+#[test]
+fn location_normalizer_resolves_regions_and_lists()
+ {
+  let normalizer =
+    LocationNormalizer::new();
+
+  let parsed = normalizer
+    .parse("Cambridge, MA", None);
+  assert_eq!(parsed.places, vec![
+    Place {
+      city:    "Cambridge".into(),
+      region:  Some("MA".into()),
+      country: Some(
+        "United States".into()
+      )
+    }
+  ]);
+
+  let parsed = normalizer
+    .parse("Toronto, Ont.", None);
+  assert_eq!(
+    parsed.places[0].region.as_deref(),
+    Some("ON")
+  );
+  assert_eq!(
+    parsed.places[0].label(),
+    "Toronto, ON"
+  );
+
+  let parsed = normalizer
+    .parse("Oxford & New York", None);
+  let cities = parsed
+    .places
+    .iter()
+    .map(|place| place.city.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(cities, vec![
+    "Oxford", "New York"
+  ]);
+
+  let parsed = normalizer.parse(
+    "St. John's, Newfoundland and \
+     Labrador",
+    None
+  );
+  assert_eq!(parsed.places, vec![
+    Place {
+      city:    "St. John's".into(),
+      region:  Some("NL".into()),
+      country: Some("Canada".into())
+    }
+  ]);
+
+  let parsed = normalizer
+    .parse("London and New York", None);
+  assert_eq!(parsed.places, vec![
+    Place::city("London"),
+    Place::city("New York")
+  ]);
+
+  let parsed = normalizer
+    .parse("London: Routledge", None);
+  assert_eq!(parsed.places, vec![
+    Place::city("London")
+  ]);
+  assert_eq!(
+    parsed.publisher.as_deref(),
+    Some("Routledge")
+  );
+}
+
+#[test]
+fn location_normalizer_consults_place_dictionary()
+ {
+  let mut dictionary =
+    Dictionary::create(
+      DictionaryAdapter::Memory
+    );
+  dictionary
+    .import_terms(
+      DictionaryCode::Place,
+      [
+        "London",
+        "Oxford",
+        "Berkeley",
+        "Los Angeles",
+        "New York"
+      ]
+      .map(str::to_string)
+      .to_vec()
+    )
+    .expect("import places");
+  dictionary
+    .import_terms(
+      DictionaryCode::Publisher,
+      vec!["Routledge".to_string()]
+    )
+    .expect("import publishers");
+  let normalizer =
+    LocationNormalizer::new();
+
+  let parsed = normalizer.parse(
+    "Berkeley, Los Angeles, London",
+    Some(&dictionary)
+  );
+  assert_eq!(
+    parsed.places,
+    vec![
+      Place::city("Berkeley"),
+      Place::city("Los Angeles"),
+      Place::city("London")
+    ],
+    "dictionary places are not \
+     countries"
+  );
+
+  let parsed = normalizer.parse(
+    "Cambridge, Oxford",
+    Some(&dictionary)
+  );
+  assert_eq!(parsed.places, vec![
+    Place::city("Cambridge"),
+    Place::city("Oxford")
+  ]);
+
+  let parsed = normalizer.parse(
+    "New York and London",
+    Some(&dictionary)
+  );
+  assert_eq!(parsed.places, vec![
+    Place::city("New York"),
+    Place::city("London")
+  ]);
+
+  let parsed = normalizer.parse(
+    "Oxford, England",
+    Some(&dictionary)
+  );
+  assert_eq!(parsed.places, vec![
+    Place {
+      city:    "Oxford".into(),
+      region:  None,
+      country: Some("England".into())
+    }
+  ]);
+
+  let parsed = normalizer.parse(
+    "London, Routledge",
+    Some(&dictionary)
+  );
+  assert_eq!(parsed.places, vec![
+    Place::city("London")
+  ]);
+  assert_eq!(
+    parsed.publisher.as_deref(),
+    Some("Routledge")
+  );
+}
+
 #[test]
 fn container_normalizer_strips_prefixes()
  {
@@ -535,3 +714,345 @@ fn normalization_any_assets_are_populated()
      populated"
   );
 }
+
+#[test]
+fn title_normalizer_sentence_cases_shouting_titles()
+ {
+  let protected =
+    ProtectedWords::load_from_file(
+      std::path::Path::new(
+        "tests/fixtures/\
+         protected-words-sample.txt"
+      )
+    )
+    .expect("protected words");
+  let normalizer =
+    TitleNormalizer::new()
+      .with_case(TitleCase::Sentence)
+      .with_protected_words(protected);
+
+  assert_eq!(
+    normalizer.normalize(
+      "ESSAYS ON HORIZONTAL MERGERS \
+       AND ANTITRUST"
+    ),
+    "Essays on horizontal mergers and \
+     Antitrust"
+  );
+  assert_eq!(
+    normalizer.normalize(
+      "Measuring CO2 uptake in New \
+       York: A Bayesian DNA study"
+    ),
+    "Measuring CO2 uptake in New \
+     York: A Bayesian DNA study"
+  );
+}
+
+#[test]
+fn title_normalizer_title_cases_minor_words()
+ {
+  let normalizer =
+    TitleNormalizer::new()
+      .with_case(TitleCase::Title);
+
+  assert_eq!(
+    normalizer.normalize(
+      "the economics of H2O pricing \
+       in the EU"
+    ),
+    "The Economics of H2O Pricing in \
+     the EU"
+  );
+  assert_eq!(
+    normalizer.normalize(
+      "WORLD WAR II AND THE STATE"
+    ),
+    "World War II and the State"
+  );
+}
+
+#[test]
+fn title_normalizer_leaves_titles_as_is_by_default()
+ {
+  let normalizer =
+    TitleNormalizer::new();
+  assert_eq!(
+    normalizer
+      .normalize("ESSAYS ON MERGERS"),
+    "ESSAYS ON MERGERS"
+  );
+  assert!(!normalizer.is_active());
+}
+
+#[test]
+fn title_normalizer_braces_protected_words_for_bibtex()
+ {
+  let normalizer =
+    TitleNormalizer::new()
+      .with_protected_words(
+        ProtectedWords::load_from_str(
+          "Bayesian"
+        )
+      );
+
+  assert_eq!(
+    normalizer.protect_for_bibtex(
+      "A bayesian view of DNA and CO2."
+    ),
+    "A {Bayesian} view of {DNA} and \
+     {CO2}."
+  );
+}
+
+#[test]
+fn normalization_config_recases_titles()
+{
+  let config =
+    NormalizationConfig::default()
+      .with_title_case(
+        TitleCase::Sentence
+      );
+
+  let mut map = Map::new();
+  map.insert(
+    "title".into(),
+    Value::Array(vec![Value::String(
+      "ESSAYS ON HORIZONTAL MERGERS"
+        .into()
+    )])
+  );
+  config.apply_to_map(&mut map);
+
+  let title = map
+    .get("title")
+    .and_then(Value::as_array)
+    .and_then(|array| array.first())
+    .and_then(Value::as_str);
+  assert_eq!(
+    title,
+    Some(
+      "Essays on horizontal mergers"
+    )
+  );
+}
+
+#[test]
+fn publisher_normalizer_collapses_variants()
+ {
+  let normalizer =
+    PublisherNormalizer::new();
+
+  for variant in [
+    "Oxford Univ. Press",
+    "OUP",
+    "Oxford University Press, Inc.",
+    "The Oxford University Press"
+  ] {
+    assert_eq!(
+      normalizer.normalize(variant),
+      "Oxford University Press",
+      "variant {variant} should \
+       collapse"
+    );
+  }
+  assert_eq!(
+    normalizer.normalize(
+      "Harper & Row, Publishers"
+    ),
+    "Harper & Row"
+  );
+  assert_eq!(
+    normalizer
+      .normalize("Macmillan & Co."),
+    "Macmillan"
+  );
+  assert_eq!(
+    normalizer.normalize(
+      "Sage Publications Ltd."
+    ),
+    "Sage Publications"
+  );
+  assert_eq!(
+    normalizer.normalize("Publishers"),
+    "Publishers",
+    "suffix stripping must leave a \
+     name"
+  );
+}
+
+#[test]
+fn publisher_normalizer_maps_imprints_on_request()
+ {
+  let normalizer =
+    PublisherNormalizer::new();
+  assert_eq!(
+    normalizer
+      .normalize("Clarendon Press"),
+    "Clarendon Press"
+  );
+
+  let normalizer = normalizer
+    .with_imprint_parents(true);
+  assert_eq!(
+    normalizer
+      .normalize("Clarendon Press"),
+    "Oxford University Press"
+  );
+}
+
+#[test]
+fn publisher_normalizer_clusters_with_loaded_table()
+ {
+  let text = fs::read_to_string(
+    "tests/fixtures/\
+     publisher-canonical-sample.txt"
+  )
+  .expect("publisher table fixture");
+  let table =
+    PublisherTable::load_from_str(
+      &text
+    );
+  assert_eq!(table.len(), 2);
+
+  let normalizer =
+    PublisherNormalizer::new()
+      .with_canonical_names(table);
+  let clusters = normalizer.cluster([
+    "Oxf. UP",
+    "OUP",
+    "Oxford Univ. Press",
+    "OUP",
+    "Blackwell"
+  ]);
+
+  assert_eq!(clusters.len(), 2);
+  assert_eq!(
+    clusters["Oxford University Press"],
+    vec![
+      "Oxf. UP",
+      "OUP",
+      "Oxford Univ. Press"
+    ]
+  );
+  assert_eq!(clusters["Wiley"], vec![
+    "Blackwell"
+  ]);
+}
+
+#[test]
+fn normalization_config_canonicalizes_publishers()
+ {
+  let mut map = Map::new();
+  map.insert(
+    "publisher".into(),
+    Value::Array(vec![Value::String(
+      "Oxford Univ. Press".into()
+    )])
+  );
+
+  NormalizationConfig::default()
+    .apply_to_map(&mut map);
+  assert_eq!(
+    map["publisher"][0],
+    "Oxford Univ. Press",
+    "canonical names are opt-in"
+  );
+
+  NormalizationConfig::default()
+    .with_canonical_publishers(true)
+    .apply_to_map(&mut map);
+  assert_eq!(
+    map["publisher"][0],
+    "Oxford University Press"
+  );
+}
+
+#[test]
+fn volume_normalizer_marks_supplements_and_combined_issues()
+ {
+  let normalizer =
+    VolumeNormalizer::new();
+
+  let issue =
+    normalizer.parse_issue("Suppl 2");
+  assert!(issue.is_supplement());
+  assert_eq!(issue.label(), "Suppl. 2");
+
+  let issue =
+    normalizer.parse_issue("3/4");
+  assert!(issue.is_combined());
+  assert_eq!(issue.numbers, vec![
+    "3", "4"
+  ]);
+  assert_eq!(issue.label(), "3-4");
+
+  assert_eq!(
+    normalizer
+      .normalize_issue("7\u{2013}8"),
+    "7-8"
+  );
+  assert_eq!(
+    normalizer
+      .normalize_issue("Special Issue"),
+    "Special Issue"
+  );
+  assert_eq!(
+    normalizer
+      .normalize_issue("134, Part 3"),
+    "134, Part 3"
+  );
+}
+
+#[test]
+fn volume_normalizer_converts_roman_numerals_on_request()
+ {
+  let normalizer =
+    VolumeNormalizer::new();
+  assert_eq!(
+    normalizer.normalize_volume("XII"),
+    "XII"
+  );
+
+  let normalizer = normalizer
+    .with_arabic_numerals(true);
+  assert_eq!(
+    normalizer.normalize_volume("XII"),
+    "12"
+  );
+  assert_eq!(
+    normalizer
+      .normalize_volume("Vol. iv"),
+    "4"
+  );
+  assert_eq!(
+    normalizer.normalize_volume("41"),
+    "41"
+  );
+}
+
+#[test]
+fn volume_keywords_cover_german_french_and_spanish()
+ {
+  assert_eq!(
+    keyword("Bd."),
+    Some((Marker::Volume, "de"))
+  );
+  assert_eq!(
+    keyword("H."),
+    Some((Marker::Issue, "de"))
+  );
+  assert_eq!(
+    keyword("t."),
+    Some((Marker::Volume, "fr"))
+  );
+  assert_eq!(
+    keyword("n°"),
+    Some((Marker::Issue, "fr"))
+  );
+  assert_eq!(
+    keyword("núm."),
+    Some((Marker::Issue, "es"))
+  );
+  assert_eq!(keyword("pp."), None);
+}
@@ -6,7 +6,8 @@ use std::collections::{
 use cite_otter::dictionary::{
   Dictionary,
   DictionaryAdapter,
-  DictionaryCode
+  DictionaryCode,
+  LayeredDictionary
 };
 use cite_otter::format::ParseFormat;
 use std::fs;
@@ -17,7 +18,11 @@ use cite_otter::normalizer::{
 use cite_otter::parser::{
   Author,
   FieldValue,
-  Parser
+  InputNormalizer,
+  LineJoiner,
+  Parser,
+  Span,
+  UnicodeForm
 };
 
 const PREPARED_LINES: [&str; 2] = [
@@ -85,6 +90,49 @@ const MONTH_RANGE_LONG_REF: &str =
    The Harvill Press, Sept. 12–14, \
    2010.";
 
+const OCR_ARTIFACT_REF: &str =
+  "Doe, J. \u{201C}E\u{FB03}cient \
+   Re\u{00AD}search\u{201D}. Journal \
+   of Tests 12:\u{00A0}100\u{2013}110, \
+   2001.";
+const ROMAN_VOLUME_REF: &str =
+  "Doe, J. A title. Journal of Tests, \
+   Vol. XII, no. 3, 2001, pp. 1-10.";
+const SUPPLEMENT_REF: &str =
+  "Doe, J. A title. Journal of Tests \
+   41(Suppl 2): 1-10, 2001.";
+const COMBINED_ISSUE_REF: &str =
+  "Doe, J. A title. Journal of Tests \
+   12(3/4): 1-10, 2001.";
+const ISSUE_RANGE_REF: &str =
+  "Doe, J. A title. Journal of Tests, \
+   vol. 12, no. 7\u{2013}8, 2001, pp. \
+   1-10.";
+const GERMAN_VOLUME_REF: &str =
+  "M\u{00FC}ller, K. Ein Titel. \
+   Zeitschrift f\u{00FC}r Tests, Bd. \
+   5, H. 2, 2001, S. 1-10.";
+const FRENCH_VOLUME_REF: &str =
+  "Dupont, J. Un titre. Revue des \
+   tests, t. 5, n\u{00B0} 2, 2001, p. \
+   1-10.";
+const SPANISH_VOLUME_REF: &str =
+  "Garc\u{00ED}a, J. Un \
+   t\u{00ED}tulo. Revista de pruebas, \
+   vol. 5, n\u{00FA}m. 2, 2001, pp. \
+   1-10.";
+const INITIALS_REF: &str =
+  "Brown, T. L. and Smith, H. D. A \
+   title. Journal of Stuff, 12(3), \
+   45-67, 2001.";
+const MULTI_PLACE_REF: &str =
+  "Doe, J. A Title. Oxford & New \
+   York: Oxford University Press, \
+   2001.";
+const REGION_PLACE_REF: &str =
+  "Doe, J. A Title. Cambridge, Mass.: \
+   Harvard University Press, 2001.";
+
 const MULTI_AUTHOR_REF: &str =
   "Doe, J. and Smith, A. A Title. \
    City: Pub, 2020.";
@@ -570,6 +618,164 @@ fn parse_uses_dictionary_for_type_resolution()
   }
 }
 
+#[test]
+fn parse_uses_imported_keyword_terms() {
+  let mut dictionary =
+    Dictionary::create(
+      DictionaryAdapter::Memory
+    )
+    .open();
+  dictionary
+    .import_weighted(vec![
+      (
+        "outubro".to_string(),
+        DictionaryCode::Month,
+        10.0
+      ),
+      (
+        "red.".to_string(),
+        DictionaryCode::Editor,
+        1.0
+      ),
+    ])
+    .expect("keyword import");
+  let parser =
+    Parser::with_dictionary(dictionary);
+
+  let references = parser.parse(
+    &["Nowak, A. Historia Polski. \
+       red. Piotr Kowalski, \
+       Warszawa, outubro 2001."],
+    ParseFormat::Json
+  );
+  let reference = &references[0].0;
+  let list = |field: &str| {
+    match reference.get(field) {
+      | Some(FieldValue::List(
+        values
+      )) => values.clone(),
+      | other => {
+        panic!(
+          "expected {field} list, got \
+           {other:?}"
+        )
+      }
+    }
+  };
+  assert_eq!(list("editor"), vec![
+    "Piotr Kowalski"
+  ]);
+  assert_eq!(list("date"), vec![
+    "2001", "10"
+  ]);
+}
+
+#[test]
+fn parse_honours_layered_dictionary_deny_list()
+ {
+  let mut base = Dictionary::create(
+    DictionaryAdapter::Memory
+  )
+  .open();
+  base
+    .import_terms(
+      DictionaryCode::Journal,
+      vec!["Nature".to_string()]
+    )
+    .expect("dictionary import");
+  let reference =
+    ["Doe, J. Nature. 2020."];
+  let type_of = |parser: Parser| {
+    match parser.parse(
+      &reference,
+      ParseFormat::Json
+    )[0]
+      .0
+      .get("type")
+    {
+      | Some(FieldValue::Single(
+        value
+      )) => value.clone(),
+      | other => {
+        panic!(
+          "expected single type \
+           value, got {other:?}"
+        )
+      }
+    }
+  };
+
+  let layered =
+    LayeredDictionary::new(base)
+      .with_denied("Nature", &[
+        DictionaryCode::Journal
+      ]);
+  assert_ne!(
+    type_of(Parser::with_dictionary(
+      layered
+    )),
+    "article",
+    "denied journal codes are ignored"
+  );
+}
+
+#[test]
+fn parse_reads_keyword_terms_from_the_dictionary()
+ {
+  let reference =
+    ["Doe, J. A hypothesis. Journal \
+      of Tests, May 2001."];
+  let parse = |parser: Parser| {
+    parser
+      .parse(
+        &reference,
+        ParseFormat::Json
+      )
+      .remove(0)
+      .0
+  };
+  let base = || {
+    Dictionary::create(
+      DictionaryAdapter::Memory
+    )
+    .open()
+  };
+
+  let parsed = parse(
+    Parser::with_dictionary(base())
+  );
+  assert!(matches!(
+    parsed.get("date"),
+    Some(FieldValue::List(values))
+      if values == &["2001", "05"]
+  ));
+  assert!(
+    !matches!(
+      parsed.get("type"),
+      Some(FieldValue::Single(value))
+        if value == "thesis"
+    ),
+    "genre terms are whole words"
+  );
+
+  let layered =
+    LayeredDictionary::new(base())
+      .with_denied("May", &[
+        DictionaryCode::Month
+      ]);
+  let parsed = parse(
+    Parser::with_dictionary(layered)
+  );
+  assert!(
+    matches!(
+      parsed.get("date"),
+      Some(FieldValue::List(values))
+        if values == &["2001"]
+    ),
+    "a denied month name is not read"
+  );
+}
+
 #[test]
 fn parse_applies_normalization_to_publisher()
  {
@@ -1365,6 +1571,240 @@ fn parse_captures_chapter_container_and_editors()
   }
 }
 
+#[test]
+fn input_normalizer_cleans_ocr_artifacts()
+ {
+  let normalizer =
+    InputNormalizer::new();
+  let input = concat!(
+    "\u{201C}E\u{FB03}cient\u{201D} ",
+    "\u{2018}Title\u{2019} ",
+    "pp.\u{00A0}12\u{2013}1\u{200B}9 ",
+    "re\u{00AD}use ",
+    "Cafe\u{0301}\u{0007}"
+  );
+
+  let normalized =
+    normalizer.normalize(input);
+  assert_eq!(
+    normalized.as_str(),
+    "\"Efficient\" 'Title' pp. 12-19 \
+     reuse Caf\u{00E9}"
+  );
+
+  // Apostrophes keep their form; only
+  // quotes opened by a left one close.
+  assert_eq!(
+    normalizer
+      .normalize(
+        "L\u{2019}\u{00E9}criture, \
+         \u{2018}Tom\u{2019}s \
+         book\u{2019}, the \
+         1990\u{2019}s"
+      )
+      .as_str(),
+    "L\u{2019}\u{00E9}criture, \
+     'Tom\u{2019}s book', the \
+     1990\u{2019}s"
+  );
+
+  let raw = InputNormalizer::disabled()
+    .normalize(input);
+  assert_eq!(raw.as_str(), input);
+
+  let compat = InputNormalizer::new()
+    .with_form(UnicodeForm::Nfkc)
+    .normalize("x\u{00B2}");
+  assert_eq!(compat.as_str(), "x2");
+}
+
+#[test]
+fn input_normalizer_maps_offsets_to_original()
+ {
+  let input = "\u{FB01}rst \u{2013} 12";
+  let normalized =
+    InputNormalizer::new()
+      .normalize(input);
+  let text = normalized.as_str();
+  assert_eq!(text, "first - 12");
+
+  // "fi" both come from the three-byte
+  // ligature at the start.
+  assert_eq!(
+    normalized.original_range(0..2),
+    0..3
+  );
+  let dash = text.find('-').unwrap();
+  assert_eq!(
+    &input[normalized
+      .original_range(dash..dash + 1)],
+    "\u{2013}"
+  );
+  let digits = text.find("12").unwrap();
+  assert_eq!(
+    &input[normalized.original_range(
+      digits..text.len()
+    )],
+    "12"
+  );
+  assert_eq!(
+    normalized
+      .original_offset(text.len()),
+    input.len()
+  );
+}
+
+#[test]
+fn parse_reads_pages_through_ocr_artifacts()
+ {
+  let parser = Parser::new();
+  let references = parser.parse(
+    &[OCR_ARTIFACT_REF],
+    ParseFormat::Json
+  );
+
+  let reference = &references[0].0;
+  assert_list_field(
+    reference, "pages", "100-110"
+  );
+  match reference.get("__source") {
+    | Some(FieldValue::List(
+      values
+    )) => {
+      assert_eq!(
+        values[0], OCR_ARTIFACT_REF,
+        "the source keeps the \
+         original text"
+      );
+    }
+    | other => {
+      panic!(
+        "expected source, got \
+         {other:?}"
+      )
+    }
+  }
+}
+
+#[test]
+fn parse_normalizes_volume_and_issue_forms()
+ {
+  let parser = Parser::new();
+  for (reference, volume, issue) in [
+    (ROMAN_VOLUME_REF, "XII", "3"),
+    (SUPPLEMENT_REF, "41", "Suppl. 2"),
+    (COMBINED_ISSUE_REF, "12", "3-4"),
+    (ISSUE_RANGE_REF, "12", "7-8"),
+    (GERMAN_VOLUME_REF, "5", "2"),
+    (FRENCH_VOLUME_REF, "5", "2"),
+    (SPANISH_VOLUME_REF, "5", "2")
+  ] {
+    let references = parser.parse(
+      &[reference],
+      ParseFormat::Json
+    );
+    let parsed = &references[0].0;
+    assert_list_field(
+      parsed, "volume", volume
+    );
+    assert_list_field(
+      parsed, "issue", issue
+    );
+  }
+
+  let parser =
+    Parser::with_normalization(
+      NormalizationConfig::default()
+        .with_arabic_volumes(true)
+    );
+  let references = parser.parse(
+    &[ROMAN_VOLUME_REF],
+    ParseFormat::Json
+  );
+  assert_list_field(
+    &references[0].0,
+    "volume",
+    "12"
+  );
+}
+
+#[test]
+fn parse_ignores_initials_that_look_like_volume_keywords()
+ {
+  let parser = Parser::new();
+  let references = parser.parse(
+    &[INITIALS_REF],
+    ParseFormat::Json
+  );
+  let parsed = &references[0].0;
+  assert_list_field(
+    parsed, "volume", "12"
+  );
+  assert_list_field(
+    parsed, "issue", "3"
+  );
+}
+
+#[test]
+fn parse_splits_publisher_places() {
+  let parser =
+    Parser::with_normalization(
+      NormalizationConfig::default()
+        .with_structured_locations(
+          true
+        )
+    );
+  let references = parser.parse(
+    &[MULTI_PLACE_REF],
+    ParseFormat::Json
+  );
+
+  let reference = &references[0].0;
+  assert_list_field(
+    reference,
+    "publisher-place",
+    "Oxford"
+  );
+  assert_list_field(
+    reference,
+    "publisher-place",
+    "New York"
+  );
+
+  let references = parser.parse(
+    &[REGION_PLACE_REF],
+    ParseFormat::Json
+  );
+  let reference = &references[0].0;
+  assert_list_field(
+    reference,
+    "publisher-place",
+    "Cambridge, MA"
+  );
+  match reference
+    .get("structured-location")
+  {
+    | Some(FieldValue::Places(
+      places
+    )) => {
+      assert_eq!(
+        places[0].region.as_deref(),
+        Some("MA")
+      );
+      assert_eq!(
+        places[0].country.as_deref(),
+        Some("United States")
+      );
+    }
+    | other => {
+      panic!(
+        "expected structured \
+         location, got {other:?}"
+      )
+    }
+  }
+}
+
 #[test]
 fn parse_captures_volume_with_parts() {
   let parser = Parser::new();
@@ -1772,6 +2212,65 @@ fn label_handles_unrecognizable_input()
   parser.label("\n doi ");
 }
 
+#[test]
+fn line_joiner_rejoins_wrapped_references()
+ {
+  let input =
+    "Smith, J. (2020). A study of \
+     comput-\n    ational methods in \
+     self-\n    esteem research. \
+     Journal of Studies,\n    12(3), \
+     45-\n    67.\nDoe, A. (2019). \
+     Another\nstudy of computational \
+     things. Berlin.\n[3] Roe, B. \
+     2018. Third.";
+  let references =
+    LineJoiner::new().join(input);
+  assert_eq!(references, vec![
+    "Smith, J. (2020). A study of \
+     computational methods in \
+     self-esteem research. Journal of \
+     Studies, 12(3), 45-67."
+      .to_string(),
+    "Doe, A. (2019). Another study of \
+     computational things. Berlin."
+      .to_string(),
+    "[3] Roe, B. 2018. Third."
+      .to_string()
+  ]);
+}
+
+#[test]
+fn parser_joins_lines_when_asked() {
+  let input = "Doe, A. (2019). A \
+               study of\nthings. \
+               Berlin: Springer.\nRoe, \
+               B. (2018). Other \
+               things. Nature.";
+  let split = Parser::new()
+    .parse(&[input], ParseFormat::Json);
+  assert_eq!(split.len(), 1);
+
+  let parser =
+    Parser::new().with_line_joiner(
+      LineJoiner::new()
+    );
+  let joined = parser
+    .parse(&[input], ParseFormat::Json);
+  assert_eq!(joined.len(), 2);
+  assert_eq!(
+    parser
+      .prepare(input, false)
+      .0
+      .len(),
+    2
+  );
+  assert_eq!(
+    parser.label(input).len(),
+    2
+  );
+}
+
 fn assert_list_field(
   reference: &BTreeMap<
     String,
@@ -1800,3 +2299,56 @@ fn assert_list_field(
     }
   }
 }
+
+#[test]
+fn parse_records_source_spans_of_field_values()
+ {
+  let input =
+    "Doe, A. (2019). A study of\n  \
+     things. Berlin: Springer.\nRoe, \
+     B. (2018). Other things. Nature.";
+  let parser =
+    Parser::new().with_line_joiner(
+      LineJoiner::new()
+    );
+  let references = parser
+    .parse(&[input], ParseFormat::Json);
+  let text = |span: &Span| {
+    &input[span.start..span.end]
+  };
+
+  let second = references[1].spans();
+  assert!(
+    input[second.start..second.end]
+      .starts_with("Roe, B. (2018)")
+  );
+  assert_eq!(
+    second.fields["date"][0]
+      .iter()
+      .map(text)
+      .collect::<Vec<_>>(),
+    vec!["(2018)."]
+  );
+
+  // The title wraps onto the next line.
+  let first = references[0].spans();
+  let title = first.fields["title"][0]
+    .iter()
+    .map(text)
+    .collect::<Vec<_>>();
+  assert_eq!(title.first(), Some(&"A"));
+  assert_eq!(
+    title.last(),
+    Some(&"things.")
+  );
+  assert_eq!(
+    first.fields["author"][0]
+      .iter()
+      .map(text)
+      .collect::<Vec<_>>(),
+    vec!["Doe,", "A."]
+  );
+  assert!(
+    !first.fields.contains_key("type")
+  );
+}